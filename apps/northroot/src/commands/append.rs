@@ -2,7 +2,10 @@
 
 use crate::path;
 use northroot_canonical::{compute_event_id, Canonicalizer, ProfileId};
-use northroot_journal::{JournalWriter, WriteOptions};
+use northroot_journal::{
+    DuplicatePolicy, Durability, JournalWriter, LockPolicy, SidecarIndex, ValidationPolicy,
+    WriteOptions,
+};
 use serde_json::Value;
 use std::io::{self, Read};
 
@@ -11,6 +14,8 @@ pub fn run(
     input: Option<String>,
     strict: bool,
     sync: bool,
+    checksums: bool,
+    idempotent: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Validate journal path (allow non-existent files for creation)
     let journal_path = if std::path::Path::new(&journal).exists() {
@@ -19,12 +24,12 @@ pub fn run(
     } else {
         // For new files, validate the parent directory exists and path is safe
         let path = std::path::Path::new(&journal);
-        
+
         // Extract filename - must exist and be non-empty
         let filename = path
             .file_name()
             .ok_or_else(|| format!("Invalid journal path: no filename: {}", journal))?;
-        
+
         // Get parent directory (empty string means current dir)
         let parent = path.parent().unwrap_or(std::path::Path::new(""));
         let parent = if parent.as_os_str().is_empty() {
@@ -32,7 +37,7 @@ pub fn run(
         } else {
             parent
         };
-        
+
         // Canonicalize parent directory to resolve all traversal sequences
         let parent_canonical = if parent.is_absolute() {
             parent
@@ -44,7 +49,7 @@ pub fn run(
                 .canonicalize()
                 .map_err(|e| format!("Invalid journal path: {}: {}", journal, e))?
         };
-        
+
         // Construct final path from canonicalized parent + filename
         // This ensures traversal sequences in the original path are eliminated
         parent_canonical.join(filename)
@@ -60,8 +65,8 @@ pub fn run(
         buffer
     };
 
-    let mut event: Value = serde_json::from_str(&json_str)
-        .map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut event: Value =
+        serde_json::from_str(&json_str).map_err(|e| format!("Invalid JSON: {}", e))?;
 
     // Initialize canonicalizer
     let profile = ProfileId::parse("northroot-canonical-v1")
@@ -74,10 +79,10 @@ pub fn run(
             // Compute event_id and compare
             let computed_id = compute_event_id(&event, &canonicalizer)
                 .map_err(|e| format!("Event ID computation failed: {}", e))?;
-            
+
             let existing_id_str = serde_json::to_string(existing_id)?;
             let computed_id_str = serde_json::to_string(&computed_id)?;
-            
+
             if existing_id_str != computed_id_str {
                 return Err(format!(
                     "Event ID mismatch: computed {} but event has {}",
@@ -97,11 +102,27 @@ pub fn run(
     event["event_id"] = serde_json::to_value(&event_id)?;
 
     // Open journal for writing
-    let write_options = WriteOptions {
-        sync,
-        create: true,
-        append: true,
-    };
+    let write_options = WriteOptions::default()
+        .with_durability(if sync {
+            Durability::Full
+        } else {
+            Durability::None
+        })
+        .with_checksums(checksums)
+        // Concurrent appends take turns instead of failing
+        .with_lock(LockPolicy::Wait)
+        // event_id was computed above; strict mode already checked the original
+        .with_validation(ValidationPolicy::default())
+        // A retried append finds the event already written and leaves it be.
+        // The writer holds the lock, so the event cannot be appended in the
+        // meantime.
+        .with_duplicates(if idempotent {
+            DuplicatePolicy::Skip
+        } else {
+            DuplicatePolicy::Append
+        })
+        // Keep an existing sidecar current so `list` and `get` need not catch up
+        .with_sidecar(SidecarIndex::path_for(&journal_path).exists());
 
     let mut writer = JournalWriter::open(&journal_path, write_options).map_err(|e| {
         let sanitized = path::sanitize_path_for_error(&journal_path);
@@ -109,10 +130,16 @@ pub fn run(
     })?;
 
     // Append event
-    writer.append_event(&event).map_err(|e| {
+    let receipt = writer.append_event(&event).map_err(|e| {
         let sanitized = path::sanitize_path_for_error(&journal_path);
         format!("Failed to append event to journal: {}: {}", sanitized, e)
     })?;
+    if receipt.duplicate {
+        eprintln!(
+            "Event {} is already in the journal (offset {}); not appended",
+            event_id.b64, receipt.offset
+        );
+    }
 
     // Finish writing (closes file)
     writer.finish().map_err(|e| {
@@ -170,11 +197,14 @@ mod tests {
             Some(event_file.to_str().unwrap().to_string()),
             false,
             false,
+            false,
+            false,
         );
         assert!(result.is_ok(), "Append failed: {:?}", result.err());
 
         // Verify event was appended
-        let mut reader = JournalReader::open(&journal_path, northroot_journal::ReadMode::Strict).unwrap();
+        let mut reader =
+            JournalReader::open(&journal_path, northroot_journal::ReadMode::Strict).unwrap();
         let read_event = reader.read_event().unwrap().unwrap();
         assert_eq!(read_event["event_type"], "test");
         assert!(read_event.get("event_id").is_some());
@@ -226,7 +256,10 @@ mod tests {
             Some(event_file1.to_str().unwrap().to_string()),
             false,
             false,
-        ).unwrap();
+            false,
+            false,
+        )
+        .unwrap();
 
         // Append second event
         let event_file2 = temp.path().join("event2.json");
@@ -236,10 +269,14 @@ mod tests {
             Some(event_file2.to_str().unwrap().to_string()),
             false,
             false,
-        ).unwrap();
+            false,
+            false,
+        )
+        .unwrap();
 
         // Verify both events
-        let mut reader = JournalReader::open(&journal_path, northroot_journal::ReadMode::Strict).unwrap();
+        let mut reader =
+            JournalReader::open(&journal_path, northroot_journal::ReadMode::Strict).unwrap();
         let read_event1 = reader.read_event().unwrap().unwrap();
         assert_eq!(read_event1["event_type"], "test1");
 
@@ -247,6 +284,48 @@ mod tests {
         assert_eq!(read_event2["event_type"], "test2");
     }
 
+    #[test]
+    fn test_append_idempotent_skips_repeated_event() {
+        let temp = TempDir::new().unwrap();
+        let journal_path = temp.path().join("test.nrj");
+        let journal_str = journal_path.to_str().unwrap();
+
+        let event = json!({
+            "event_type": "test",
+            "event_version": "1",
+            "occurred_at": "2024-01-01T00:00:00Z",
+            "principal_id": "service:test",
+            "canonical_profile_id": "northroot-canonical-v1"
+        });
+        let event_file = temp.path().join("event.json");
+        fs::write(&event_file, serde_json::to_string(&event).unwrap()).unwrap();
+        let append = |idempotent| {
+            run(
+                journal_str.to_string(),
+                Some(event_file.to_str().unwrap().to_string()),
+                false,
+                false,
+                false,
+                idempotent,
+            )
+            .unwrap()
+        };
+
+        // A retried append leaves the journal as it was
+        append(true);
+        append(true);
+        let count = |path| {
+            let mut reader =
+                JournalReader::open(path, northroot_journal::ReadMode::Strict).unwrap();
+            std::iter::from_fn(|| reader.read_event().unwrap()).count()
+        };
+        assert_eq!(count(&journal_path), 1);
+
+        // Without --idempotent the event is written again
+        append(false);
+        assert_eq!(count(&journal_path), 2);
+    }
+
     #[test]
     fn test_append_invalid_json() {
         let original_dir = std::env::current_dir().unwrap();
@@ -277,6 +356,8 @@ mod tests {
             Some(invalid_file.to_str().unwrap().to_string()),
             false,
             false,
+            false,
+            false,
         );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
@@ -322,9 +403,14 @@ mod tests {
             Some(event_file.to_str().unwrap().to_string()),
             true, // strict mode
             false,
+            false,
+            false,
         );
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Event ID mismatch"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Event ID mismatch"));
     }
 
     #[test]
@@ -353,6 +439,8 @@ mod tests {
             Some(event_file.to_str().unwrap().to_string()),
             false,
             false,
+            false,
+            false,
         );
         assert!(result.is_ok(), "Append failed: {:?}", result.err());
 
@@ -374,4 +462,3 @@ mod tests {
         );
     }
 }
-
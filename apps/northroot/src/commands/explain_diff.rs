@@ -0,0 +1,101 @@
+//! Explain-diff command implementation.
+
+use northroot_canonical::{explain_diff, Canonicalizer, DifferenceKind, ProfileId};
+use serde_json::Value;
+
+pub fn run(a: String, b: String, json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let profile = ProfileId::parse("northroot-canonical-v1")
+        .map_err(|e| format!("Invalid profile ID: {}", e))?;
+    let canonicalizer = Canonicalizer::new(profile);
+
+    let left = read_json(&a)?;
+    let right = read_json(&b)?;
+
+    let diff = explain_diff(&left, &right, &canonicalizer)
+        .map_err(|e| format!("Canonicalization failed: {}", e))?;
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    println!("event_id a: {}", diff.event_id_a.b64);
+    println!("event_id b: {}", diff.event_id_b.b64);
+    if diff.differences.is_empty() {
+        println!("No structural differences");
+        return Ok(());
+    }
+    println!();
+    println!("{:<40} {:<24} DETAIL", "POINTER", "KIND");
+    println!("{}", "-".repeat(100));
+    for difference in &diff.differences {
+        let (kind, detail) = describe(&difference.kind);
+        println!("{:<40} {:<24} {}", difference.pointer, kind, detail);
+    }
+
+    Ok(())
+}
+
+fn read_json(path: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let json_str = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    let value = serde_json::from_str(&json_str)
+        .map_err(|e| format!("Invalid JSON in {}: {}", path, e))?;
+    Ok(value)
+}
+
+fn describe(kind: &DifferenceKind) -> (&'static str, String) {
+    match kind {
+        DifferenceKind::Missing { a } => ("missing", format!("only in a: {}", a)),
+        DifferenceKind::Extra { b } => ("extra", format!("only in b: {}", b)),
+        DifferenceKind::TypeChanged { a_type, b_type } => {
+            ("type_changed", format!("{} -> {}", a_type, b_type))
+        }
+        DifferenceKind::UnicodeNormalization { a, b } => (
+            "unicode_normalization",
+            format!("{:?} -> {:?} (equal under NFC)", a, b),
+        ),
+        DifferenceKind::NumberFormat { a, b } => ("number_format", format!("{} -> {}", a, b)),
+        DifferenceKind::ValueChanged { a, b } => ("value_changed", format!("{} -> {}", a, b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_explain_diff_reads_both_files() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("a.json");
+        let b = temp.path().join("b.json");
+        fs::write(&a, json!({"event_type": "test", "amount": 1}).to_string()).unwrap();
+        fs::write(&b, json!({"event_type": "test", "amount": "1"}).to_string()).unwrap();
+
+        let result = run(
+            a.to_str().unwrap().to_string(),
+            b.to_str().unwrap().to_string(),
+            true,
+        );
+        assert!(result.is_ok(), "explain-diff failed: {:?}", result.err());
+    }
+
+    #[test]
+    fn test_explain_diff_rejects_invalid_json() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("a.json");
+        let b = temp.path().join("b.json");
+        fs::write(&a, "{}").unwrap();
+        fs::write(&b, "{ invalid").unwrap();
+
+        let result = run(
+            a.to_str().unwrap().to_string(),
+            b.to_str().unwrap().to_string(),
+            false,
+        );
+        assert!(result.unwrap_err().to_string().contains("Invalid JSON"));
+    }
+}
@@ -0,0 +1,109 @@
+//! Get command implementation.
+
+use crate::path;
+use northroot_canonical::{Digest, DigestAlg};
+use northroot_journal::{JournalReader, ReadMode, SidecarIndex};
+use serde_json;
+
+pub fn run(
+    journal: String,
+    event_id: String,
+    no_index: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Validate and normalize journal path
+    let journal_path = path::validate_journal_path(&journal, false)
+        .map_err(|e| format!("Invalid journal path: {}", e))?;
+
+    let digest = Digest::new(DigestAlg::Sha256, event_id.as_str())
+        .map_err(|e| format!("Invalid event_id: {}", e))?;
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).map_err(|e| {
+        let sanitized = path::sanitize_path_for_error(&journal_path);
+        format!("Failed to open journal file: {}: {}", sanitized, e)
+    })?;
+
+    let sidecar = if no_index {
+        None
+    } else {
+        SidecarIndex::open(&journal_path)
+            .map_err(|e| {
+                eprintln!(
+                    "Warning: sidecar index unavailable ({}); scanning journal",
+                    e
+                )
+            })
+            .ok()
+    };
+
+    // Look the event up in the sidecar, falling back to an in-journal seek
+    let found = match sidecar {
+        Some(mut sidecar) => match sidecar.find_event(&digest)? {
+            Some(offset) => {
+                reader.seek_offset(offset)?;
+                true
+            }
+            None => false,
+        },
+        None => reader.seek_event(&digest)?,
+    };
+
+    let event = if found { reader.read_event()? } else { None };
+    match event {
+        Some(event) if event.get("event_id") == Some(&serde_json::to_value(&digest)?) => {
+            println!("{}", serde_json::to_string(&event)?);
+            Ok(())
+        }
+        Some(_) => Err(format!("Sidecar index is inconsistent for event_id: {}", event_id).into()),
+        None => Err(format!("Event not found: {}", event_id).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use northroot_journal::{JournalWriter, WriteOptions};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const EVENT_ID: &str = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE";
+
+    fn write_journal(temp: &TempDir) -> String {
+        let journal_path = temp.path().join("test.nrj");
+        let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+        writer
+            .append_event(&json!({
+                "event_id": {"alg": "sha-256", "b64": EVENT_ID},
+                "event_type": "test"
+            }))
+            .unwrap();
+        writer.finish().unwrap();
+        journal_path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_get_finds_event_with_and_without_sidecar() {
+        let temp = TempDir::new().unwrap();
+        let journal = write_journal(&temp);
+
+        let result = run(journal.clone(), EVENT_ID.to_string(), true);
+        assert!(result.is_ok(), "get failed: {:?}", result.err());
+        assert!(!temp.path().join("test.nrx").exists());
+
+        let result = run(journal, EVENT_ID.to_string(), false);
+        assert!(result.is_ok(), "get failed: {:?}", result.err());
+        assert!(temp.path().join("test.nrx").exists());
+    }
+
+    #[test]
+    fn test_get_reports_missing_event() {
+        let temp = TempDir::new().unwrap();
+        let journal = write_journal(&temp);
+
+        let missing = "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI".to_string();
+        let result = run(journal.clone(), missing, false);
+        assert!(result.unwrap_err().to_string().contains("Event not found"));
+
+        let result = run(journal, "not-a-digest".to_string(), false);
+        assert!(result.unwrap_err().to_string().contains("Invalid event_id"));
+    }
+}
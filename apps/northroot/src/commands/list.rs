@@ -2,18 +2,103 @@
 
 use crate::output;
 use crate::path;
-use northroot_journal::{JournalReader, ReadMode};
+use northroot_journal::sidecar::timestamp_key;
+use northroot_journal::{EventJson, JournalReader, ReadMode, SidecarIndex};
 use serde_json;
 
+/// Event filters for `northroot list`.
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    /// Only events with this `event_type`.
+    pub event_type: Option<String>,
+    /// Only events with this `principal_id`.
+    pub principal: Option<String>,
+    /// Only events that occurred at or after this timestamp.
+    pub since: Option<String>,
+    /// Only events that occurred at or before this timestamp.
+    pub until: Option<String>,
+}
+
+impl ListFilter {
+    fn is_empty(&self) -> bool {
+        self.event_type.is_none()
+            && self.principal.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (flag, value) in [("--since", &self.since), ("--until", &self.until)] {
+            if let Some(ts) = value {
+                if timestamp_key(ts).is_none() {
+                    return Err(format!("Invalid {} timestamp: {}", flag, ts));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn matches(&self, event: &EventJson) -> bool {
+        let field = |name: &str| event.get(name).and_then(|v| v.as_str());
+        if let Some(event_type) = &self.event_type {
+            if field("event_type") != Some(event_type.as_str()) {
+                return false;
+            }
+        }
+        if let Some(principal) = &self.principal {
+            if field("principal_id") != Some(principal.as_str()) {
+                return false;
+            }
+        }
+        if self.since.is_some() || self.until.is_some() {
+            let Some(at) = field("occurred_at").and_then(timestamp_key) else {
+                return false;
+            };
+            if self.since.as_deref().and_then(timestamp_key).is_some_and(|since| at < since) {
+                return false;
+            }
+            if self.until.as_deref().and_then(timestamp_key).is_some_and(|until| at > until) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns candidate frame offsets from the sidecar, in journal order.
+    fn candidates(&self, sidecar: &mut SidecarIndex) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+        let mut sets = Vec::new();
+        if let Some(event_type) = &self.event_type {
+            sets.push(sidecar.find_event_type(event_type)?);
+        }
+        if let Some(principal) = &self.principal {
+            sets.push(sidecar.find_principal(principal)?);
+        }
+        if self.since.is_some() || self.until.is_some() {
+            sets.push(sidecar.find_occurred_between(self.since.as_deref(), self.until.as_deref())?);
+        }
+        // Intersect the sorted offset lists, smallest first.
+        sets.sort_by_key(Vec::len);
+        let mut sets = sets.into_iter();
+        let mut offsets = sets.next().unwrap_or_default();
+        for set in sets {
+            offsets.retain(|offset| set.binary_search(offset).is_ok());
+        }
+        Ok(offsets)
+    }
+}
+
 pub fn run(
     journal: String,
     json: bool,
     max_events: Option<u64>,
     max_size: Option<u64>,
+    filter: ListFilter,
+    no_index: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Validate and normalize journal path
     let journal_path = path::validate_journal_path(&journal, false)
         .map_err(|e| format!("Invalid journal path: {}", e))?;
+    filter.validate()?;
 
     // Check journal size if limit is set
     if let Some(max_bytes) = max_size {
@@ -33,13 +118,44 @@ pub fn run(
         format!("Failed to open journal file: {}: {}", sanitized, e)
     })?;
 
+    // Use the sidecar index to jump straight to matching events
+    let offsets = if filter.is_empty() || no_index {
+        None
+    } else {
+        match SidecarIndex::open(&journal_path) {
+            Ok(mut sidecar) => Some(filter.candidates(&mut sidecar)?),
+            Err(e) => {
+                eprintln!("Warning: sidecar index unavailable ({}); scanning journal", e);
+                None
+            }
+        }
+    };
+    let mut offsets = offsets.map(Vec::into_iter);
+
     // Output header if table format
     if !json {
         output::print_table_header();
     }
 
     let mut event_count: u64 = 0;
-    while let Some(event) = reader.read_event()? {
+    loop {
+        let event = match offsets.as_mut() {
+            Some(offsets) => match offsets.next() {
+                Some(offset) => {
+                    reader.seek_offset(offset)?;
+                    reader.read_event()?
+                }
+                None => None,
+            },
+            None => reader.read_event()?,
+        };
+        let Some(event) = event else {
+            break;
+        };
+        if !filter.matches(&event) {
+            continue;
+        }
+
         // Check max_events limit
         if let Some(max) = max_events {
             if event_count >= max {
@@ -57,3 +173,46 @@ pub fn run(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_matches() {
+        let event = json!({
+            "event_type": "authorization",
+            "principal_id": "service:api",
+            "occurred_at": "2024-01-01T00:00:00.5Z"
+        });
+        let filter = ListFilter {
+            event_type: Some("authorization".to_string()),
+            since: Some("2024-01-01T00:00:00Z".to_string()),
+            until: Some("2024-01-01T00:00:01Z".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&event));
+
+        let filter = ListFilter {
+            principal: Some("service:other".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&event));
+
+        let filter = ListFilter {
+            until: Some("2024-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&event));
+    }
+
+    #[test]
+    fn test_filter_rejects_invalid_timestamps() {
+        let filter = ListFilter {
+            since: Some("yesterday".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_err());
+    }
+}
@@ -3,6 +3,9 @@
 pub mod append;
 pub mod canonicalize;
 pub mod event_id;
+pub mod explain_diff;
+pub mod get;
 pub mod list;
+pub mod repair;
 pub mod verify;
 
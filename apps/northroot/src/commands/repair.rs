@@ -0,0 +1,165 @@
+//! Repair command implementation.
+
+use crate::path;
+use northroot_journal::JournalRecovery;
+use serde_json::json;
+
+pub fn run(
+    journal: String,
+    quarantine: Option<String>,
+    truncate: bool,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Validate and normalize journal path
+    let journal_path = path::validate_journal_path(&journal, false)
+        .map_err(|e| format!("Invalid journal path: {}", e))?;
+
+    let recovery = JournalRecovery::scan(&journal_path).map_err(|e| {
+        let sanitized = path::sanitize_path_for_error(&journal_path);
+        format!("Failed to scan journal file: {}: {}", sanitized, e)
+    })?;
+
+    // Damage followed by valid frames is not a torn tail; cutting it off
+    // would discard those frames
+    if let (true, Some(resumes_at)) = (truncate, recovery.resumes_at()) {
+        return Err(format!(
+            "Refusing to truncate: journal is damaged at offset {} but valid frames resume at offset {}",
+            recovery.valid_len(),
+            resumes_at
+        )
+        .into());
+    }
+
+    // Nothing is written unless the tail is damaged and truncation was requested
+    let mut quarantined = None;
+    let repaired = !recovery.is_clean() && truncate;
+    if repaired {
+        if let Some(dest) = quarantine.as_deref() {
+            quarantined = Some(recovery.quarantine(dest)?);
+        }
+        recovery.truncate()?;
+    }
+
+    if json_output {
+        let report = json!({
+            "clean": recovery.is_clean(),
+            "file_len": recovery.file_len(),
+            "valid_len": recovery.valid_len(),
+            "discarded_len": recovery.discarded_len(),
+            "frames": recovery.frames(),
+            "events": recovery.events(),
+            "damage": recovery.damage().map(|e| e.to_string()),
+            "torn_tail": recovery.is_torn_tail(),
+            "resumes_at": recovery.resumes_at(),
+            "quarantined": quarantined,
+            "truncated": repaired,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} valid frames ({} events), {} of {} bytes",
+        recovery.frames(),
+        recovery.events(),
+        recovery.valid_len(),
+        recovery.file_len()
+    );
+    let Some(damage) = recovery.damage() else {
+        println!("Journal is clean; nothing to repair");
+        return Ok(());
+    };
+    if let Some(resumes_at) = recovery.resumes_at() {
+        println!("Damaged frame at offset {}: {}", recovery.valid_len(), damage);
+        println!(
+            "Valid frames resume at offset {}; the journal is corrupted in the middle and cannot be repaired by truncation",
+            resumes_at
+        );
+        return Ok(());
+    }
+    println!("Damaged tail: {}", damage);
+    if let Some(copied) = quarantined {
+        println!("Copied {} bytes to {}", copied, quarantine.unwrap_or_default());
+    }
+    if repaired {
+        println!("Discarded {} bytes", recovery.discarded_len());
+    } else {
+        println!(
+            "Would discard {} bytes; rerun with --truncate to repair",
+            recovery.discarded_len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use northroot_journal::{JournalWriter, WriteOptions};
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write_torn_journal(temp: &TempDir) -> (String, u64) {
+        let journal_path = temp.path().join("test.nrj");
+        let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+        writer.append_event(&json!({"event_type": "test"})).unwrap();
+        writer.finish().unwrap();
+        let valid_len = fs::metadata(&journal_path).unwrap().len();
+
+        let mut bytes = fs::read(&journal_path).unwrap();
+        bytes.extend_from_slice(&[0x01, 0, 0, 0, 0xFF]);
+        fs::write(&journal_path, &bytes).unwrap();
+        (journal_path.to_str().unwrap().to_string(), valid_len)
+    }
+
+    #[test]
+    fn test_repair_dry_run_leaves_journal() {
+        let temp = TempDir::new().unwrap();
+        let (journal, valid_len) = write_torn_journal(&temp);
+
+        let result = run(journal.clone(), None, false, false);
+        assert!(result.is_ok(), "repair failed: {:?}", result.err());
+        assert_eq!(fs::metadata(&journal).unwrap().len(), valid_len + 5);
+    }
+
+    #[test]
+    fn test_repair_quarantines_and_truncates() {
+        let temp = TempDir::new().unwrap();
+        let (journal, valid_len) = write_torn_journal(&temp);
+        let quarantine = temp.path().join("test.nrj.torn");
+
+        let dest = quarantine.to_str().unwrap().to_string();
+        let result = run(journal.clone(), Some(dest), true, true);
+        assert!(result.is_ok(), "repair failed: {:?}", result.err());
+        assert_eq!(fs::metadata(&journal).unwrap().len(), valid_len);
+        assert_eq!(fs::read(&quarantine).unwrap(), vec![0x01, 0, 0, 0, 0xFF]);
+
+        // Appending works again
+        let writer = JournalWriter::open(&journal, WriteOptions::default());
+        assert!(writer.is_ok());
+    }
+
+    #[test]
+    fn test_repair_refuses_to_truncate_mid_file_damage() {
+        let temp = TempDir::new().unwrap();
+        let journal_path = temp.path().join("test.nrj");
+        let options = WriteOptions::default().with_checksums(true);
+        let mut writer = JournalWriter::open(&journal_path, options).unwrap();
+        for n in 0..3 {
+            writer.append_event(&json!({"event_type": "test", "n": n})).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Flip a payload byte of the second event
+        let mut bytes = fs::read(&journal_path).unwrap();
+        let len = bytes.len();
+        bytes[len / 2] ^= 0x01;
+        fs::write(&journal_path, &bytes).unwrap();
+
+        let journal = journal_path.to_str().unwrap().to_string();
+        assert!(run(journal.clone(), None, false, true).is_ok());
+        assert!(run(journal.clone(), None, true, false).is_err());
+        assert_eq!(fs::read(&journal_path).unwrap(), bytes);
+    }
+}
@@ -1,16 +1,42 @@
 //! Verify command implementation.
 
 use crate::path;
-use northroot_canonical::{Canonicalizer, ProfileId};
-use northroot_journal::{JournalReader, ReadMode, verify_event_id};
+use northroot_journal::frame::FRAME_KIND_EVENT_JSON;
+use northroot_journal::{
+    verify_incremental_with, verify_journal_with, verify_links, FrameOutcome, FrameReport,
+    JournalError, LinkIssueKind, LinkOptions, LinkReport, LinkScope, VerificationReport,
+    VerifiedPrefix, VerifyOptions,
+};
+use serde::Serialize;
 use serde_json::json;
 
+/// How `northroot verify` prints its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A table of events, printed as they are verified.
+    Text,
+    /// A JSON array of `{event_id, valid, error}` objects, one per event
+    /// (`--json`).
+    Json,
+    /// The JSON verification report object (`--json --report`).
+    Report,
+}
+
+/// State file handling for `northroot verify --incremental`.
+#[derive(Debug, Default)]
+pub struct Incremental {
+    /// State file path (default: the journal path with `.verified` appended).
+    pub state: Option<String>,
+}
+
 pub fn run(
     journal: String,
     strict: bool,
-    json_output: bool,
-    max_events: Option<u64>,
+    format: Format,
     max_size: Option<u64>,
+    options: VerifyOptions,
+    incremental: Option<Incremental>,
+    links: Option<LinkScope>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Validate and normalize journal path
     let journal_path = path::validate_journal_path(&journal, false)
@@ -29,79 +55,194 @@ pub fn run(
         }
     }
 
-    let profile = ProfileId::parse("northroot-canonical-v1")
-        .map_err(|e| format!("Invalid profile ID: {}", e))?;
-    let canonicalizer = Canonicalizer::new(profile);
-
-    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).map_err(|e| {
+    let open_error = |e: JournalError| {
         let sanitized = path::sanitize_path_for_error(&journal_path);
         format!("Failed to open journal file: {}: {}", sanitized, e)
-    })?;
-
-    let mut all_ok = true;
-    let mut results = Vec::new();
-    let mut event_count: u64 = 0;
-
-    while let Some(event) = reader.read_event()? {
-        // Check max_events limit
-        if let Some(max) = max_events {
-            if event_count >= max {
-                break;
+    };
+    // Events are listed as they are verified, so memory stays flat however
+    // long the journal is
+    if format == Format::Text {
+        println!("{:<44} {:<10} ERROR", "EVENT_ID", "VALID");
+        println!("{}", "-".repeat(80));
+    }
+    let mut listed = 0u64;
+    let on_frame = |frame: &FrameReport| match format {
+        Format::Text => print_frame(frame),
+        Format::Json => print_json_entry(frame, &mut listed),
+        Format::Report => {}
+    };
+    let mut summary = None;
+    let report = match incremental {
+        None => verify_journal_with(&journal_path, &options, on_frame).map_err(open_error)?,
+        Some(incremental) => {
+            let state = incremental
+                .state
+                .map_or_else(|| VerifiedPrefix::path_for(&journal_path), Into::into);
+            let prefix = VerifiedPrefix::load(&state).map_err(|e| {
+                let sanitized = path::sanitize_path_for_error(&state);
+                format!("Failed to read state file: {}: {}", sanitized, e)
+            })?;
+            let (report, verified) = match verify_incremental_with(
+                &journal_path,
+                prefix.as_ref(),
+                &options,
+                on_frame,
+            ) {
+                Err(JournalError::PrefixChanged { len }) => {
+                    return Err(format!(
+                        "Journal changed since it was last verified: its first {} bytes no longer match {}. \
+                         Delete the state file to verify the whole journal again.",
+                        len,
+                        path::sanitize_path_for_error(&state)
+                    )
+                    .into());
+                }
+                result => result.map_err(open_error)?,
+            };
+            let skipped = prefix.map_or(0, |prefix| prefix.len);
+            let mut lines = vec![match verified {
+                Some(verified) => {
+                    verified.save(&state)?;
+                    format!(
+                        "{} new frames verified after {} bytes already verified",
+                        report.totals.frames, skipped
+                    )
+                }
+                None => format!(
+                    "{} new frames checked after {} bytes already verified; state file not updated",
+                    report.totals.frames, skipped
+                ),
+            }];
+            if skipped > 0 {
+                lines.push(
+                    "note: events repeating an event_id from the already verified bytes are not \
+                     detected; verify without --incremental to check for them"
+                        .to_string(),
+                );
             }
+            summary = Some(lines);
+            report
         }
-        event_count += 1;
+    };
 
-        let event_id_str = event
-            .get("event_id")
-            .and_then(|v| v.get("b64"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("?")
-            .to_string();
-
-        match verify_event_id(&event, &canonicalizer) {
-            Ok(true) => {
-                results.push((event_id_str.clone(), true, None));
-            }
-            Ok(false) => {
-                all_ok = false;
-                results.push((event_id_str.clone(), false, Some("event_id mismatch".to_string())));
-            }
-            Err(e) => {
-                all_ok = false;
-                results.push((event_id_str.clone(), false, Some(e.to_string())));
-            }
+    // Link checks need every frame to be readable
+    let damaged = report.truncated_at.is_some() || report.totals.damaged > 0;
+    let links = match links {
+        Some(scope) if !damaged => {
+            let options = LinkOptions {
+                scope,
+                max_events: options.max_events,
+            };
+            Some(verify_links(&journal_path, &options).map_err(open_error)?)
         }
-    }
+        _ => None,
+    };
 
-    // Output results
-    if json_output {
-        let json_results: Vec<_> = results
-            .into_iter()
-            .map(|(id, valid, error)| {
-                json!({
-                    "event_id": id,
-                    "valid": valid,
-                    "error": error
-                })
-            })
-            .collect();
-        println!("{}", serde_json::to_string_pretty(&json_results)?);
+    // Output results; JSON keeps stdout to itself, so notes go to stderr
+    let warn = |line: String| match format {
+        Format::Text => println!("{}", line),
+        _ => eprintln!("{}", line),
+    };
+    if format == Format::Json {
+        println!("{}", if listed == 0 { "[]" } else { "\n]" });
+    }
+    if format == Format::Report {
+        let output = Output {
+            report: &report,
+            links: links.as_ref(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
-        println!("{:<44} {:<10} ERROR", "EVENT_ID", "VALID");
-        println!("{}", "-".repeat(80));
-        for (id, valid, error_opt) in results {
-            let error_str = error_opt.as_deref().unwrap_or("");
-            println!("{:<44} {:<10} {}", truncate(&id, 44), if valid { "✓" } else { "✗" }, error_str);
+        for duplicate in &report.duplicate_event_ids {
+            warn(format!(
+                "warning: event_id {} appears {} times",
+                duplicate.event_id.b64,
+                duplicate.offsets.len()
+            ));
+        }
+        for issue in links.iter().flat_map(|links| &links.issues) {
+            let chain = issue
+                .chain
+                .as_ref()
+                .map_or(String::new(), |key| format!(" in chain {}", key));
+            let kind = match issue.kind {
+                LinkIssueKind::Fork => "fork",
+                LinkIssueKind::Gap => "gap",
+                LinkIssueKind::Dangling => "dangling prev_event_id",
+                LinkIssueKind::MultipleGenesis => "second genesis event",
+            };
+            warn(format!(
+                "warning: {} at offset {}{} (event_id {})",
+                kind, issue.offset, chain, issue.event_id.b64
+            ));
         }
     }
+    summary.into_iter().flatten().for_each(warn);
 
-    if strict && !all_ok {
+    if let Some(offset) = report.truncated_at {
+        return Err(format!("Journal is truncated at offset {}", offset).into());
+    }
+    if let Some(frame) = report
+        .failures
+        .iter()
+        .find(|f| f.outcome == FrameOutcome::Damaged)
+    {
+        let error = frame.error.as_deref().unwrap_or("damaged frame");
+        return Err(format!("Journal is damaged at offset {}: {}", frame.offset, error).into());
+    }
+    if strict && !(report.passed() && links.is_none_or(|links| links.passed())) {
         std::process::exit(1);
     }
 
     Ok(())
 }
 
+/// Prints one event of the `--json` list, opening the array before the first.
+///
+/// The list keeps the shape `northroot verify --json` has always had: a
+/// pretty-printed array of `{event_id, valid, error}` objects.
+fn print_json_entry(frame: &FrameReport, listed: &mut u64) {
+    if frame.kind != Some(FRAME_KIND_EVENT_JSON) {
+        return;
+    }
+    let entry = json!({
+        "event_id": frame.event_id.as_ref().map_or("?", |id| id.b64.as_str()),
+        "valid": frame.outcome == FrameOutcome::Valid,
+        "error": frame.error,
+    });
+    let text = serde_json::to_string_pretty(&entry).expect("JSON values serialize");
+    print!("{}", if *listed == 0 { "[\n" } else { ",\n" });
+    let indented: Vec<_> = text.lines().map(|line| format!("  {}", line)).collect();
+    print!("{}", indented.join("\n"));
+    *listed += 1;
+}
+
+/// `--json --report` output: the verification report, plus the link report
+/// with `--chain`.
+#[derive(Serialize)]
+struct Output<'a> {
+    #[serde(flatten)]
+    report: &'a VerificationReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    links: Option<&'a LinkReport>,
+}
+
+/// Prints one line of the text listing for an event or damaged frame.
+fn print_frame(frame: &FrameReport) {
+    if !matches!(frame.kind, Some(FRAME_KIND_EVENT_JSON) | None) {
+        return;
+    }
+    let id = frame.event_id.as_ref().map_or("?", |id| id.b64.as_str());
+    let valid = frame.outcome == FrameOutcome::Valid;
+    let error_str = frame.error.as_deref().unwrap_or("");
+    println!(
+        "{:<44} {:<10} {}",
+        truncate(id, 44),
+        if valid { "✓" } else { "✗" },
+        error_str
+    );
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
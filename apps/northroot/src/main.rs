@@ -6,7 +6,8 @@ mod commands;
 mod output;
 mod path;
 
-use commands::{append, canonicalize, event_id, list, verify};
+use commands::{append, canonicalize, event_id, explain_diff, get, list, repair, verify};
+use northroot_journal::{LinkScope, VerifyOptions};
 
 #[derive(Parser)]
 #[command(name = "northroot")]
@@ -28,6 +29,16 @@ enum Commands {
         /// Input JSON file (or stdin if not provided)
         input: Option<String>,
     },
+    /// Explain why two JSON inputs produce different event_ids
+    ExplainDiff {
+        /// First input JSON file
+        a: String,
+        /// Second input JSON file
+        b: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// List events in a journal
     List {
         /// Path to journal file
@@ -41,6 +52,31 @@ enum Commands {
         /// Reject journals larger than SIZE bytes (default: unlimited)
         #[arg(long)]
         max_size: Option<u64>,
+        /// Only events with this event_type
+        #[arg(long)]
+        event_type: Option<String>,
+        /// Only events with this principal_id
+        #[arg(long)]
+        principal: Option<String>,
+        /// Only events that occurred at or after this RFC3339 timestamp
+        #[arg(long)]
+        since: Option<String>,
+        /// Only events that occurred at or before this RFC3339 timestamp
+        #[arg(long)]
+        until: Option<String>,
+        /// Scan the journal instead of using the .nrx sidecar index
+        #[arg(long)]
+        no_index: bool,
+    },
+    /// Print the event with the given event_id
+    Get {
+        /// Path to journal file
+        journal: String,
+        /// Event ID (base64url SHA-256 digest)
+        event_id: String,
+        /// Scan the journal instead of using the .nrx sidecar index
+        #[arg(long)]
+        no_index: bool,
     },
     /// Verify all event IDs in a journal
     Verify {
@@ -49,15 +85,43 @@ enum Commands {
         /// Exit with error code if any verification fails
         #[arg(long)]
         strict: bool,
-        /// Output as JSON
+        /// Output as JSON: one {event_id, valid, error} object per event
         #[arg(long)]
         json: bool,
+        /// With --json, print the verification report object instead: totals,
+        /// failing frames, duplicates, unknown frame kinds and truncation
+        #[arg(long, requires = "json")]
+        report: bool,
         /// Stop after reading N events (default: unlimited)
         #[arg(long)]
         max_events: Option<u64>,
         /// Reject journals larger than SIZE bytes (default: unlimited)
         #[arg(long)]
         max_size: Option<u64>,
+        /// Canonicalize and hash events on N threads (0 = one per CPU)
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+        /// Only verify frames appended since the last incremental run
+        #[arg(long)]
+        incremental: bool,
+        /// State file for --incremental (default: <JOURNAL>.verified)
+        #[arg(long, requires = "incremental")]
+        state: Option<String>,
+        /// Also check that prev_event_id links form one unbroken chain; this
+        /// reads the whole journal, so it cannot be combined with --incremental
+        #[arg(long, conflicts_with = "incremental")]
+        chain: bool,
+        /// Check prev_event_id chains separately per value of FIELD, e.g.
+        /// principal_id or a JSON pointer such as /subject/id (implies --chain)
+        #[arg(long, value_name = "FIELD", conflicts_with = "incremental")]
+        chain_by: Option<String>,
+        /// Fail events that repeat an earlier event_id instead of warning
+        #[arg(long)]
+        reject_duplicates: bool,
+        /// Do not look for repeated event_ids, which takes memory for every
+        /// distinct event_id in the journal
+        #[arg(long, conflicts_with = "reject_duplicates")]
+        no_duplicate_check: bool,
     },
     /// Append an event to a journal
     Append {
@@ -71,6 +135,26 @@ enum Commands {
         /// Sync file to disk after append (default: false)
         #[arg(long)]
         sync: bool,
+        /// Create new journals in v2 format with per-frame CRC32C checksums
+        #[arg(long)]
+        checksums: bool,
+        /// Skip the event if its event_id is already in the journal
+        #[arg(long)]
+        idempotent: bool,
+    },
+    /// Find and cut off a torn journal tail (dry run unless --truncate)
+    Repair {
+        /// Path to journal file
+        journal: String,
+        /// Copy the discarded bytes to this new file first
+        #[arg(long)]
+        quarantine: Option<String>,
+        /// Truncate the journal to its last valid frame
+        #[arg(long)]
+        truncate: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -80,25 +164,80 @@ fn main() {
     let result = match cli.command {
         Commands::Canonicalize { input } => canonicalize::run(input),
         Commands::EventId { input } => event_id::run(input),
+        Commands::ExplainDiff { a, b, json } => explain_diff::run(a, b, json),
         Commands::List {
             journal,
             json,
             max_events,
             max_size,
-        } => list::run(journal, json, max_events, max_size),
+            event_type,
+            principal,
+            since,
+            until,
+            no_index,
+        } => {
+            let filter = list::ListFilter {
+                event_type,
+                principal,
+                since,
+                until,
+            };
+            list::run(journal, json, max_events, max_size, filter, no_index)
+        }
+        Commands::Get {
+            journal,
+            event_id,
+            no_index,
+        } => get::run(journal, event_id, no_index),
         Commands::Verify {
             journal,
             strict,
             json,
+            report,
             max_events,
             max_size,
-        } => verify::run(journal, strict, json, max_events, max_size),
+            threads,
+            incremental,
+            state,
+            chain,
+            chain_by,
+            reject_duplicates,
+            no_duplicate_check,
+        } => {
+            let options = VerifyOptions {
+                max_events,
+                threads,
+                reject_duplicates,
+                detect_duplicates: !no_duplicate_check,
+                ..Default::default()
+            };
+            let incremental = incremental.then_some(verify::Incremental { state });
+            let links = match chain_by.as_deref() {
+                Some("principal_id") => Some(LinkScope::Principal),
+                Some(field) => Some(LinkScope::Field(field.to_string())),
+                None => chain.then_some(LinkScope::Journal),
+            };
+            let format = match (json, report) {
+                (true, true) => verify::Format::Report,
+                (true, false) => verify::Format::Json,
+                _ => verify::Format::Text,
+            };
+            verify::run(journal, strict, format, max_size, options, incremental, links)
+        }
         Commands::Append {
             journal,
             input,
             strict,
             sync,
-        } => append::run(journal, input, strict, sync),
+            checksums,
+            idempotent,
+        } => append::run(journal, input, strict, sync, checksums, idempotent),
+        Commands::Repair {
+            journal,
+            quarantine,
+            truncate,
+            json,
+        } => repair::run(journal, quarantine, truncate, json),
     };
 
     if let Err(e) = result {
@@ -0,0 +1,303 @@
+//! Structural diff explaining why two events canonicalize (and hash) differently.
+//!
+//! Differences are reported as JSON Pointers (RFC 6901) into the inputs. The
+//! top-level `event_id` member is ignored because it is excluded from the hash
+//! input (see [`compute_event_id`]).
+
+use crate::{compute_event_id, Canonicalizer, Digest, EventIdError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use unicode_normalization::UnicodeNormalization;
+
+/// Result of comparing two events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalDiff {
+    /// Event ID computed for the left-hand input.
+    pub event_id_a: Digest,
+    /// Event ID computed for the right-hand input.
+    pub event_id_b: Digest,
+    /// Whether both inputs produce identical canonical bytes.
+    pub canonical_equal: bool,
+    /// Structural differences, in document order of the left-hand input.
+    pub differences: Vec<Difference>,
+}
+
+/// A single difference located by JSON Pointer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Difference {
+    /// JSON Pointer (RFC 6901) to the differing location.
+    pub pointer: String,
+    /// What differs at that location.
+    #[serde(flatten)]
+    pub kind: DifferenceKind,
+}
+
+/// Classification of a difference.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DifferenceKind {
+    /// Present in the left-hand input only.
+    Missing {
+        /// Value on the left-hand side.
+        a: Value,
+    },
+    /// Present in the right-hand input only.
+    Extra {
+        /// Value on the right-hand side.
+        b: Value,
+    },
+    /// JSON types differ (e.g. number vs string).
+    TypeChanged {
+        /// JSON type on the left-hand side.
+        a_type: String,
+        /// JSON type on the right-hand side.
+        b_type: String,
+    },
+    /// Strings (or object keys) are equal after NFC normalization but differ as code points.
+    UnicodeNormalization {
+        /// Left-hand string.
+        a: String,
+        /// Right-hand string.
+        b: String,
+    },
+    /// Numbers are numerically equal but are written differently (e.g. `1.0` vs `1`).
+    NumberFormat {
+        /// Left-hand number as serialized.
+        a: String,
+        /// Right-hand number as serialized.
+        b: String,
+    },
+    /// Same type, different value.
+    ValueChanged {
+        /// Left-hand value.
+        a: Value,
+        /// Right-hand value.
+        b: Value,
+    },
+}
+
+/// Canonicalizes both inputs and explains how they differ.
+///
+/// # Example
+///
+/// ```rust
+/// use northroot_canonical::{diff::explain_diff, Canonicalizer, ProfileId};
+/// use serde_json::json;
+///
+/// let canonicalizer = Canonicalizer::new(ProfileId::parse("northroot-canonical-v1")?);
+/// let a = json!({"event_type": "test", "amount": 1});
+/// let b = json!({"event_type": "test", "amount": 1.0});
+///
+/// let diff = explain_diff(&a, &b, &canonicalizer)?;
+/// assert_ne!(diff.event_id_a, diff.event_id_b);
+/// assert_eq!(diff.differences[0].pointer, "/amount");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns [`EventIdError`] if either input cannot be canonicalized.
+pub fn explain_diff(
+    a: &Value,
+    b: &Value,
+    canonicalizer: &Canonicalizer,
+) -> Result<CanonicalDiff, EventIdError> {
+    let canonical_a = canonicalizer.canonicalize(a)?;
+    let canonical_b = canonicalizer.canonicalize(b)?;
+    let event_id_a = compute_event_id(a, canonicalizer)?;
+    let event_id_b = compute_event_id(b, canonicalizer)?;
+
+    let mut differences = Vec::new();
+    match (a, b) {
+        (Value::Object(left), Value::Object(right)) => {
+            let strip = |map: &Map<String, Value>| {
+                let mut map = map.clone();
+                map.remove("event_id");
+                map
+            };
+            diff_objects(&strip(left), &strip(right), "", &mut differences);
+        }
+        _ => diff_values(a, b, "", &mut differences),
+    }
+
+    Ok(CanonicalDiff {
+        event_id_a,
+        event_id_b,
+        canonical_equal: canonical_a.bytes == canonical_b.bytes,
+        differences,
+    })
+}
+
+fn diff_values(a: &Value, b: &Value, pointer: &str, out: &mut Vec<Difference>) {
+    let push = |out: &mut Vec<Difference>, kind| {
+        out.push(Difference {
+            pointer: pointer.to_string(),
+            kind,
+        })
+    };
+
+    match (a, b) {
+        (Value::Object(left), Value::Object(right)) => diff_objects(left, right, pointer, out),
+        (Value::Array(left), Value::Array(right)) => {
+            for (idx, (l, r)) in left.iter().zip(right.iter()).enumerate() {
+                diff_values(l, r, &format!("{}/{}", pointer, idx), out);
+            }
+            for (idx, l) in left.iter().enumerate().skip(right.len()) {
+                out.push(Difference {
+                    pointer: format!("{}/{}", pointer, idx),
+                    kind: DifferenceKind::Missing { a: l.clone() },
+                });
+            }
+            for (idx, r) in right.iter().enumerate().skip(left.len()) {
+                out.push(Difference {
+                    pointer: format!("{}/{}", pointer, idx),
+                    kind: DifferenceKind::Extra { b: r.clone() },
+                });
+            }
+        }
+        (Value::Number(l), Value::Number(r)) => {
+            if l == r {
+                return;
+            }
+            let (ls, rs) = (l.to_string(), r.to_string());
+            if ls != rs && numerically_equal(l, r) {
+                push(out, DifferenceKind::NumberFormat { a: ls, b: rs });
+            } else {
+                push(
+                    out,
+                    DifferenceKind::ValueChanged {
+                        a: a.clone(),
+                        b: b.clone(),
+                    },
+                );
+            }
+        }
+        (Value::String(l), Value::String(r)) => {
+            if l == r {
+                return;
+            }
+            if nfc(l) == nfc(r) {
+                push(
+                    out,
+                    DifferenceKind::UnicodeNormalization {
+                        a: l.clone(),
+                        b: r.clone(),
+                    },
+                );
+            } else {
+                push(
+                    out,
+                    DifferenceKind::ValueChanged {
+                        a: a.clone(),
+                        b: b.clone(),
+                    },
+                );
+            }
+        }
+        _ if json_type(a) != json_type(b) => push(
+            out,
+            DifferenceKind::TypeChanged {
+                a_type: json_type(a).to_string(),
+                b_type: json_type(b).to_string(),
+            },
+        ),
+        _ if a != b => push(
+            out,
+            DifferenceKind::ValueChanged {
+                a: a.clone(),
+                b: b.clone(),
+            },
+        ),
+        _ => {}
+    }
+}
+
+fn diff_objects(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    pointer: &str,
+    out: &mut Vec<Difference>,
+) {
+    for (key, left) in a {
+        let child = format!("{}/{}", pointer, escape_pointer(key));
+        if let Some(right) = b.get(key) {
+            diff_values(left, right, &child, out);
+            continue;
+        }
+        // A key spelled with different code points but the same NFC form.
+        let normalized = nfc(key);
+        match b
+            .keys()
+            .find(|other| !a.contains_key(*other) && nfc(other) == normalized)
+        {
+            Some(other) => out.push(Difference {
+                pointer: child,
+                kind: DifferenceKind::UnicodeNormalization {
+                    a: key.clone(),
+                    b: other.clone(),
+                },
+            }),
+            None => out.push(Difference {
+                pointer: child,
+                kind: DifferenceKind::Missing { a: left.clone() },
+            }),
+        }
+    }
+    for (key, right) in b {
+        if a.contains_key(key) {
+            continue;
+        }
+        let normalized = nfc(key);
+        if a.keys()
+            .any(|other| !b.contains_key(other) && nfc(other) == normalized)
+        {
+            continue;
+        }
+        out.push(Difference {
+            pointer: format!("{}/{}", pointer, escape_pointer(key)),
+            kind: DifferenceKind::Extra { b: right.clone() },
+        });
+    }
+}
+
+/// Compares two numbers by value: integers exactly, and through `f64` only
+/// when a side is a float and the other converts to `f64` without rounding.
+fn numerically_equal(l: &Number, r: &Number) -> bool {
+    fn as_int(n: &Number) -> Option<i128> {
+        n.as_i64()
+            .map(i128::from)
+            .or_else(|| n.as_u64().map(i128::from))
+    }
+    match (as_int(l), as_int(r)) {
+        (Some(l), Some(r)) => l == r,
+        (Some(int), None) => exact_f64(int).is_some_and(|int| r.as_f64() == Some(int)),
+        (None, Some(int)) => exact_f64(int).is_some_and(|int| l.as_f64() == Some(int)),
+        (None, None) => l.as_f64().is_some() && l.as_f64() == r.as_f64(),
+    }
+}
+
+/// Returns `int` as an `f64` if the conversion does not round.
+fn exact_f64(int: i128) -> Option<f64> {
+    let float = int as f64;
+    (float as i128 == int).then_some(float)
+}
+
+fn nfc(s: &str) -> String {
+    s.nfc().collect()
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escapes a key for use as a JSON Pointer reference token (RFC 6901 §3).
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
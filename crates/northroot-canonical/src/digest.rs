@@ -13,7 +13,7 @@ pub enum DigestAlg {
 }
 
 /// Algorithm + bytes digest, encoded as base64url without padding.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Digest {
     /// Digest algorithm (currently always `sha-256`).
     pub alg: DigestAlg,
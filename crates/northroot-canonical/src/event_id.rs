@@ -3,7 +3,7 @@
 //! Event IDs are computed as: `sha256(domain_separator || canonical_bytes(event))`
 //! where the event_id field is excluded from the hash input.
 
-use crate::{Canonicalizer, Digest, DigestAlg};
+use crate::{Canonicalizer, Digest, DigestAlg, HygieneReport};
 use serde::Serialize;
 use serde_json::Value;
 use sha2::{Digest as Sha2Digest, Sha256};
@@ -53,6 +53,19 @@ pub fn compute_event_id<T: Serialize>(
     event: &T,
     canonicalizer: &Canonicalizer,
 ) -> Result<Digest, EventIdError> {
+    compute_event_id_with_report(event, canonicalizer).map(|(event_id, _)| event_id)
+}
+
+/// Computes the event ID like [`compute_event_id`], together with the hygiene
+/// report of canonicalizing the event.
+///
+/// # Errors
+///
+/// Returns [`EventIdError`] if serialization or canonicalization fails.
+pub fn compute_event_id_with_report<T: Serialize>(
+    event: &T,
+    canonicalizer: &Canonicalizer,
+) -> Result<(Digest, HygieneReport), EventIdError> {
     // Serialize to JSON Value first
     let mut value: Value =
         serde_json::to_value(event).map_err(|e| EventIdError::Serialization(e.to_string()))?;
@@ -76,7 +89,7 @@ pub fn compute_event_id<T: Serialize>(
 
     use base64::Engine;
     let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(hash_bytes);
-    Ok(Digest::new(DigestAlg::Sha256, b64)?)
+    Ok((Digest::new(DigestAlg::Sha256, b64)?, result.report))
 }
 
 /// Error during event ID computation.
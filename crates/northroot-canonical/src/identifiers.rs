@@ -1,7 +1,12 @@
+use crate::canonicalizer::Canonicalizer;
 use crate::digest::Digest;
+use crate::event_id::{compute_event_id, EventIdError};
 use crate::validation::ValidationError;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
 
 /// Opaque reference to content-addressed bytes.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -61,11 +66,6 @@ newtype!(
     "Identifier for canonicalization profiles (pattern: `[A-Za-z0-9_-]{16,128}`)",
     r"^[A-Za-z0-9_-]{16,128}$"
 );
-newtype!(
-    PrincipalId,
-    "Stable identifier for principals (`kind:name`, lowercase, URL-safe).",
-    r"^(human|service|agent|org):[a-z][a-z0-9_-]{0,62}$"
-);
 newtype!(
     ToolName,
     "Canonical tool identifier like `canon.hash` or `llm.generate`.",
@@ -76,3 +76,482 @@ newtype!(
     "UTC RFC3339 timestamp with `Z` suffix.",
     r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d{1,9})?Z$"
 );
+
+/// Maximum number of dot-separated segments in a [`ToolName`] or [`ToolPattern`].
+const TOOL_MAX_SEGMENTS: usize = 8;
+
+/// Maximum length of a [`ToolPattern`], as declared by the schema's `maxLength`.
+const TOOL_PATTERN_MAX_LEN: usize = 64;
+
+impl ToolName {
+    /// Returns the dot-separated segments of the tool name.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.')
+    }
+
+    /// Returns `true` if this tool name is matched by `pattern`.
+    ///
+    /// Matching is segment-aware: `llm.*` covers `llm.generate` but not `llmx.generate`.
+    ///
+    /// ```rust
+    /// use northroot_canonical::{ToolName, ToolPattern};
+    ///
+    /// let tool = ToolName::parse("llm.generate")?;
+    /// assert!(tool.is_within(&ToolPattern::parse("llm.*")?));
+    /// assert!(tool.is_within(&ToolPattern::parse("*.generate")?));
+    /// assert!(!tool.is_within(&ToolPattern::parse("llm.embed")?));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn is_within(&self, pattern: &ToolPattern) -> bool {
+        pattern.matches(self)
+    }
+
+    /// Returns `true` if any pattern in the allowlist matches this tool name.
+    ///
+    /// An empty allowlist permits nothing.
+    pub fn is_within_any<'a>(&self, allowlist: impl IntoIterator<Item = &'a ToolPattern>) -> bool {
+        allowlist.into_iter().any(|pattern| pattern.matches(self))
+    }
+}
+
+/// Allowlist pattern over [`ToolName`]s.
+///
+/// A pattern is a dot-separated list of segments, each either a literal tool
+/// segment or `*`:
+///
+/// - `llm.generate` matches exactly that tool.
+/// - A `*` in the final position matches one or more trailing segments, so
+///   `llm.*` covers `llm.generate` and `llm.embed.batch` but not `llm` itself.
+/// - A `*` anywhere else matches exactly one segment, so `llm.*.stream` covers
+///   `llm.generate.stream` but not `llm.generate.batch.stream`.
+///
+/// A lone `*` matches every tool.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ToolPattern(String);
+
+impl ToolPattern {
+    /// Parses a validated tool pattern from a string.
+    pub fn parse(value: impl Into<String>) -> Result<Self, ValidationError> {
+        let s = value.into();
+        let segment = Regex::new(r"^[a-z][a-z0-9_]*$").expect("invalid regex");
+        let segments: Vec<&str> = s.split('.').collect();
+        let valid = s.len() <= TOOL_PATTERN_MAX_LEN
+            && segments.len() <= TOOL_MAX_SEGMENTS
+            && segments
+                .iter()
+                .all(|seg| *seg == "*" || segment.is_match(seg));
+        if !valid {
+            return Err(ValidationError::PatternMismatch {
+                field: "ToolPattern",
+                value: s,
+            });
+        }
+        Ok(Self(s))
+    }
+
+    /// Returns `true` if `tool` is matched by this pattern.
+    pub fn matches(&self, tool: &ToolName) -> bool {
+        let pattern: Vec<&str> = self.0.split('.').collect();
+        let name: Vec<&str> = tool.segments().collect();
+        let (last, init) = pattern
+            .split_last()
+            .expect("split yields at least one segment");
+
+        if *last == "*" {
+            if name.len() <= init.len() {
+                return false;
+            }
+        } else if name.len() != pattern.len() {
+            return false;
+        }
+
+        pattern
+            .iter()
+            .zip(name.iter())
+            .all(|(p, n)| *p == "*" || p == n)
+    }
+}
+
+impl TryFrom<String> for ToolPattern {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl From<ToolPattern> for String {
+    fn from(value: ToolPattern) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<str> for ToolPattern {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Class of actor encoded in the `kind` segment of a [`PrincipalId`].
+///
+/// Mirrors `PrincipalKind` in `schemas/canonical/v1/types.schema.json`. Parsing is
+/// driven by [`PrincipalKind::ALL`], so a new kind only needs a variant and a
+/// string form here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum PrincipalKind {
+    /// A natural person.
+    Human,
+    /// A long-running service or daemon.
+    Service,
+    /// An autonomous or semi-autonomous agent.
+    Agent,
+    /// An organization.
+    Org,
+}
+
+impl PrincipalKind {
+    /// Every recognized principal kind, in schema order.
+    pub const ALL: &'static [PrincipalKind] = &[
+        PrincipalKind::Human,
+        PrincipalKind::Service,
+        PrincipalKind::Agent,
+        PrincipalKind::Org,
+    ];
+
+    /// Returns the wire form used in the `kind` segment (e.g. `"agent"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PrincipalKind::Human => "human",
+            PrincipalKind::Service => "service",
+            PrincipalKind::Agent => "agent",
+            PrincipalKind::Org => "org",
+        }
+    }
+}
+
+impl FromStr for PrincipalKind {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PrincipalKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| ValidationError::PatternMismatch {
+                field: "PrincipalKind",
+                value: s.to_string(),
+            })
+    }
+}
+
+impl fmt::Display for PrincipalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Pattern for the `name` segment of a [`PrincipalId`].
+const PRINCIPAL_NAME_PATTERN: &str = r"^[a-z][a-z0-9_-]{0,62}$";
+
+/// Stable identifier for principals (`kind:name`, lowercase, URL-safe).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PrincipalId(String);
+
+impl PrincipalId {
+    /// Creates a new instance without validation; callers are responsible for conformity.
+    pub fn new(value: String) -> Self {
+        Self(value)
+    }
+
+    /// Parses a validated identifier from a string.
+    pub fn parse(value: impl Into<String>) -> Result<Self, ValidationError> {
+        let s = value.into();
+        if split_principal(&s).is_none() {
+            return Err(ValidationError::PatternMismatch {
+                field: "PrincipalId",
+                value: s,
+            });
+        }
+        Ok(Self(s))
+    }
+
+    /// Builds a validated identifier from its kind and name segments.
+    ///
+    /// ```rust
+    /// use northroot_canonical::{PrincipalId, PrincipalKind};
+    ///
+    /// let id = PrincipalId::from_parts(PrincipalKind::Agent, "planner")?;
+    /// assert_eq!(id.as_ref(), "agent:planner");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_parts(kind: PrincipalKind, name: &str) -> Result<Self, ValidationError> {
+        Self::parse(format!("{}:{}", kind.as_str(), name))
+    }
+
+    /// Returns the principal kind, or `None` if the identifier was built
+    /// without validation and does not carry a recognized kind.
+    pub fn kind(&self) -> Option<PrincipalKind> {
+        split_principal(&self.0).map(|(kind, _)| kind)
+    }
+
+    /// Returns the `name` segment, or `None` if the identifier is malformed.
+    pub fn name(&self) -> Option<&str> {
+        split_principal(&self.0).map(|(_, name)| name)
+    }
+}
+
+/// Splits `kind:name`, returning `None` unless both segments are valid.
+fn split_principal(value: &str) -> Option<(PrincipalKind, &str)> {
+    let (kind, name) = value.split_once(':')?;
+    let kind = kind.parse::<PrincipalKind>().ok()?;
+    let re = Regex::new(PRINCIPAL_NAME_PATTERN).expect("invalid regex");
+    re.is_match(name).then_some((kind, name))
+}
+
+impl From<String> for PrincipalId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl AsRef<str> for PrincipalId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A principal acting on behalf of another (e.g. `agent:planner` for `org:acme`).
+///
+/// Delegation is kept separate from [`PrincipalId`] so that `principal_id` fields
+/// stay flat; events that need to record delegation carry this pair explicitly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "DelegationFields")]
+pub struct PrincipalDelegation {
+    /// Principal that performed the action.
+    pub actor: PrincipalId,
+    /// Principal on whose authority the actor acted.
+    pub on_behalf_of: PrincipalId,
+}
+
+impl PrincipalDelegation {
+    /// Constructs a validated delegation; a principal cannot delegate to itself.
+    pub fn new(actor: PrincipalId, on_behalf_of: PrincipalId) -> Result<Self, ValidationError> {
+        for id in [&actor, &on_behalf_of] {
+            if id.kind().is_none() {
+                return Err(ValidationError::PatternMismatch {
+                    field: "PrincipalId",
+                    value: id.as_ref().to_string(),
+                });
+            }
+        }
+        if actor == on_behalf_of {
+            return Err(ValidationError::PatternMismatch {
+                field: "PrincipalDelegation",
+                value: actor.as_ref().to_string(),
+            });
+        }
+        Ok(Self {
+            actor,
+            on_behalf_of,
+        })
+    }
+}
+
+/// Unvalidated wire form of a [`PrincipalDelegation`], checked by
+/// [`PrincipalDelegation::new`] when deserializing.
+#[derive(Deserialize)]
+struct DelegationFields {
+    actor: PrincipalId,
+    on_behalf_of: PrincipalId,
+}
+
+impl TryFrom<DelegationFields> for PrincipalDelegation {
+    type Error = ValidationError;
+
+    fn try_from(fields: DelegationFields) -> Result<Self, Self::Error> {
+        Self::new(fields.actor, fields.on_behalf_of)
+    }
+}
+
+/// Operational record identifier (UUID v4 or v7) from `ids.schema.json#/$defs/RecordID`.
+///
+/// Record IDs name an occurrence (a log line, a retry, a queue message). They are
+/// not content-derived and must never be used where an [`EventId`] or
+/// [`ContentId`] is expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RecordId(Uuid);
+
+impl RecordId {
+    /// Generates a new time-ordered UUIDv7 record identifier.
+    pub fn new_v7() -> Self {
+        Self(Uuid::now_v7())
+    }
+
+    /// Parses a hyphenated UUID string, accepting only versions 4 and 7.
+    pub fn parse(value: &str) -> Result<Self, ValidationError> {
+        let re = Regex::new(
+            r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+        )
+        .expect("invalid regex");
+        let uuid = re
+            .is_match(value)
+            .then(|| Uuid::parse_str(value).ok())
+            .flatten()
+            .filter(|uuid| matches!(uuid.get_version_num(), 4 | 7))
+            .ok_or_else(|| ValidationError::PatternMismatch {
+                field: "RecordId",
+                value: value.to_string(),
+            })?;
+        Ok(Self(uuid))
+    }
+
+    /// Returns the UUID version (4 or 7).
+    pub fn version(&self) -> usize {
+        self.0.get_version_num()
+    }
+
+    /// Returns the embedded Unix timestamp in milliseconds (UUIDv7 only).
+    pub fn unix_millis(&self) -> Option<u64> {
+        let (secs, nanos) = self.0.get_timestamp()?.to_unix();
+        Some(secs * 1000 + u64::from(nanos) / 1_000_000)
+    }
+
+    /// Returns the embedded creation time as a [`Timestamp`] (UUIDv7 only).
+    ///
+    /// ```rust
+    /// use northroot_canonical::RecordId;
+    ///
+    /// let id = RecordId::parse("01890a5d-ac96-774b-bcce-b302099a8057")?;
+    /// assert_eq!(id.timestamp().unwrap().as_ref(), "2023-06-30T03:34:18.518Z");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.unix_millis().map(format_unix_millis)
+    }
+
+    /// Returns the underlying UUID.
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for RecordId {
+    type Error = ValidationError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<RecordId> for String {
+    fn from(value: RecordId) -> Self {
+        value.0.hyphenated().to_string()
+    }
+}
+
+impl fmt::Display for RecordId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0.hyphenated(), f)
+    }
+}
+
+/// Formats Unix milliseconds as a UTC RFC 3339 timestamp with millisecond precision.
+fn format_unix_millis(millis: u64) -> Timestamp {
+    let days = (millis / 86_400_000) as i64;
+    let ms_of_day = millis % 86_400_000;
+    // Civil-from-days (H. Hinnant), valid for the proleptic Gregorian calendar.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    Timestamp::new(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1000 % 60,
+        ms_of_day % 1000
+    ))
+}
+
+/// Canonical payload identity (`ids.schema.json#/$defs/ContentID`).
+///
+/// Wraps a [`Digest`] of canonical bytes so it cannot be passed where an
+/// [`EventId`] is required.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ContentId(Digest);
+
+impl ContentId {
+    /// Wraps a digest known to identify canonical content.
+    pub fn from_digest(digest: Digest) -> Self {
+        Self(digest)
+    }
+
+    /// Returns the underlying digest.
+    pub fn digest(&self) -> &Digest {
+        &self.0
+    }
+
+    /// Unwraps into the underlying digest.
+    pub fn into_digest(self) -> Digest {
+        self.0
+    }
+}
+
+impl AsRef<Digest> for ContentId {
+    fn as_ref(&self) -> &Digest {
+        &self.0
+    }
+}
+
+/// Verifiable event identity (`ids.schema.json#/$defs/EventID`).
+///
+/// Wraps the domain-separated [`Digest`] produced by
+/// [`compute_event_id`](crate::compute_event_id).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EventId(Digest);
+
+impl EventId {
+    /// Computes the event ID of `event`; see [`compute_event_id`](crate::compute_event_id).
+    pub fn compute<T: Serialize>(
+        event: &T,
+        canonicalizer: &Canonicalizer,
+    ) -> Result<Self, EventIdError> {
+        compute_event_id(event, canonicalizer).map(Self)
+    }
+
+    /// Wraps a digest known to be an event ID (e.g. read from an `event_id` field).
+    pub fn from_digest(digest: Digest) -> Self {
+        Self(digest)
+    }
+
+    /// Returns the underlying digest.
+    pub fn digest(&self) -> &Digest {
+        &self.0
+    }
+
+    /// Unwraps into the underlying digest.
+    pub fn into_digest(self) -> Digest {
+        self.0
+    }
+}
+
+impl AsRef<Digest> for EventId {
+    fn as_ref(&self) -> &Digest {
+        &self.0
+    }
+}
@@ -40,9 +40,13 @@
 //!
 //! - [`Canonicalizer`] - Produces deterministic canonical bytes from JSON
 //! - [`compute_event_id`] - Computes content-derived event identifiers
+//! - [`explain_diff`] - Explains why two events produce different identifiers
 //! - [`Quantity`] - Lossless numeric types (Dec, Int, Rat, F64)
 //! - [`Digest`] - Content-addressed identifiers
 //! - [`PrincipalId`], [`ProfileId`], [`Timestamp`] - Core identifier types
+//! - [`PrincipalKind`], [`PrincipalDelegation`] - Structured principal classes and delegation
+//! - [`ToolName`], [`ToolPattern`] - Tool identifiers and segment-aware allowlist patterns
+//! - [`RecordId`], [`ContentId`], [`EventId`] - Distinct ID classes from `ids.schema.json`
 //!
 //! ## See Also
 //!
@@ -54,6 +58,8 @@
 
 /// Canonicalization helpers for deterministic hashing.
 pub mod canonicalizer;
+/// Structural diff explaining why two events hash differently.
+pub mod diff;
 /// Digest/identifier primitives.
 pub mod digest;
 /// Event ID computation with domain-separated hashing.
@@ -68,9 +74,13 @@ pub mod quantities;
 pub mod validation;
 
 pub use canonicalizer::{CanonicalizationError, CanonicalizationResult, Canonicalizer};
+pub use diff::{explain_diff, CanonicalDiff, Difference, DifferenceKind};
 pub use digest::{Digest, DigestAlg};
-pub use event_id::{compute_event_id, verify_event_id, EventIdError};
+pub use event_id::{compute_event_id, compute_event_id_with_report, verify_event_id, EventIdError};
 pub use hygiene::{HygieneReport, HygieneStatus, HygieneWarning};
-pub use identifiers::{ContentRef, PrincipalId, ProfileId, Timestamp, ToolName};
+pub use identifiers::{
+    ContentId, ContentRef, EventId, PrincipalDelegation, PrincipalId, PrincipalKind, ProfileId,
+    RecordId, Timestamp, ToolName, ToolPattern,
+};
 pub use quantities::Quantity;
 pub use validation::ValidationError;
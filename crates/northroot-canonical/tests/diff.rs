@@ -0,0 +1,158 @@
+use northroot_canonical::{explain_diff, Canonicalizer, DifferenceKind, ProfileId};
+use serde_json::json;
+
+fn canonicalizer() -> Canonicalizer {
+    Canonicalizer::new(ProfileId::parse("northroot-canonical-v1").unwrap())
+}
+
+fn base_event() -> serde_json::Value {
+    json!({
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": "service:example",
+        "canonical_profile_id": "northroot-canonical-v1"
+    })
+}
+
+#[test]
+fn identical_events_have_no_differences() {
+    let mut b = base_event();
+    b["event_id"] = json!({"alg": "sha-256", "b64": "ignored"});
+
+    let diff = explain_diff(&base_event(), &b, &canonicalizer()).unwrap();
+    assert!(diff.differences.is_empty());
+    assert_eq!(diff.event_id_a, diff.event_id_b);
+}
+
+#[test]
+fn reports_missing_and_extra_keys() {
+    let mut a = base_event();
+    a["only_a"] = json!("x");
+    let mut b = base_event();
+    b["nested"] = json!({"a/b": true});
+
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    assert_ne!(diff.event_id_a, diff.event_id_b);
+    assert_eq!(diff.differences.len(), 2);
+    assert_eq!(diff.differences[0].pointer, "/only_a");
+    assert!(matches!(
+        diff.differences[0].kind,
+        DifferenceKind::Missing { .. }
+    ));
+    assert_eq!(diff.differences[1].pointer, "/nested");
+    assert!(matches!(
+        diff.differences[1].kind,
+        DifferenceKind::Extra { .. }
+    ));
+}
+
+#[test]
+fn reports_type_change_with_pointer_escaping() {
+    let mut a = base_event();
+    a["data"] = json!({"a/b": [1, {"c~d": 5}]});
+    let mut b = base_event();
+    b["data"] = json!({"a/b": [1, {"c~d": "5"}]});
+
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    assert_eq!(diff.differences.len(), 1);
+    assert_eq!(diff.differences[0].pointer, "/data/a~1b/1/c~0d");
+    assert_eq!(
+        diff.differences[0].kind,
+        DifferenceKind::TypeChanged {
+            a_type: "number".into(),
+            b_type: "string".into()
+        }
+    );
+}
+
+#[test]
+fn reports_number_formatting() {
+    let mut a = base_event();
+    a["amount"] = json!(100);
+    let mut b = base_event();
+    b["amount"] = json!(100.0);
+
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    assert_ne!(diff.event_id_a, diff.event_id_b);
+    assert_eq!(
+        diff.differences[0].kind,
+        DifferenceKind::NumberFormat {
+            a: "100".into(),
+            b: "100.0".into()
+        }
+    );
+}
+
+#[test]
+fn distinct_large_integers_are_value_changes() {
+    // Both round to the same f64, but are different integers
+    let mut a = base_event();
+    a["amount"] = json!(9007199254740993u64);
+    let mut b = base_event();
+    b["amount"] = json!(9007199254740992u64);
+
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    assert_eq!(
+        diff.differences[0].kind,
+        DifferenceKind::ValueChanged {
+            a: json!(9007199254740993u64),
+            b: json!(9007199254740992u64)
+        }
+    );
+
+    // A float only matches an integer it represents exactly
+    b["amount"] = json!(9007199254740992.0);
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    assert!(matches!(
+        diff.differences[0].kind,
+        DifferenceKind::ValueChanged { .. }
+    ));
+}
+
+#[test]
+fn reports_unicode_normalization_in_values_and_keys() {
+    let mut a = base_event();
+    a["name"] = json!("caf\u{00e9}");
+    a["r\u{00e9}sum\u{00e9}"] = json!(1);
+    let mut b = base_event();
+    b["name"] = json!("cafe\u{0301}");
+    b["re\u{0301}sume\u{0301}"] = json!(1);
+
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    assert!(!diff.canonical_equal);
+    assert_eq!(diff.differences.len(), 2);
+    assert!(diff
+        .differences
+        .iter()
+        .all(|d| matches!(d.kind, DifferenceKind::UnicodeNormalization { .. })));
+}
+
+#[test]
+fn reports_array_length_changes() {
+    let mut a = base_event();
+    a["items"] = json!(["x", "y", "z"]);
+    let mut b = base_event();
+    b["items"] = json!(["x"]);
+
+    let diff = explain_diff(&a, &b, &canonicalizer()).unwrap();
+    let pointers: Vec<_> = diff
+        .differences
+        .iter()
+        .map(|d| d.pointer.as_str())
+        .collect();
+    assert_eq!(pointers, ["/items/1", "/items/2"]);
+}
+
+#[test]
+fn diff_serializes_with_kind_tag() {
+    let mut b = base_event();
+    b["event_type"] = json!("other");
+
+    let diff = explain_diff(&base_event(), &b, &canonicalizer()).unwrap();
+    let value = serde_json::to_value(&diff.differences[0]).unwrap();
+    assert_eq!(
+        value,
+        json!({"pointer": "/event_type", "kind": "value_changed", "a": "test", "b": "other"})
+    );
+}
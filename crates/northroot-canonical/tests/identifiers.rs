@@ -0,0 +1,214 @@
+use northroot_canonical::{
+    compute_event_id, Canonicalizer, ContentId, EventId, PrincipalDelegation, PrincipalId,
+    PrincipalKind, ProfileId, RecordId, Timestamp, ToolName, ToolPattern,
+};
+use serde_json::json;
+
+#[test]
+fn principal_id_exposes_kind_and_name() {
+    let id = PrincipalId::parse("agent:nr-assistant-1").unwrap();
+    assert_eq!(id.kind(), Some(PrincipalKind::Agent));
+    assert_eq!(id.name(), Some("nr-assistant-1"));
+}
+
+#[test]
+fn principal_id_from_parts_round_trips() {
+    for kind in PrincipalKind::ALL {
+        let id = PrincipalId::from_parts(*kind, "example").unwrap();
+        assert_eq!(id.as_ref(), format!("{}:example", kind));
+        assert_eq!(id.kind(), Some(*kind));
+    }
+}
+
+#[test]
+fn principal_id_rejects_unknown_kind_and_bad_name() {
+    assert!(PrincipalId::parse("robot:r2").is_err());
+    assert!(PrincipalId::parse("service:Billing").is_err());
+    assert!(PrincipalId::parse("service").is_err());
+    assert!(PrincipalId::from_parts(PrincipalKind::Org, "").is_err());
+}
+
+#[test]
+fn unchecked_principal_id_has_no_kind() {
+    let id = PrincipalId::new("not-a-principal".into());
+    assert_eq!(id.kind(), None);
+    assert_eq!(id.name(), None);
+}
+
+#[test]
+fn principal_kind_serializes_lowercase() {
+    assert_eq!(
+        serde_json::to_value(PrincipalKind::Service).unwrap(),
+        json!("service")
+    );
+    assert_eq!("org".parse::<PrincipalKind>().unwrap(), PrincipalKind::Org);
+}
+
+#[test]
+fn principal_delegation_serializes_as_pair() {
+    let delegation = PrincipalDelegation::new(
+        PrincipalId::parse("agent:planner").unwrap(),
+        PrincipalId::parse("org:acme").unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&delegation).unwrap(),
+        json!({"actor": "agent:planner", "on_behalf_of": "org:acme"})
+    );
+}
+
+#[test]
+fn principal_delegation_rejects_self_and_malformed() {
+    let id = PrincipalId::parse("agent:planner").unwrap();
+    assert!(PrincipalDelegation::new(id.clone(), id.clone()).is_err());
+    assert!(PrincipalDelegation::new(id, PrincipalId::new("bogus".into())).is_err());
+}
+
+#[test]
+fn principal_delegation_deserialization_validates() {
+    let ok: PrincipalDelegation =
+        serde_json::from_value(json!({"actor": "agent:planner", "on_behalf_of": "org:acme"}))
+            .unwrap();
+    assert_eq!(ok.on_behalf_of.as_ref(), "org:acme");
+    for bad in [
+        json!({"actor": "agent:planner", "on_behalf_of": "agent:planner"}),
+        json!({"actor": "agent:planner", "on_behalf_of": "bogus"}),
+    ] {
+        assert!(
+            serde_json::from_value::<PrincipalDelegation>(bad.clone()).is_err(),
+            "accepted {bad}"
+        );
+    }
+}
+
+fn tool(name: &str) -> ToolName {
+    ToolName::parse(name).unwrap()
+}
+
+fn pattern(p: &str) -> ToolPattern {
+    ToolPattern::parse(p).unwrap()
+}
+
+#[test]
+fn tool_pattern_exact_match() {
+    assert!(tool("llm.generate").is_within(&pattern("llm.generate")));
+    assert!(!tool("llm.generate.stream").is_within(&pattern("llm.generate")));
+    assert!(!tool("llm").is_within(&pattern("llm.generate")));
+}
+
+#[test]
+fn tool_pattern_prefix_is_segment_aware() {
+    let p = pattern("llm.*");
+    assert!(tool("llm.generate").is_within(&p));
+    assert!(tool("llm.embed.batch").is_within(&p));
+    assert!(!tool("llm").is_within(&p));
+    assert!(!tool("llmx.generate").is_within(&p));
+}
+
+#[test]
+fn tool_pattern_single_segment_wildcard() {
+    let p = pattern("llm.*.stream");
+    assert!(tool("llm.generate.stream").is_within(&p));
+    assert!(!tool("llm.generate.batch.stream").is_within(&p));
+    assert!(!tool("llm.stream").is_within(&p));
+}
+
+#[test]
+fn tool_pattern_lone_wildcard_matches_everything() {
+    assert!(tool("canon.hash").is_within(&pattern("*")));
+    assert!(tool("x").is_within(&pattern("*")));
+}
+
+#[test]
+fn tool_pattern_rejects_malformed() {
+    for bad in [
+        "",
+        "llm.",
+        ".llm",
+        "llm.**",
+        "LLM.generate",
+        "llm.gen*",
+        "a.b.c.d.e.f.g.h.i",
+    ] {
+        assert!(ToolPattern::parse(bad).is_err(), "accepted {bad:?}");
+    }
+}
+
+#[test]
+fn tool_pattern_enforces_schema_max_length() {
+    // Two long segments and a wildcard, 64 characters in all
+    let longest = format!("{}.{}.*", "a".repeat(30), "b".repeat(31));
+    assert_eq!(longest.len(), 64);
+    assert!(ToolPattern::parse(longest.as_str()).is_ok());
+    let too_long = format!("{}.{}.*", "a".repeat(31), "b".repeat(31));
+    assert!(ToolPattern::parse(too_long.as_str()).is_err());
+    assert!(serde_json::from_value::<ToolPattern>(json!(too_long)).is_err());
+}
+
+#[test]
+fn tool_pattern_deserialization_validates() {
+    let ok: Vec<ToolPattern> = serde_json::from_value(json!(["llm.*", "canon.hash"])).unwrap();
+    assert_eq!(ok[0].as_ref(), "llm.*");
+    assert!(serde_json::from_value::<ToolPattern>(json!("llm..x")).is_err());
+}
+
+#[test]
+fn tool_allowlist_empty_permits_nothing() {
+    let allowlist = vec![pattern("canon.*"), pattern("llm.generate")];
+    assert!(tool("canon.hash").is_within_any(&allowlist));
+    assert!(!tool("llm.embed").is_within_any(&allowlist));
+    assert!(!tool("canon.hash").is_within_any(&Vec::new()));
+}
+
+#[test]
+fn record_id_v7_is_time_ordered_and_extractable() {
+    let first = RecordId::new_v7();
+    let second = RecordId::new_v7();
+    assert_eq!(first.version(), 7);
+    assert!(first.unix_millis().unwrap() <= second.unix_millis().unwrap());
+    assert!(Timestamp::parse(first.timestamp().unwrap().as_ref().to_string()).is_ok());
+}
+
+#[test]
+fn record_id_parses_v4_without_timestamp() {
+    let id = RecordId::parse("9f1c2b7e-3d4a-4c5b-8e6f-0a1b2c3d4e5f").unwrap();
+    assert_eq!(id.version(), 4);
+    assert_eq!(id.unix_millis(), None);
+    assert_eq!(
+        serde_json::to_value(id).unwrap(),
+        json!("9f1c2b7e-3d4a-4c5b-8e6f-0a1b2c3d4e5f")
+    );
+}
+
+#[test]
+fn record_id_rejects_other_versions_and_forms() {
+    // v1 UUID
+    assert!(RecordId::parse("6ba7b810-9dad-11d1-80b4-00c04fd430c8").is_err());
+    // Unhyphenated form
+    assert!(RecordId::parse("9f1c2b7e3d4a4c5b8e6f0a1b2c3d4e5f").is_err());
+    assert!(serde_json::from_value::<RecordId>(json!("not-a-uuid")).is_err());
+}
+
+#[test]
+fn event_id_and_content_id_wrap_digests_transparently() {
+    let canonicalizer = Canonicalizer::new(ProfileId::parse("northroot-canonical-v1").unwrap());
+    let event = json!({
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": "service:example",
+        "canonical_profile_id": "northroot-canonical-v1"
+    });
+
+    let event_id = EventId::compute(&event, &canonicalizer).unwrap();
+    let digest = compute_event_id(&event, &canonicalizer).unwrap();
+    assert_eq!(event_id.digest(), &digest);
+    assert_eq!(
+        serde_json::to_value(&event_id).unwrap(),
+        serde_json::to_value(&digest).unwrap()
+    );
+
+    let content_id = ContentId::from_digest(digest.clone());
+    assert_eq!(content_id.into_digest(), digest);
+}
@@ -0,0 +1,200 @@
+//! Running hash chain over journal frames.
+//!
+//! In journals with [`FLAG_HASH_CHAIN`](crate::frame::FLAG_HASH_CHAIN) every frame
+//! stores the chain value of all frames before it:
+//!
+//! ```text
+//! chain_0     = sha256(CHAIN_DOMAIN || file_header)
+//! chain_{n+1} = sha256(CHAIN_DOMAIN || chain_n || frame_header_n || payload_n)
+//! ```
+//!
+//! Deleting, reordering or inserting a frame changes every later chain value, so
+//! the first frame whose stored value disagrees with the recomputed one marks the
+//! break. The chain is unkeyed: it proves the file is internally consistent, and
+//! the head must be anchored elsewhere (e.g. in a checkpoint event) to detect a
+//! wholesale rewrite or a truncated tail.
+
+use crate::errors::JournalError;
+use crate::frame::{JournalHeader, RecordFrame, FRAME_HEADER_SIZE, HEADER_SIZE};
+use northroot_canonical::{Digest, DigestAlg};
+use sha2::{Digest as Sha2Digest, Sha256};
+
+/// Domain separator for chain hashing: `b"northroot:journal-chain:v1\0"`.
+const CHAIN_DOMAIN: &[u8] = b"northroot:journal-chain:v1\0";
+
+/// Size of a chain value in bytes (SHA-256).
+pub const CHAIN_HASH_SIZE: usize = 32;
+
+/// Size of a [`ChainHead`] trailer payload: chain value + frame count.
+pub const CHAIN_HEAD_PAYLOAD_SIZE: usize = CHAIN_HASH_SIZE + 8;
+
+/// Raw chain value.
+pub type ChainHash = [u8; CHAIN_HASH_SIZE];
+
+/// Returns the chain value before the first frame, bound to the file header.
+pub fn genesis(header: &JournalHeader) -> ChainHash {
+    let mut hasher = Sha256::new();
+    hasher.update(CHAIN_DOMAIN);
+    hasher.update(header.to_bytes());
+    debug_assert_eq!(header.to_bytes().len(), HEADER_SIZE);
+    hasher.finalize().into()
+}
+
+/// Advances the chain over one frame.
+pub fn next(prev: &ChainHash, frame_header: &[u8; FRAME_HEADER_SIZE], payload: &[u8]) -> ChainHash {
+    let mut hasher = Sha256::new();
+    hasher.update(CHAIN_DOMAIN);
+    hasher.update(prev);
+    hasher.update(frame_header);
+    hasher.update(payload);
+    hasher.finalize().into()
+}
+
+/// Converts a raw chain value to a [`Digest`] for display and anchoring.
+pub fn to_digest(hash: &ChainHash) -> Digest {
+    use base64::Engine;
+    Digest {
+        alg: DigestAlg::Sha256,
+        b64: base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(hash),
+    }
+}
+
+/// Converts a [`Digest`] made by [`to_digest`] back to a raw chain value.
+///
+/// Returns `None` if the digest is not a SHA-256 value.
+pub fn from_digest(digest: &Digest) -> Option<ChainHash> {
+    use base64::Engine;
+    if digest.alg != DigestAlg::Sha256 {
+        return None;
+    }
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(&digest.b64)
+        .ok()?;
+    bytes.try_into().ok()
+}
+
+/// Payload of a `ChainHead` trailer frame.
+///
+/// The writer appends a trailer when it finishes so that a later writer can
+/// resume the chain without rescanning the file. `head` commits to the first
+/// `frames` frames, i.e. every frame before the trailer itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainHead {
+    /// Chain value covering all frames before the trailer.
+    pub head: ChainHash,
+    /// Number of frames before the trailer.
+    pub frames: u64,
+}
+
+impl ChainHead {
+    /// Serializes the trailer payload.
+    pub fn to_bytes(&self) -> [u8; CHAIN_HEAD_PAYLOAD_SIZE] {
+        let mut bytes = [0u8; CHAIN_HEAD_PAYLOAD_SIZE];
+        bytes[..CHAIN_HASH_SIZE].copy_from_slice(&self.head);
+        bytes[CHAIN_HASH_SIZE..].copy_from_slice(&self.frames.to_le_bytes());
+        bytes
+    }
+
+    /// Deserializes a trailer payload.
+    pub fn from_bytes(bytes: &[u8], offset: u64) -> Result<Self, JournalError> {
+        if bytes.len() != CHAIN_HEAD_PAYLOAD_SIZE {
+            return Err(JournalError::InvalidFrame {
+                offset,
+                reason: format!(
+                    "chain head payload must be {} bytes",
+                    CHAIN_HEAD_PAYLOAD_SIZE
+                ),
+            });
+        }
+        let mut head = [0u8; CHAIN_HASH_SIZE];
+        head.copy_from_slice(&bytes[..CHAIN_HASH_SIZE]);
+        let frames = u64::from_le_bytes(bytes[CHAIN_HASH_SIZE..].try_into().expect("8 bytes"));
+        Ok(Self { head, frames })
+    }
+
+    /// Returns the on-disk size of a trailer frame under `header`'s layout.
+    pub fn frame_len(header: &JournalHeader) -> usize {
+        header.frame_header_len() + CHAIN_HEAD_PAYLOAD_SIZE
+    }
+}
+
+/// Progress of chain verification, as tracked by readers and writers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ChainState {
+    /// Chain value covering every frame seen so far.
+    pub head: ChainHash,
+    /// Number of frames seen so far.
+    pub frames: u64,
+}
+
+impl ChainState {
+    pub(crate) fn new(header: &JournalHeader) -> Self {
+        Self {
+            head: genesis(header),
+            frames: 0,
+        }
+    }
+
+    /// Checks a frame's stored chain value and advances past it.
+    pub(crate) fn advance(
+        &mut self,
+        offset: u64,
+        frame: &RecordFrame,
+        stored_prev: &ChainHash,
+        payload: &[u8],
+    ) -> Result<(), JournalError> {
+        if stored_prev != &self.head {
+            return Err(JournalError::ChainBroken {
+                offset,
+                ordinal: self.frames,
+            });
+        }
+        self.head = next(&self.head, &frame.to_bytes(), payload);
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{FrameKind, FLAG_HASH_CHAIN};
+
+    #[test]
+    fn chain_head_round_trip() {
+        let head = ChainHead {
+            head: [7u8; CHAIN_HASH_SIZE],
+            frames: 42,
+        };
+        assert_eq!(ChainHead::from_bytes(&head.to_bytes(), 0).unwrap(), head);
+        assert!(ChainHead::from_bytes(&[0u8; 12], 0).is_err());
+    }
+
+    #[test]
+    fn chain_depends_on_order() {
+        let header = JournalHeader::with_flags(FLAG_HASH_CHAIN).unwrap();
+        let a = RecordFrame::new(FrameKind::EventJson, 1)
+            .unwrap()
+            .to_bytes();
+        let g = genesis(&header);
+        let ab = next(&next(&g, &a, b"a"), &a, b"b");
+        let ba = next(&next(&g, &a, b"b"), &a, b"a");
+        assert_ne!(ab, ba);
+        assert_ne!(genesis(&JournalHeader::new()), g);
+    }
+
+    #[test]
+    fn chain_state_reports_first_break() {
+        let header = JournalHeader::with_flags(FLAG_HASH_CHAIN).unwrap();
+        let frame = RecordFrame::new(FrameKind::EventJson, 1).unwrap();
+        let mut state = ChainState::new(&header);
+        let g = state.head;
+        state.advance(16, &frame, &g, b"a").unwrap();
+        match state.advance(100, &frame, &g, b"b") {
+            Err(JournalError::ChainBroken { offset, ordinal }) => {
+                assert_eq!((offset, ordinal), (100, 1));
+            }
+            other => panic!("expected ChainBroken, got {:?}", other),
+        }
+    }
+}
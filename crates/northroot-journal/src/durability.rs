@@ -0,0 +1,149 @@
+//! Durability modes for journal writers.
+
+use std::time::{Duration, Instant};
+
+/// When a [`JournalWriter`](crate::JournalWriter) makes appended frames durable.
+///
+/// Only journals on a [`JournalStorage`](crate::JournalStorage), such as files
+/// opened with [`JournalWriter::open`](crate::JournalWriter::open), are synced;
+/// other sinks are flushed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Durability {
+    /// Flush only; the operating system decides when data reaches the disk
+    /// (the default).
+    #[default]
+    None,
+    /// `fdatasync` after every frame: the data and file size are durable, other
+    /// metadata such as the modification time may not be.
+    Data,
+    /// `fsync` after every frame.
+    Full,
+    /// `fdatasync` once `every_n` frames were appended or `every_ms`
+    /// milliseconds passed since the last sync, whichever comes first. A zero
+    /// disables that trigger.
+    ///
+    /// Time is only checked when a frame is appended, and
+    /// [`finish`](crate::JournalWriter::finish) syncs whatever is left. A crash
+    /// loses at most the frames since the last sync.
+    Group {
+        /// Sync after this many frames.
+        every_n: u32,
+        /// Sync once this many milliseconds passed since the last sync.
+        every_ms: u64,
+    },
+}
+
+/// How far a sync goes; see [`JournalStorage::sync`](crate::JournalStorage::sync).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncKind {
+    /// `fdatasync`: data and file size.
+    Data,
+    /// `fsync`: data and all metadata.
+    Full,
+}
+
+/// Tracks frames appended since the last sync and decides when to sync.
+#[derive(Debug)]
+pub(crate) struct SyncSchedule {
+    durability: Durability,
+    pending: u32,
+    last_sync: Instant,
+}
+
+impl SyncSchedule {
+    pub(crate) fn new(durability: Durability) -> Self {
+        Self {
+            durability,
+            pending: 0,
+            last_sync: Instant::now(),
+        }
+    }
+
+    /// Records an appended frame and returns the sync that is due now, if any.
+    pub(crate) fn record(&mut self, now: Instant) -> Option<SyncKind> {
+        self.pending = self.pending.saturating_add(1);
+        match self.durability {
+            Durability::None => None,
+            Durability::Data => Some(SyncKind::Data),
+            Durability::Full => Some(SyncKind::Full),
+            Durability::Group { every_n, every_ms } => {
+                let by_count = every_n > 0 && self.pending >= every_n;
+                let by_time = every_ms > 0
+                    && now.saturating_duration_since(self.last_sync)
+                        >= Duration::from_millis(every_ms);
+                (by_count || by_time).then_some(SyncKind::Data)
+            }
+        }
+    }
+
+    /// Forgets the last `frames` recorded frames, which were rolled back.
+    pub(crate) fn forget(&mut self, frames: u32) {
+        self.pending = self.pending.saturating_sub(frames);
+    }
+
+    /// Returns the sync needed before closing, if any frames are unsynced.
+    pub(crate) fn remaining(&self) -> Option<SyncKind> {
+        if self.pending == 0 {
+            return None;
+        }
+        match self.durability {
+            Durability::None => None,
+            Durability::Full => Some(SyncKind::Full),
+            Durability::Data | Durability::Group { .. } => Some(SyncKind::Data),
+        }
+    }
+
+    /// Records that everything appended so far is durable.
+    pub(crate) fn synced(&mut self, now: Instant) {
+        self.pending = 0;
+        self.last_sync = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_frame_modes_sync_every_frame() {
+        let now = Instant::now();
+        let mut schedule = SyncSchedule::new(Durability::Data);
+        assert_eq!(schedule.record(now), Some(SyncKind::Data));
+        let mut schedule = SyncSchedule::new(Durability::Full);
+        assert_eq!(schedule.record(now), Some(SyncKind::Full));
+        let mut schedule = SyncSchedule::new(Durability::None);
+        assert_eq!(schedule.record(now), None);
+        assert_eq!(schedule.remaining(), None);
+    }
+
+    #[test]
+    fn group_syncs_by_count() {
+        let now = Instant::now();
+        let mut schedule = SyncSchedule::new(Durability::Group {
+            every_n: 3,
+            every_ms: 0,
+        });
+        assert_eq!(schedule.record(now), None);
+        assert_eq!(schedule.record(now), None);
+        assert_eq!(schedule.record(now), Some(SyncKind::Data));
+        schedule.synced(now);
+        assert_eq!(schedule.remaining(), None);
+        assert_eq!(schedule.record(now), None);
+        assert_eq!(schedule.remaining(), Some(SyncKind::Data));
+    }
+
+    #[test]
+    fn group_syncs_by_time() {
+        let start = Instant::now();
+        let mut schedule = SyncSchedule::new(Durability::Group {
+            every_n: 0,
+            every_ms: 10,
+        });
+        schedule.synced(start);
+        assert_eq!(schedule.record(start + Duration::from_millis(5)), None);
+        assert_eq!(
+            schedule.record(start + Duration::from_millis(10)),
+            Some(SyncKind::Data)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::validation::Rejection;
 use thiserror::Error;
 
 /// Errors that can occur during journal operations.
@@ -37,10 +38,77 @@ pub enum JournalError {
     /// Attempted to write to a non-empty file without proper initialization.
     #[error("file is not empty; cannot initialize header")]
     FileNotEmpty,
+    /// Frame CRC32C does not match its header and payload (v2 checksummed journals).
+    #[error("checksum mismatch in frame at offset {offset}")]
+    ChecksumMismatch {
+        /// Byte offset where the frame starts.
+        offset: u64,
+    },
+    /// Stored hash chain value disagrees with the recomputed chain (hash-chained journals).
+    #[error("hash chain broken at frame {ordinal} (offset {offset})")]
+    ChainBroken {
+        /// Byte offset where the frame starts.
+        offset: u64,
+        /// Zero-based index of the frame in the journal.
+        ordinal: u64,
+    },
     /// Truncated frame detected in strict mode.
     #[error("truncated frame at offset {offset}")]
     TruncatedFrame {
         /// Byte offset where truncation occurred.
         offset: u64,
     },
+    /// `EventJson` payload is not in canonical form in a journal that requires it.
+    #[error("non-canonical event payload in frame at offset {offset}")]
+    NonCanonicalPayload {
+        /// Byte offset where the frame starts.
+        offset: u64,
+    },
+    /// A frame of a hash-chained journal was encoded without the chain value
+    /// of the frame before it.
+    #[error("hash-chained frame requires the previous chain value")]
+    MissingChainValue,
+    /// Journal is locked by another writer or reader (see [`LockPolicy`](crate::LockPolicy)).
+    #[error("journal is locked by another process")]
+    Locked,
+    /// Journal ends in a partial or damaged frame, so appending is refused.
+    #[error("journal tail is torn at offset {offset}; repair the journal before appending")]
+    TornTail {
+        /// Byte offset where the damaged frame starts.
+        offset: u64,
+    },
+    /// Journal is damaged in the middle: valid frames follow the damage, so
+    /// cutting the journal back to its valid prefix would discard them.
+    #[error(
+        "journal is damaged at offset {offset} but valid frames resume at offset {resumes_at}; \
+         truncating would discard them"
+    )]
+    DamagedMidFile {
+        /// Byte offset where the damaged frame starts.
+        offset: u64,
+        /// Byte offset of the first valid frame after the damage.
+        resumes_at: u64,
+    },
+    /// Event failed the writer's [`ValidationPolicy`](crate::ValidationPolicy)
+    /// and was not written.
+    #[error("event rejected: {0}")]
+    Rejected(#[from] Rejection),
+    /// A batch of frames was only partly written and could not be rolled
+    /// back. The first `committed` events of the batch are in the journal;
+    /// the rest are not.
+    #[error("batch interrupted after {committed} events: {source}")]
+    BatchInterrupted {
+        /// Number of the batch's events that were written completely.
+        committed: u64,
+        /// The I/O error that interrupted the batch.
+        source: std::io::Error,
+    },
+    /// The journal no longer starts with the bytes recorded in a
+    /// [`VerifiedPrefix`](crate::VerifiedPrefix): it was shortened or
+    /// rewritten since it was last verified.
+    #[error("journal changed within its verified prefix of {len} bytes")]
+    PrefixChanged {
+        /// Length of the verified prefix.
+        len: u64,
+    },
 }
@@ -1,3 +1,5 @@
+use crate::errors::JournalError;
+use northroot_canonical::{Canonicalizer, ProfileId};
 use serde_json::Value;
 
 /// Event JSON payload type.
@@ -7,21 +9,67 @@ use serde_json::Value;
 /// and verification happen via `northroot-core`.
 pub type EventJson = Value;
 
+/// Top-level fields every event must have.
+pub(crate) const REQUIRED_FIELDS: [&str; 6] = [
+    "event_id",
+    "event_type",
+    "event_version",
+    "occurred_at",
+    "principal_id",
+    "canonical_profile_id",
+];
+
 /// Helper to validate that a JSON value is a valid event object.
 ///
 /// This performs basic structural checks (is an object, has required fields).
 /// Full verification (event_id computation, canonicalization) should be done
-/// via `northroot-core::Verifier`.
+/// via `northroot-core::Verifier`, or by a writer's
+/// [`ValidationPolicy`](crate::ValidationPolicy).
 pub fn is_valid_event_structure(value: &EventJson) -> bool {
     let Some(obj) = value.as_object() else {
         return false;
     };
 
     // Check for required top-level fields
-    obj.contains_key("event_id")
-        && obj.contains_key("event_type")
-        && obj.contains_key("event_version")
-        && obj.contains_key("occurred_at")
-        && obj.contains_key("principal_id")
-        && obj.contains_key("canonical_profile_id")
+    REQUIRED_FIELDS.iter().all(|field| obj.contains_key(*field))
+}
+
+/// Returns the canonical (RFC 8785) bytes of `event`, including its `event_id`.
+///
+/// Journals with [`FLAG_CANONICAL_PAYLOAD`](crate::frame::FLAG_CANONICAL_PAYLOAD)
+/// store exactly these bytes, so journals holding the same events are
+/// byte-identical.
+///
+/// # Errors
+///
+/// Returns [`JournalError::InvalidJson`] if the event cannot be canonicalized.
+pub fn canonical_payload(event: &EventJson) -> Result<Vec<u8>, JournalError> {
+    // Canonical bytes do not depend on the profile
+    let canonicalizer = Canonicalizer::new(ProfileId::new("northroot-canonical-v1".to_string()));
+    canonicalizer
+        .canonicalize(event)
+        .map(|result| result.bytes)
+        .map_err(|e| JournalError::InvalidJson(format!("canonicalization failed: {}", e)))
+}
+
+/// Checks that `payload` is the canonical encoding of the event it holds.
+pub(crate) fn is_canonical_payload(payload: &[u8], event: &EventJson) -> bool {
+    canonical_payload(event).is_ok_and(|canonical| canonical == payload)
+}
+
+/// Fails with [`JournalError::NonCanonicalPayload`] unless the payload of the
+/// frame at `offset` is the canonical encoding of `event`.
+///
+/// Strict readers apply this to every event in a journal flagged with
+/// [`FLAG_CANONICAL_PAYLOAD`](crate::frame::FLAG_CANONICAL_PAYLOAD).
+pub(crate) fn ensure_canonical_payload(
+    payload: &[u8],
+    event: &EventJson,
+    offset: u64,
+) -> Result<(), JournalError> {
+    if is_canonical_payload(payload, event) {
+        Ok(())
+    } else {
+        Err(JournalError::NonCanonicalPayload { offset })
+    }
 }
@@ -1,11 +1,28 @@
+use crate::chain::{ChainHash, CHAIN_HASH_SIZE};
 use crate::errors::JournalError;
 
 /// Journal file magic bytes: `b"NRJ1"`.
 pub const MAGIC: &[u8; 4] = b"NRJ1";
 
-/// Current journal format version: `0x0001`.
+/// Journal format version 1: `0x0001` (flags must be zero).
 pub const VERSION: u16 = 0x0001;
 
+/// Journal format version 2: `0x0002` (feature flags in [`JournalHeader::flags`]).
+pub const VERSION_2: u16 = 0x0002;
+
+/// Header flag (v2): every frame carries a CRC32C of its header and payload.
+pub const FLAG_FRAME_CRC32C: u16 = 0x0001;
+
+/// Header flag (v2): every frame carries the running hash chain of all previous frames.
+pub const FLAG_HASH_CHAIN: u16 = 0x0002;
+
+/// Header flag (v2): every `EventJson` payload is exactly the canonical (RFC 8785)
+/// bytes of its event, including `event_id`.
+pub const FLAG_CANONICAL_PAYLOAD: u16 = 0x0004;
+
+/// All header flags understood by this implementation.
+pub const KNOWN_FLAGS: u16 = FLAG_FRAME_CRC32C | FLAG_HASH_CHAIN | FLAG_CANONICAL_PAYLOAD;
+
 /// Header size in bytes: 16 bytes.
 pub const HEADER_SIZE: usize = 16;
 
@@ -22,20 +39,29 @@ impl RecordFrame {
     pub const FRAME_HEADER_SIZE: usize = 8;
 }
 
+/// Size of the CRC32C field that follows the frame header in checksummed journals.
+pub const FRAME_CHECKSUM_SIZE: usize = 4;
+
 /// Maximum recommended payload size: 16 MiB.
 pub const MAX_PAYLOAD_SIZE: u32 = 16 * 1024 * 1024;
 
 /// Record frame kind: EventJson.
 pub const FRAME_KIND_EVENT_JSON: u8 = 0x01;
 
+/// Record frame kind: ChainHead trailer (hash-chained journals).
+pub const FRAME_KIND_CHAIN_HEAD: u8 = 0x02;
+
+/// Record frame kind: Index segment mapping event ordinals and IDs to offsets.
+pub const FRAME_KIND_INDEX: u8 = 0x03;
+
 /// Journal file header (16 bytes).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JournalHeader {
     /// Magic bytes: `"NRJ1"`.
     pub magic: [u8; 4],
-    /// Format version: `0x0001`.
+    /// Format version: `0x0001` or `0x0002`.
     pub version: u16,
-    /// Reserved flags (must be 0).
+    /// Feature flags (must be 0 in v1; see `FLAG_*` constants for v2).
     pub flags: u16,
     /// Reserved bytes (must be all zeros).
     pub reserved: [u8; 8],
@@ -52,6 +78,110 @@ impl JournalHeader {
         }
     }
 
+    /// Creates a header with the given feature flags.
+    ///
+    /// Returns a v1 header when `flags` is zero so that journals without
+    /// optional features stay readable by v1-only readers.
+    pub fn with_flags(flags: u16) -> Result<Self, JournalError> {
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(JournalError::InvalidHeader(format!(
+                "unknown flags: 0x{:04x}",
+                flags & !KNOWN_FLAGS
+            )));
+        }
+        Ok(Self {
+            version: if flags == 0 { VERSION } else { VERSION_2 },
+            flags,
+            ..Self::new()
+        })
+    }
+
+    /// Returns `true` if frames carry a CRC32C checksum.
+    pub fn has_checksums(&self) -> bool {
+        self.flags & FLAG_FRAME_CRC32C != 0
+    }
+
+    /// Returns `true` if frames carry the running hash chain.
+    pub fn has_hash_chain(&self) -> bool {
+        self.flags & FLAG_HASH_CHAIN != 0
+    }
+
+    /// Returns `true` if `EventJson` payloads must be canonical bytes.
+    pub fn has_canonical_payloads(&self) -> bool {
+        self.flags & FLAG_CANONICAL_PAYLOAD != 0
+    }
+
+    /// Returns the on-disk size of a frame header under this journal's flags.
+    pub fn frame_header_len(&self) -> usize {
+        let mut len = FRAME_HEADER_SIZE;
+        if self.has_checksums() {
+            len += FRAME_CHECKSUM_SIZE;
+        }
+        if self.has_hash_chain() {
+            len += CHAIN_HASH_SIZE;
+        }
+        len
+    }
+
+    /// Encodes a complete frame (header, extension fields and payload).
+    ///
+    /// `prev_chain` is required in hash-chained journals and ignored otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError::MissingChainValue`] if the journal is
+    /// hash-chained and `prev_chain` is `None`.
+    pub fn encode_frame(
+        &self,
+        frame: &RecordFrame,
+        prev_chain: Option<&ChainHash>,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, JournalError> {
+        let base = frame.to_bytes();
+        let chain: &[u8] = match (self.has_hash_chain(), prev_chain) {
+            (true, Some(prev)) => prev,
+            (true, None) => return Err(JournalError::MissingChainValue),
+            (false, _) => &[],
+        };
+        let mut bytes = Vec::with_capacity(self.frame_header_len() + payload.len());
+        bytes.extend_from_slice(&base);
+        if self.has_checksums() {
+            bytes.extend_from_slice(&frame_checksum(&base, chain, payload).to_le_bytes());
+        }
+        bytes.extend_from_slice(chain);
+        bytes.extend_from_slice(payload);
+        Ok(bytes)
+    }
+
+    /// Decodes a frame header of [`frame_header_len`](Self::frame_header_len) bytes.
+    ///
+    /// `offset` is the frame's position in the file and is used for error reporting.
+    pub fn decode_frame_header(
+        &self,
+        bytes: &[u8],
+        offset: u64,
+    ) -> Result<(RecordFrame, FrameExtension), JournalError> {
+        let frame = RecordFrame::from_bytes(bytes).map_err(|e| match e {
+            JournalError::InvalidFrame { offset: _, reason } => {
+                JournalError::InvalidFrame { offset, reason }
+            }
+            other => other,
+        })?;
+        let mut ext = FrameExtension::default();
+        let mut rest = &bytes[FRAME_HEADER_SIZE..self.frame_header_len()];
+        if self.has_checksums() {
+            let (crc, tail) = rest.split_at(FRAME_CHECKSUM_SIZE);
+            ext.crc32c = Some(u32::from_le_bytes(crc.try_into().expect("4 bytes")));
+            rest = tail;
+        }
+        if self.has_hash_chain() {
+            let mut prev = [0u8; CHAIN_HASH_SIZE];
+            prev.copy_from_slice(&rest[..CHAIN_HASH_SIZE]);
+            ext.prev_chain = Some(prev);
+        }
+        Ok((frame, ext))
+    }
+
     /// Serializes the header to bytes.
     pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
         let mut bytes = [0u8; HEADER_SIZE];
@@ -80,20 +210,26 @@ impl JournalHeader {
         }
 
         let version = u16::from_le_bytes([bytes[4], bytes[5]]);
-        if version != VERSION {
+        if version != VERSION && version != VERSION_2 {
             return Err(JournalError::InvalidHeader(format!(
-                "unsupported version: 0x{:04x}, expected 0x{:04x}",
-                version, VERSION
+                "unsupported version: 0x{:04x}, expected 0x{:04x} or 0x{:04x}",
+                version, VERSION, VERSION_2
             )));
         }
 
         let flags = u16::from_le_bytes([bytes[6], bytes[7]]);
-        if flags != 0 {
+        if version == VERSION && flags != 0 {
             return Err(JournalError::InvalidHeader(format!(
                 "non-zero flags: 0x{:04x}",
                 flags
             )));
         }
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(JournalError::InvalidHeader(format!(
+                "unknown flags: 0x{:04x}",
+                flags & !KNOWN_FLAGS
+            )));
+        }
 
         let mut reserved = [0u8; 8];
         reserved.copy_from_slice(&bytes[8..16]);
@@ -119,10 +255,17 @@ impl Default for JournalHeader {
 }
 
 /// Record frame kind.
+///
+/// New kinds may be added in minor releases; match with a wildcard arm.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum FrameKind {
     /// EventJson: UTF-8 JSON object representing a canonical event.
     EventJson,
+    /// ChainHead: trailer recording the hash chain head (hash-chained journals).
+    ChainHead,
+    /// Index: segment mapping event ordinals and `event_id`s to byte offsets.
+    Index,
     /// Unknown/unsupported frame kind.
     Unknown(u8),
 }
@@ -132,6 +275,8 @@ impl FrameKind {
     pub fn from_byte(byte: u8) -> Self {
         match byte {
             FRAME_KIND_EVENT_JSON => FrameKind::EventJson,
+            FRAME_KIND_CHAIN_HEAD => FrameKind::ChainHead,
+            FRAME_KIND_INDEX => FrameKind::Index,
             _ => FrameKind::Unknown(byte),
         }
     }
@@ -140,11 +285,41 @@ impl FrameKind {
     pub fn to_byte(self) -> u8 {
         match self {
             FrameKind::EventJson => FRAME_KIND_EVENT_JSON,
+            FrameKind::ChainHead => FRAME_KIND_CHAIN_HEAD,
+            FrameKind::Index => FRAME_KIND_INDEX,
             FrameKind::Unknown(b) => b,
         }
     }
 }
 
+/// Optional fields that follow the 8-byte record header in v2 journals.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameExtension {
+    /// Stored CRC32C (with [`FLAG_FRAME_CRC32C`]).
+    pub crc32c: Option<u32>,
+    /// Stored chain value of all preceding frames (with [`FLAG_HASH_CHAIN`]).
+    pub prev_chain: Option<ChainHash>,
+}
+
+impl FrameExtension {
+    /// Verifies the stored CRC32C, if any, against the frame and payload.
+    pub fn verify_checksum(
+        &self,
+        frame: &RecordFrame,
+        payload: &[u8],
+        offset: u64,
+    ) -> Result<(), JournalError> {
+        let Some(stored) = self.crc32c else {
+            return Ok(());
+        };
+        let chain: &[u8] = self.prev_chain.as_ref().map_or(&[], |c| c.as_slice());
+        if frame_checksum(&frame.to_bytes(), chain, payload) != stored {
+            return Err(JournalError::ChecksumMismatch { offset });
+        }
+        Ok(())
+    }
+}
+
 /// Record frame header (8 bytes).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecordFrame {
@@ -156,6 +331,31 @@ pub struct RecordFrame {
     pub len: u32,
 }
 
+/// Where a frame sits in a journal, returned alongside its payload.
+///
+/// Offsets are absolute byte positions in the journal, so they can be cited in
+/// error reports or stored by external indexes and passed back to
+/// [`JournalReader::seek_offset`](crate::JournalReader::seek_offset).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Zero-based position among all frames of the journal, or `None` after a
+    /// seek, since the reader then no longer knows how many frames precede it.
+    pub ordinal: Option<u64>,
+    /// Offset of the record header.
+    pub offset: u64,
+    /// Record header of the frame.
+    pub header: RecordFrame,
+    /// Payload length in bytes.
+    pub payload_len: u32,
+}
+
+impl FrameInfo {
+    /// Returns the frame kind.
+    pub fn kind(&self) -> FrameKind {
+        self.header.kind
+    }
+}
+
 impl RecordFrame {
     /// Creates a new frame header.
     pub fn new(kind: FrameKind, len: u32) -> Result<Self, JournalError> {
@@ -215,6 +415,16 @@ impl RecordFrame {
     }
 }
 
+/// Computes the CRC32C stored after a frame header in checksummed journals.
+///
+/// The checksum covers the 8-byte frame header, the stored chain value (empty
+/// unless the journal is hash-chained) and the payload, in that order.
+pub fn frame_checksum(frame_header: &[u8; FRAME_HEADER_SIZE], chain: &[u8], payload: &[u8]) -> u32 {
+    let crc = crc32c::crc32c(frame_header);
+    let crc = crc32c::crc32c_append(crc, chain);
+    crc32c::crc32c_append(crc, payload)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,7 +447,7 @@ mod tests {
     #[test]
     fn header_rejects_invalid_version() {
         let mut bytes = JournalHeader::new().to_bytes();
-        bytes[4] = 0x02;
+        bytes[4] = 0x03;
         bytes[5] = 0x00;
         assert!(JournalError::InvalidHeader(
             JournalHeader::from_bytes(&bytes).unwrap_err().to_string()
@@ -253,6 +463,87 @@ mod tests {
         assert!(JournalHeader::from_bytes(&bytes).is_err());
     }
 
+    #[test]
+    fn header_v2_round_trip_with_checksums() {
+        let header = JournalHeader::with_flags(FLAG_FRAME_CRC32C).unwrap();
+        assert_eq!(header.version, VERSION_2);
+        assert!(header.has_checksums());
+        assert_eq!(header.frame_header_len(), FRAME_HEADER_SIZE + 4);
+        let restored = JournalHeader::from_bytes(&header.to_bytes()).unwrap();
+        assert_eq!(header, restored);
+    }
+
+    #[test]
+    fn header_without_flags_stays_v1() {
+        let header = JournalHeader::with_flags(0).unwrap();
+        assert_eq!(header, JournalHeader::new());
+        assert_eq!(header.frame_header_len(), FRAME_HEADER_SIZE);
+    }
+
+    #[test]
+    fn header_v2_rejects_unknown_flags() {
+        assert!(JournalHeader::with_flags(0x8000).is_err());
+        let mut bytes = JournalHeader::with_flags(FLAG_FRAME_CRC32C)
+            .unwrap()
+            .to_bytes();
+        bytes[7] = 0x80;
+        assert!(JournalHeader::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn frame_checksum_covers_header_and_payload() {
+        let header = RecordFrame::new(FrameKind::EventJson, 3)
+            .unwrap()
+            .to_bytes();
+        let crc = frame_checksum(&header, &[], b"abc");
+        assert_ne!(crc, frame_checksum(&header, &[], b"abd"));
+        assert_ne!(crc, frame_checksum(&header, &[0u8; 32], b"abc"));
+        let other = RecordFrame::new(FrameKind::Unknown(0x7f), 3)
+            .unwrap()
+            .to_bytes();
+        assert_ne!(crc, frame_checksum(&other, &[], b"abc"));
+    }
+
+    #[test]
+    fn frame_encode_decode_round_trip_all_layouts() {
+        let prev = [9u8; 32];
+        for flags in [0, FLAG_FRAME_CRC32C, FLAG_HASH_CHAIN, KNOWN_FLAGS] {
+            let header = JournalHeader::with_flags(flags).unwrap();
+            let frame = RecordFrame::new(FrameKind::EventJson, 5).unwrap();
+            let bytes = header.encode_frame(&frame, Some(&prev), b"hello").unwrap();
+            assert_eq!(bytes.len(), header.frame_header_len() + 5);
+
+            let (decoded, ext) = header.decode_frame_header(&bytes, 16).unwrap();
+            assert_eq!(decoded, frame);
+            assert_eq!(ext.crc32c.is_some(), header.has_checksums());
+            assert_eq!(ext.prev_chain.is_some(), header.has_hash_chain());
+            ext.verify_checksum(&decoded, b"hello", 16).unwrap();
+            if header.has_checksums() {
+                assert!(ext.verify_checksum(&decoded, b"jello", 16).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn encode_frame_requires_prev_chain_in_chained_journals() {
+        let header = JournalHeader::with_flags(FLAG_HASH_CHAIN).unwrap();
+        let frame = RecordFrame::new(FrameKind::EventJson, 5).unwrap();
+        assert!(matches!(
+            header.encode_frame(&frame, None, b"hello"),
+            Err(JournalError::MissingChainValue)
+        ));
+        let plain = JournalHeader::new();
+        assert!(plain.encode_frame(&frame, None, b"hello").is_ok());
+    }
+
+    #[test]
+    fn frame_kind_chain_head_and_index() {
+        assert_eq!(FrameKind::from_byte(0x02), FrameKind::ChainHead);
+        assert_eq!(FrameKind::ChainHead.to_byte(), FRAME_KIND_CHAIN_HEAD);
+        assert_eq!(FrameKind::from_byte(0x03), FrameKind::Index);
+        assert_eq!(FrameKind::Index.to_byte(), FRAME_KIND_INDEX);
+    }
+
     #[test]
     fn header_rejects_non_zero_reserved() {
         let mut bytes = JournalHeader::new().to_bytes();
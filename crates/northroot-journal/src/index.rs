@@ -0,0 +1,277 @@
+//! In-band index segments for random access.
+//!
+//! An `Index` frame maps a contiguous range of event ordinals (zero-based
+//! positions among `EventJson` frames) to the byte offsets of their frames,
+//! together with each event's `event_id`. Segments are written incrementally:
+//! each covers the events appended since the previous segment and links back
+//! to it, so the latest segment at the end of the file reaches every indexed
+//! event. Readers that do not understand the kind skip it.
+//!
+//! Payload layout (little-endian):
+//!
+//! ```text
+//! entries:  count x [offset u64][event_id 32 bytes]
+//! footer:   [first_ordinal u64][count u64][prev_index_offset u64]["NRIX"]
+//! ```
+//!
+//! `prev_index_offset` is `u64::MAX` for the first segment. Events whose
+//! `event_id` is not a SHA-256 digest are stored with an all-zero ID and can
+//! only be found by ordinal.
+
+use crate::errors::JournalError;
+use crate::event::EventJson;
+use northroot_canonical::{Digest, DigestAlg};
+use std::collections::HashMap;
+
+/// Magic bytes closing every index payload: `b"NRIX"`.
+pub const INDEX_MAGIC: &[u8; 4] = b"NRIX";
+
+/// Size of one index entry: offset + event ID.
+pub const INDEX_ENTRY_SIZE: usize = 8 + 32;
+
+/// Size of the index payload footer.
+pub const INDEX_FOOTER_SIZE: usize = 8 + 8 + 8 + 4;
+
+/// Maximum number of entries in one segment (keeps payloads under the frame limit).
+pub const MAX_INDEX_ENTRIES: usize = 64 * 1024;
+
+const NO_PREV: u64 = u64::MAX;
+
+/// Raw event ID stored in an index entry.
+pub type IndexKey = [u8; 32];
+
+/// One indexed event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// Byte offset of the event's frame.
+    pub offset: u64,
+    /// Raw SHA-256 `event_id`, or all zeros if the event has none.
+    pub event_id: IndexKey,
+}
+
+/// Decoded payload of an `Index` frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSegment {
+    /// Ordinal of the first entry.
+    pub first_ordinal: u64,
+    /// Offset of the previous segment's frame, if any.
+    pub prev_offset: Option<u64>,
+    /// Entries for ordinals `first_ordinal..first_ordinal + entries.len()`.
+    pub entries: Vec<IndexEntry>,
+}
+
+impl IndexSegment {
+    /// Serializes the segment payload.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::payload_len(self.entries.len() as u64));
+        for entry in &self.entries {
+            bytes.extend_from_slice(&entry.offset.to_le_bytes());
+            bytes.extend_from_slice(&entry.event_id);
+        }
+        bytes.extend_from_slice(&self.first_ordinal.to_le_bytes());
+        bytes.extend_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.prev_offset.unwrap_or(NO_PREV).to_le_bytes());
+        bytes.extend_from_slice(INDEX_MAGIC);
+        bytes
+    }
+
+    /// Deserializes a segment payload.
+    pub fn from_bytes(bytes: &[u8], offset: u64) -> Result<Self, JournalError> {
+        let invalid = |reason: &str| JournalError::InvalidFrame {
+            offset,
+            reason: reason.to_string(),
+        };
+        let count = Self::footer_count(bytes).ok_or_else(|| invalid("missing index footer"))?;
+        if bytes.len() as u64 != Self::payload_len(count) as u64 {
+            return Err(invalid("index payload length does not match entry count"));
+        }
+        let footer = &bytes[bytes.len() - INDEX_FOOTER_SIZE..];
+        let first_ordinal = read_u64(&footer[0..8]);
+        let prev = read_u64(&footer[16..24]);
+        let entries = bytes[..bytes.len() - INDEX_FOOTER_SIZE]
+            .chunks_exact(INDEX_ENTRY_SIZE)
+            .map(|chunk| {
+                let mut event_id = [0u8; 32];
+                event_id.copy_from_slice(&chunk[8..]);
+                IndexEntry {
+                    offset: read_u64(&chunk[..8]),
+                    event_id,
+                }
+            })
+            .collect();
+        Ok(Self {
+            first_ordinal,
+            prev_offset: (prev != NO_PREV).then_some(prev),
+            entries,
+        })
+    }
+
+    /// Returns the entry count declared by a payload's footer, if it has one.
+    ///
+    /// Used to locate a segment from the end of the file.
+    pub fn footer_count(bytes: &[u8]) -> Option<u64> {
+        if bytes.len() < INDEX_FOOTER_SIZE || !bytes.ends_with(INDEX_MAGIC) {
+            return None;
+        }
+        let footer = &bytes[bytes.len() - INDEX_FOOTER_SIZE..];
+        Some(read_u64(&footer[8..16]))
+    }
+
+    /// Returns the payload size of a segment with `count` entries.
+    pub fn payload_len(count: u64) -> usize {
+        (count as usize)
+            .saturating_mul(INDEX_ENTRY_SIZE)
+            .saturating_add(INDEX_FOOTER_SIZE)
+    }
+}
+
+/// Extracts the raw index key from an event's `event_id`.
+pub fn event_key(event: &EventJson) -> IndexKey {
+    event
+        .get("event_id")
+        .and_then(|id| serde_json::from_value::<Digest>(id.clone()).ok())
+        .and_then(|digest| digest_key(&digest))
+        .unwrap_or([0u8; 32])
+}
+
+/// Extracts the raw index key from an `EventJson` payload.
+pub fn payload_key(payload: &[u8]) -> IndexKey {
+    serde_json::from_slice::<EventJson>(payload)
+        .map(|event| event_key(&event))
+        .unwrap_or([0u8; 32])
+}
+
+/// Decodes a SHA-256 digest to its raw bytes.
+pub fn digest_key(digest: &Digest) -> Option<IndexKey> {
+    use base64::Engine;
+    if digest.alg != DigestAlg::Sha256 {
+        return None;
+    }
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(&digest.b64)
+        .ok()?;
+    bytes.try_into().ok()
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8 bytes"))
+}
+
+/// Offsets of every event in a journal, assembled from index segments and a
+/// scan of the frames after the latest one.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JournalIndex {
+    /// Entry for each event, by ordinal.
+    pub entries: Vec<IndexEntry>,
+    /// First ordinal of each event ID.
+    pub by_id: HashMap<IndexKey, u64>,
+    /// Number of events covered by index segments.
+    pub indexed: u64,
+    /// Offset of the latest index segment.
+    pub last_segment: Option<u64>,
+    /// File position up to which frames have been accounted for.
+    pub scanned_until: u64,
+}
+
+impl JournalIndex {
+    /// Records `entry` as the next ordinal.
+    pub fn push(&mut self, entry: IndexEntry) {
+        let ordinal = self.entries.len() as u64;
+        if entry.event_id != [0u8; 32] {
+            self.by_id.entry(entry.event_id).or_insert(ordinal);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Records an index segment at `offset` covering every event pushed so far.
+    pub fn mark_segment(&mut self, offset: u64) {
+        self.indexed = self.entries.len() as u64;
+        self.last_segment = Some(offset);
+    }
+
+    /// Returns the entries not yet covered by a segment.
+    pub fn pending(&self) -> &[IndexEntry] {
+        &self.entries[self.indexed as usize..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn segment_round_trip() {
+        let segment = IndexSegment {
+            first_ordinal: 3,
+            prev_offset: Some(16),
+            entries: vec![
+                IndexEntry {
+                    offset: 100,
+                    event_id: [1u8; 32],
+                },
+                IndexEntry {
+                    offset: 200,
+                    event_id: [0u8; 32],
+                },
+            ],
+        };
+        let bytes = segment.to_bytes();
+        assert_eq!(bytes.len(), IndexSegment::payload_len(2));
+        assert_eq!(IndexSegment::footer_count(&bytes), Some(2));
+        assert_eq!(IndexSegment::from_bytes(&bytes, 0).unwrap(), segment);
+
+        let first = IndexSegment {
+            prev_offset: None,
+            entries: vec![],
+            ..segment
+        };
+        assert_eq!(
+            IndexSegment::from_bytes(&first.to_bytes(), 0).unwrap(),
+            first
+        );
+    }
+
+    #[test]
+    fn segment_rejects_bad_payloads() {
+        assert!(IndexSegment::from_bytes(b"not an index", 0).is_err());
+        let mut bytes = IndexSegment {
+            first_ordinal: 0,
+            prev_offset: None,
+            entries: vec![],
+        }
+        .to_bytes();
+        bytes.insert(0, 0);
+        assert!(IndexSegment::from_bytes(&bytes, 0).is_err());
+    }
+
+    #[test]
+    fn event_key_decodes_sha256_ids() {
+        let event = json!({
+            "event_id": {"alg": "sha-256", "b64": "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE"}
+        });
+        assert_eq!(event_key(&event), [1u8; 32]);
+        assert_eq!(event_key(&json!({"event_id": "nope"})), [0u8; 32]);
+        assert_eq!(event_key(&json!({})), [0u8; 32]);
+    }
+
+    #[test]
+    fn pending_excludes_indexed_events() {
+        let entry = |offset, byte| IndexEntry {
+            offset,
+            event_id: [byte; 32],
+        };
+        let mut index = JournalIndex::default();
+        index.push(entry(16, 1));
+        index.mark_segment(40);
+        index.push(entry(100, 2));
+        index.push(entry(200, 0));
+        index.push(entry(300, 2));
+        assert_eq!(
+            index.pending(),
+            &[entry(100, 2), entry(200, 0), entry(300, 2)]
+        );
+        assert_eq!(index.by_id.get(&[2u8; 32]), Some(&1));
+        assert!(!index.by_id.contains_key(&[0u8; 32]));
+    }
+}
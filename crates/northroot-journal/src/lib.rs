@@ -11,6 +11,8 @@
 //! use northroot_canonical::{compute_event_id, Canonicalizer, ProfileId};
 //! use northroot_journal::{JournalWriter, JournalReader, WriteOptions, ReadMode};
 //! use serde_json::json;
+//! # let dir = tempfile::tempdir()?;
+//! # let path = dir.path().join("events.nrj");
 //!
 //! // Create canonicalizer
 //! let profile = ProfileId::parse("northroot-canonical-v1")?;
@@ -28,12 +30,12 @@
 //! let event_id = compute_event_id(&event, &canonicalizer)?;
 //! event["event_id"] = serde_json::to_value(&event_id)?;
 //!
-//! let mut writer = JournalWriter::open("events.nrj", WriteOptions::default())?;
+//! let mut writer = JournalWriter::open(&path, WriteOptions::default())?;
 //! writer.append_event(&event)?;
 //! writer.finish()?;
 //!
 //! // Read events
-//! let mut reader = JournalReader::open("events.nrj", ReadMode::Strict)?;
+//! let mut reader = JournalReader::open(&path, ReadMode::Strict)?;
 //! while let Some(read_event) = reader.read_event()? {
 //!     println!("Read event: {}", read_event["event_id"]);
 //! }
@@ -44,7 +46,14 @@
 //!
 //! - [`JournalWriter`] - Write events to journal files
 //! - [`JournalReader`] - Read events from journal files
+//! - [`MmapJournalReader`] - Zero-copy and parallel reads over a memory-mapped journal
+//! - [`JournalRecovery`] - Find and cut off a torn journal tail
+//! - [`JournalStorage`] - Storage a journal lives in: files, memory or fault injection
 //! - [`verify_event_id`] - Verify event identity
+//! - [`verify_journal`] - Verify a whole journal into a [`VerificationReport`]
+//! - [`verify_journal_with`] - Verify a journal, streaming each frame's result
+//! - [`verify_incremental`] - Verify only what was appended since a [`VerifiedPrefix`]
+//! - [`verify_links`] - Check the `prev_event_id` chains between events
 //!
 //! ## See Also
 //!
@@ -56,22 +65,58 @@
 
 #![deny(missing_docs)]
 
+/// Running hash chain over frames (hash-chained journals).
+pub mod chain;
+/// Durability modes for journal writers.
+pub mod durability;
 /// Error types for journal operations.
 pub mod errors;
 /// Event JSON type alias and helpers.
 pub mod event;
 /// Frame structure and serialization.
 pub mod frame;
+/// In-band index segments for random access.
+pub mod index;
+/// Verification of `prev_event_id` links between events.
+pub mod links;
+/// Advisory file locks on journals.
+pub mod lock;
+/// Memory-mapped zero-copy journal reader.
+pub mod mmap;
 /// Journal reader implementation.
 pub mod reader;
-/// Verification helpers for journal events.
+/// Recovery of journals with a torn tail.
+pub mod recovery;
+/// External `.nrx` index files kept next to a journal.
+pub mod sidecar;
+/// Pluggable storage backends, including in-memory and fault-injecting ones.
+pub mod storage;
+/// Checks events must pass before a writer appends them.
+pub mod validation;
+/// Verification of events and whole journals.
 pub mod verification;
 /// Journal writer implementation.
 pub mod writer;
 
+pub use chain::ChainHead;
+pub use durability::{Durability, SyncKind};
 pub use errors::JournalError;
 pub use event::EventJson;
-pub use frame::{FrameKind, JournalHeader, RecordFrame};
-pub use reader::{JournalReader, ReadMode};
-pub use verification::verify_event_id;
-pub use writer::{JournalWriter, WriteOptions};
+pub use frame::{FrameExtension, FrameInfo, FrameKind, JournalHeader, RecordFrame};
+pub use links::{
+    verify_links, verify_links_reader, LinkChain, LinkIssue, LinkIssueKind, LinkOptions,
+    LinkReport, LinkScope,
+};
+pub use lock::LockPolicy;
+pub use mmap::{FrameRef, MmapJournalReader};
+pub use reader::{JournalReader, ReadMode, SalvageGap};
+pub use recovery::JournalRecovery;
+pub use sidecar::{SidecarIndex, SidecarRefresh};
+pub use storage::{Faults, FaultyStorage, JournalStorage, MemoryStorage, StorageStream};
+pub use validation::{Rejection, ValidationPolicy};
+pub use verification::{
+    verify_event_id, verify_incremental, verify_incremental_with, verify_journal,
+    verify_journal_with, verify_reader, verify_reader_with, DuplicateEventId, FrameOutcome,
+    FrameReport, VerificationReport, VerificationTotals, VerifiedPrefix, VerifyOptions,
+};
+pub use writer::{AppendReceipt, DuplicatePolicy, JournalWriter, WriteOptions};
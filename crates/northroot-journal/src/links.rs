@@ -0,0 +1,295 @@
+//! Verification of `prev_event_id` links between events.
+//!
+//! Events may name the event before them with an optional `prev_event_id`
+//! digest. [`verify_links`] walks a journal in order and checks that these
+//! links form unbroken chains: one across the whole journal, or one per value
+//! of a key such as `principal_id`. Only `event_id`, `prev_event_id` and the
+//! key are read, so any event schema works. The `event_id`s events claim are
+//! trusted; [`verify_journal`](crate::verify_journal) checks them.
+
+use crate::errors::JournalError;
+use crate::event::EventJson;
+use crate::frame::FrameKind;
+use crate::index::{self, IndexKey};
+use crate::reader::{JournalReader, ReadMode};
+use northroot_canonical::Digest;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// Which events form a chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LinkScope {
+    /// All events of the journal form one chain.
+    #[default]
+    Journal,
+    /// Events form one chain per `principal_id`.
+    Principal,
+    /// Events form one chain per value of a field, given as a top-level field
+    /// name or as a JSON pointer such as `/subject/id`.
+    Field(String),
+}
+
+impl LinkScope {
+    /// Returns the field chains are keyed by, if any.
+    fn field(&self) -> Option<&str> {
+        match self {
+            LinkScope::Journal => None,
+            LinkScope::Principal => Some("principal_id"),
+            LinkScope::Field(field) => Some(field),
+        }
+    }
+}
+
+/// Options for [`verify_links`].
+#[derive(Debug, Clone, Default)]
+pub struct LinkOptions {
+    /// Which events form a chain (default: the whole journal).
+    pub scope: LinkScope,
+    /// Stop after this many events (default: no limit).
+    pub max_events: Option<u64>,
+}
+
+/// What is wrong with an event's `prev_event_id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkIssueKind {
+    /// It names an event that already has a successor.
+    Fork,
+    /// It names an earlier event of the chain other than the latest one,
+    /// skipping the events after it.
+    Gap,
+    /// It names no earlier event of the chain, or is not a valid digest.
+    Dangling,
+    /// It is missing, but the chain already has a first event.
+    MultipleGenesis,
+}
+
+/// An event whose `prev_event_id` does not continue its chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkIssue {
+    /// What is wrong.
+    pub kind: LinkIssueKind,
+    /// Key of the event's chain, or `None` when the journal is one chain.
+    pub chain: Option<String>,
+    /// Offset of the event's frame.
+    pub offset: u64,
+    /// The event's `event_id`.
+    pub event_id: Digest,
+    /// The event's `prev_event_id`, if it is a valid digest.
+    pub prev_event_id: Option<Digest>,
+}
+
+/// Summary of one chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkChain {
+    /// Key of the chain, or `None` when the journal is one chain.
+    pub key: Option<String>,
+    /// Number of events in the chain.
+    pub events: u64,
+    /// The first event without a `prev_event_id`, if any.
+    pub genesis: Option<Digest>,
+    /// The chain's latest event.
+    pub head: Digest,
+}
+
+/// Result of [`verify_links`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LinkReport {
+    /// `EventJson` frames read.
+    pub events: u64,
+    /// Events skipped because they are not valid JSON or lack a valid
+    /// `event_id`.
+    pub unidentified: u64,
+    /// Events skipped because they lack the field chains are keyed by.
+    pub unkeyed: u64,
+    /// Every chain, in order of its first event.
+    pub chains: Vec<LinkChain>,
+    /// Every issue found, in file order.
+    pub issues: Vec<LinkIssue>,
+}
+
+impl LinkReport {
+    /// Returns `true` if every chain is unbroken.
+    pub fn passed(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks the `prev_event_id` chains of the journal at `path`.
+///
+/// Frames are read in strict mode. Memory grows with the number of events,
+/// since every `event_id` is kept to resolve later links.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::{verify_links, LinkOptions, LinkScope};
+///
+/// let options = LinkOptions {
+///     scope: LinkScope::Principal,
+///     ..Default::default()
+/// };
+/// let report = verify_links("events.nrj", &options)?;
+/// for issue in &report.issues {
+///     println!("{:?} at offset {}", issue.kind, issue.offset);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns [`JournalError`] if the journal cannot be opened or read, or a
+/// frame is damaged or truncated.
+pub fn verify_links<P: AsRef<Path>>(
+    path: P,
+    options: &LinkOptions,
+) -> Result<LinkReport, JournalError> {
+    let mut reader = JournalReader::open(path, ReadMode::Strict)?;
+    verify_links_reader(&mut reader, options)
+}
+
+/// Checks the `prev_event_id` chains in the remaining frames of `reader`
+/// like [`verify_links`].
+///
+/// # Errors
+///
+/// Returns [`JournalError`] if a frame cannot be read.
+pub fn verify_links_reader<R: Read>(
+    reader: &mut JournalReader<R>,
+    options: &LinkOptions,
+) -> Result<LinkReport, JournalError> {
+    let mut report = LinkReport::default();
+    let mut chains: HashMap<Option<String>, Chain> = HashMap::new();
+    let mut order = Vec::new();
+
+    while options.max_events.is_none_or(|max| report.events < max) {
+        let Some((info, payload)) = reader.read_frame_with_info()? else {
+            break;
+        };
+        if info.kind() != FrameKind::EventJson {
+            continue;
+        }
+        report.events += 1;
+        let Some((event, event_id, id_key)) = identify(&payload) else {
+            report.unidentified += 1;
+            continue;
+        };
+        let key = match options.scope.field() {
+            None => None,
+            Some(field) => match field_value(&event, field) {
+                Some(value) => Some(value),
+                None => {
+                    report.unkeyed += 1;
+                    continue;
+                }
+            },
+        };
+
+        let prev = event.get("prev_event_id").filter(|prev| !prev.is_null());
+        let prev_event_id = prev.and_then(|prev| Digest::deserialize(prev).ok());
+        let prev_key = prev_event_id.as_ref().and_then(index::digest_key);
+        let issue = match chains.get_mut(&key) {
+            Some(chain) => chain.link(prev.is_some(), prev_key),
+            None => {
+                let genesis = prev.is_none().then(|| event_id.clone());
+                let chain = Chain::new(key.clone(), genesis, event_id.clone(), id_key);
+                chains.insert(key.clone(), chain);
+                order.push(key.clone());
+                // The first event of a chain has nothing earlier to name
+                prev.is_some().then_some(LinkIssueKind::Dangling)
+            }
+        };
+        if let Some(kind) = issue {
+            report.issues.push(LinkIssue {
+                kind,
+                chain: key.clone(),
+                offset: info.offset,
+                event_id: event_id.clone(),
+                prev_event_id,
+            });
+        }
+
+        let chain = chains.get_mut(&key).expect("chain was just found or added");
+        chain.successors.entry(id_key).or_insert(false);
+        chain.tip = id_key;
+        chain.summary.head = event_id;
+        chain.summary.events += 1;
+    }
+
+    report.chains = order
+        .into_iter()
+        .map(|key| chains.remove(&key).expect("chain was added").summary)
+        .collect();
+    Ok(report)
+}
+
+/// A chain being walked.
+struct Chain {
+    summary: LinkChain,
+    /// `event_id` of the latest event.
+    tip: IndexKey,
+    /// Every `event_id` in the chain, and whether an event has named it as
+    /// its predecessor yet.
+    successors: HashMap<IndexKey, bool>,
+}
+
+impl Chain {
+    fn new(key: Option<String>, genesis: Option<Digest>, head: Digest, tip: IndexKey) -> Self {
+        Self {
+            summary: LinkChain {
+                key,
+                events: 0,
+                genesis,
+                head,
+            },
+            tip,
+            successors: HashMap::new(),
+        }
+    }
+
+    /// Links the next event, which has a `prev_event_id` if `has_prev` is set
+    /// and names `prev` with it, and returns what is wrong with the link.
+    fn link(&mut self, has_prev: bool, prev: Option<IndexKey>) -> Option<LinkIssueKind> {
+        if !has_prev {
+            return Some(LinkIssueKind::MultipleGenesis);
+        }
+        let Some(prev) = prev else {
+            return Some(LinkIssueKind::Dangling);
+        };
+        let is_tip = prev == self.tip;
+        match self.successors.get_mut(&prev) {
+            None => Some(LinkIssueKind::Dangling),
+            Some(true) => Some(LinkIssueKind::Fork),
+            Some(linked) => {
+                *linked = true;
+                (!is_tip).then_some(LinkIssueKind::Gap)
+            }
+        }
+    }
+}
+
+/// Parses an event and its `event_id`.
+fn identify(payload: &[u8]) -> Option<(EventJson, Digest, IndexKey)> {
+    let event: EventJson = serde_json::from_slice(payload).ok()?;
+    let event_id = Digest::deserialize(event.get("event_id")?).ok()?;
+    let key = index::digest_key(&event_id)?;
+    Some((event, event_id, key))
+}
+
+/// Returns the value of `field` in `event` as a chain key: strings as they
+/// are, other values as compact JSON. Missing and `null` fields have none.
+fn field_value(event: &EventJson, field: &str) -> Option<String> {
+    let value = if field.starts_with('/') {
+        event.pointer(field)
+    } else {
+        event.get(field)
+    }?;
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        other => Some(other.to_string()),
+    }
+}
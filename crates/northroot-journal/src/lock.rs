@@ -0,0 +1,52 @@
+//! Advisory file locks on journals.
+//!
+//! [`JournalWriter::open`](crate::JournalWriter::open) holds an exclusive lock
+//! for the writer's lifetime, so frames from concurrent writers never overlap.
+//! [`JournalReader::open_locked`](crate::JournalReader::open_locked) takes a
+//! shared lock, which keeps writers out while it reads a consistent snapshot.
+//!
+//! Locks are advisory (`flock` on Unix, `LockFileEx` on Windows): they only
+//! coordinate processes that use them, and they are released when the file is
+//! closed.
+
+use crate::errors::JournalError;
+use std::fs::{File, TryLockError};
+
+/// What to do when a journal is already locked by someone else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockPolicy {
+    /// Block until the lock is available.
+    Wait,
+    /// Fail with [`JournalError::Locked`] (the default).
+    #[default]
+    Fail,
+    /// Do not lock, e.g. on file systems without lock support. Concurrent
+    /// writers can then corrupt the journal.
+    Unlocked,
+}
+
+/// Takes an exclusive lock on `file` according to `policy`.
+pub(crate) fn lock_exclusive(file: &File, policy: LockPolicy) -> Result<(), JournalError> {
+    match policy {
+        LockPolicy::Wait => Ok(file.lock()?),
+        LockPolicy::Fail => try_lock(file.try_lock()),
+        LockPolicy::Unlocked => Ok(()),
+    }
+}
+
+/// Takes a shared lock on `file` according to `policy`.
+pub(crate) fn lock_shared(file: &File, policy: LockPolicy) -> Result<(), JournalError> {
+    match policy {
+        LockPolicy::Wait => Ok(file.lock_shared()?),
+        LockPolicy::Fail => try_lock(file.try_lock_shared()),
+        LockPolicy::Unlocked => Ok(()),
+    }
+}
+
+fn try_lock(result: Result<(), TryLockError>) -> Result<(), JournalError> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(TryLockError::WouldBlock) => Err(JournalError::Locked),
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
@@ -0,0 +1,435 @@
+//! Memory-mapped journal reader.
+//!
+//! [`MmapJournalReader`] maps the whole journal and hands out frames as
+//! borrowed slices of the mapping, so scanning allocates nothing per frame and
+//! payloads can be parsed in place (e.g. as [`RawValue`] or borrowed structs).
+//! Frames can also be scanned in parallel with
+//! [`par_map_frames`](MmapJournalReader::par_map_frames).
+//!
+//! Checksums, hash chain links, truncation and, in strict mode, canonical
+//! event payloads are checked exactly as in
+//! [`JournalReader`](crate::JournalReader) with the same [`ReadMode`].
+//! [`ReadMode::Salvage`] is not supported: resynchronizing after damage needs
+//! the buffered reader.
+
+use crate::chain::{self, ChainHash, ChainHead};
+use crate::errors::JournalError;
+use crate::event::{self, EventJson};
+use crate::frame::{FrameExtension, FrameKind, JournalHeader, RecordFrame};
+use crate::reader::ReadMode;
+use memmap2::Mmap;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Journal reader over a memory-mapped file.
+///
+/// The mapping reflects the file as it was when opened plus whatever is
+/// appended in place; the reader only looks at the length seen at open time.
+/// Truncating the file while it is mapped is undefined behavior on most
+/// platforms, so only map journals that are append-only.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::{FrameKind, MmapJournalReader, ReadMode};
+///
+/// let reader = MmapJournalReader::open("events.nrj", ReadMode::Strict)?;
+/// for frame in reader.frames() {
+///     let frame = frame?;
+///     if frame.kind() == FrameKind::EventJson {
+///         let raw = frame.raw_json()?;
+///         println!("{}", raw.get());
+///     }
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct MmapJournalReader {
+    mmap: Mmap,
+    header: JournalHeader,
+    mode: ReadMode,
+}
+
+/// A verified frame borrowed from a memory-mapped journal.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameRef<'a> {
+    offset: u64,
+    ordinal: u64,
+    frame: &'a [u8],
+    kind: FrameKind,
+    payload: &'a [u8],
+    canonical: bool,
+}
+
+impl<'a> FrameRef<'a> {
+    /// Byte offset where the frame starts.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Zero-based index of the frame in the journal (all kinds).
+    pub fn ordinal(&self) -> u64 {
+        self.ordinal
+    }
+
+    /// Frame kind.
+    pub fn kind(&self) -> FrameKind {
+        self.kind
+    }
+
+    /// Payload bytes.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Complete on-disk bytes of the frame, header fields included.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.frame
+    }
+
+    /// Payload as UTF-8 text.
+    pub fn as_str(&self) -> Result<&'a str, JournalError> {
+        Ok(std::str::from_utf8(self.payload)?)
+    }
+
+    /// Payload as an unparsed JSON value borrowed from the mapping.
+    ///
+    /// The payload is validated as JSON but not turned into a tree.
+    pub fn raw_json(&self) -> Result<&'a RawValue, JournalError> {
+        serde_json::from_str(self.as_str()?).map_err(JournalError::JsonParse)
+    }
+
+    /// Deserializes the payload, borrowing strings from the mapping where `T`
+    /// allows it.
+    pub fn parse<T: Deserialize<'a>>(&self) -> Result<T, JournalError> {
+        serde_json::from_str(self.as_str()?).map_err(JournalError::JsonParse)
+    }
+
+    /// Parses the payload into an owned event.
+    ///
+    /// In [`ReadMode::Strict`], events of a journal with canonical payloads
+    /// must be stored canonically, as for
+    /// [`JournalReader::read_event`](crate::JournalReader::read_event).
+    pub fn event(&self) -> Result<EventJson, JournalError> {
+        let event = self.parse()?;
+        if self.canonical {
+            event::ensure_canonical_payload(self.payload, &event, self.offset)?;
+        }
+        Ok(event)
+    }
+}
+
+/// Frame located by walking headers; payload not yet verified.
+#[derive(Debug, Clone)]
+struct Span {
+    offset: usize,
+    frame: RecordFrame,
+    ext: FrameExtension,
+    payload: Range<usize>,
+}
+
+impl MmapJournalReader {
+    /// Maps a journal file for reading and validates its header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the file cannot be opened or mapped, or the
+    /// header is invalid. [`ReadMode::Salvage`] fails with an
+    /// [`io::ErrorKind::Unsupported`] error.
+    pub fn open<P: AsRef<Path>>(path: P, mode: ReadMode) -> Result<Self, JournalError> {
+        if mode == ReadMode::Salvage {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "salvage mode needs JournalReader; memory-mapped reads do not resynchronize",
+            )
+            .into());
+        }
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only and journals are append-only; see
+        // the type-level docs for the truncation caveat.
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < JournalHeader::HEADER_SIZE {
+            return Err(JournalError::InvalidHeader(format!(
+                "header too short: {} bytes",
+                mmap.len()
+            )));
+        }
+        let header = JournalHeader::from_bytes(&mmap[..JournalHeader::HEADER_SIZE])?;
+        Ok(Self { mmap, header, mode })
+    }
+
+    /// Returns the validated file header.
+    pub fn header(&self) -> &JournalHeader {
+        &self.header
+    }
+
+    /// Returns the mapped length in bytes.
+    pub fn len(&self) -> u64 {
+        self.mmap.len() as u64
+    }
+
+    /// Returns `true` if the journal has no frames.
+    pub fn is_empty(&self) -> bool {
+        self.mmap.len() <= JournalHeader::HEADER_SIZE
+    }
+
+    /// Iterates over frames in order, verifying checksums and the hash chain.
+    ///
+    /// The iterator stops after the first error.
+    pub fn frames(&self) -> Frames<'_> {
+        Frames {
+            reader: self,
+            offset: JournalHeader::HEADER_SIZE,
+            ordinal: 0,
+            chain: self
+                .header
+                .has_hash_chain()
+                .then(|| chain::genesis(&self.header)),
+            done: false,
+        }
+    }
+
+    /// Iterates over `EventJson` payloads as raw JSON, skipping other kinds.
+    ///
+    /// Payloads are checked for canonical form like [`FrameRef::event`] does,
+    /// which parses them in full.
+    pub fn raw_events(&self) -> impl Iterator<Item = Result<&RawValue, JournalError>> + '_ {
+        self.frames().filter_map(|frame| match frame {
+            Ok(frame) if frame.kind() == FrameKind::EventJson && frame.canonical => {
+                Some(frame.event().and_then(|_| frame.raw_json()))
+            }
+            Ok(frame) if frame.kind() == FrameKind::EventJson => Some(frame.raw_json()),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Applies `f` to every frame using up to `threads` worker threads and
+    /// returns the results in frame order. `threads == 0` uses the available
+    /// parallelism.
+    ///
+    /// Frame headers are walked once up front; checksums, hash chain links and
+    /// `f` then run in parallel. The error reported is the one a sequential
+    /// scan would hit first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] for invalid or truncated frames (per
+    /// [`ReadMode`]), checksum mismatches and broken chain links.
+    pub fn par_map_frames<T, F>(&self, threads: usize, f: F) -> Result<Vec<T>, JournalError>
+    where
+        T: Send,
+        F: Fn(FrameRef<'_>) -> T + Sync,
+    {
+        let (spans, walk_error) = self.walk();
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let chunk_len = spans.len().div_ceil(threads).max(1);
+
+        let results: Vec<Result<Vec<T>, JournalError>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = spans
+                .chunks(chunk_len)
+                .enumerate()
+                .map(|(chunk, slice)| {
+                    let first = chunk * chunk_len;
+                    let spans = &spans;
+                    let f = &f;
+                    scope.spawn(move || {
+                        let mut out = Vec::with_capacity(slice.len());
+                        for ordinal in first..first + slice.len() {
+                            let frame = self.verify(spans, ordinal)?;
+                            out.push(f(frame));
+                        }
+                        Ok(out)
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("frame worker panicked"))
+                .collect()
+        });
+
+        let mut out = Vec::with_capacity(spans.len());
+        for result in results {
+            out.extend(result?);
+        }
+        match walk_error {
+            Some(e) => Err(e),
+            None => Ok(out),
+        }
+    }
+
+    /// Verifies frame `ordinal`: its checksum, the chain link into it and, for
+    /// trailers, the recorded head.
+    fn verify<'a>(&'a self, spans: &[Span], ordinal: usize) -> Result<FrameRef<'a>, JournalError> {
+        let span = &spans[ordinal];
+        let offset = span.offset as u64;
+        let payload = &self.mmap[span.payload.clone()];
+        span.ext.verify_checksum(&span.frame, payload, offset)?;
+
+        if let Some(prev) = span.ext.prev_chain {
+            let expected = match ordinal.checked_sub(1) {
+                None => chain::genesis(&self.header),
+                Some(before) => self.link(&spans[before]),
+            };
+            self.check_link(span, &prev, &expected, ordinal as u64, payload)?;
+        }
+
+        Ok(self.frame_ref(span, ordinal as u64))
+    }
+
+    /// Chain value after `span`, computed from the value it stores.
+    fn link(&self, span: &Span) -> ChainHash {
+        let prev = span.ext.prev_chain.expect("hash-chained journal");
+        chain::next(
+            &prev,
+            &span.frame.to_bytes(),
+            &self.mmap[span.payload.clone()],
+        )
+    }
+
+    fn check_link(
+        &self,
+        span: &Span,
+        stored: &ChainHash,
+        expected: &ChainHash,
+        ordinal: u64,
+        payload: &[u8],
+    ) -> Result<(), JournalError> {
+        let offset = span.offset as u64;
+        let broken = JournalError::ChainBroken { offset, ordinal };
+        if stored != expected {
+            return Err(broken);
+        }
+        if span.frame.kind == FrameKind::ChainHead {
+            let trailer = ChainHead::from_bytes(payload, offset)?;
+            if trailer.head != *stored || trailer.frames != ordinal {
+                return Err(broken);
+            }
+        }
+        Ok(())
+    }
+
+    fn frame_ref(&self, span: &Span, ordinal: u64) -> FrameRef<'_> {
+        FrameRef {
+            offset: span.offset as u64,
+            ordinal,
+            frame: &self.mmap[span.offset..span.payload.end],
+            kind: span.frame.kind,
+            payload: &self.mmap[span.payload.clone()],
+            canonical: span.frame.kind == FrameKind::EventJson
+                && self.mode == ReadMode::Strict
+                && self.header.has_canonical_payloads(),
+        }
+    }
+
+    /// Locates every frame by its header, stopping at the first invalid or
+    /// truncated frame (an error only in strict mode).
+    fn walk(&self) -> (Vec<Span>, Option<JournalError>) {
+        let mut spans = Vec::new();
+        let mut offset = JournalHeader::HEADER_SIZE;
+        loop {
+            match self.span_at(offset) {
+                Ok(Some(span)) => {
+                    offset = span.payload.end;
+                    spans.push(span);
+                }
+                Ok(None) => return (spans, None),
+                Err(e) => return (spans, Some(e)),
+            }
+        }
+    }
+
+    /// Decodes the frame header at `offset` and bounds-checks its payload.
+    fn span_at(&self, offset: usize) -> Result<Option<Span>, JournalError> {
+        let len = self.mmap.len();
+        if offset >= len {
+            return Ok(None);
+        }
+        let header_len = self.header.frame_header_len();
+        let truncated = |at: usize| match self.mode {
+            ReadMode::Permissive | ReadMode::Salvage => Ok(None),
+            ReadMode::Strict => Err(JournalError::TruncatedFrame { offset: at as u64 }),
+        };
+        if len - offset < header_len {
+            return truncated(offset);
+        }
+        let (frame, ext) = self
+            .header
+            .decode_frame_header(&self.mmap[offset..offset + header_len], offset as u64)?;
+        let start = offset + header_len;
+        let end = start + frame.len as usize;
+        if end > len {
+            return truncated(start);
+        }
+        Ok(Some(Span {
+            offset,
+            frame,
+            ext,
+            payload: start..end,
+        }))
+    }
+}
+
+/// Sequential frame iterator returned by [`MmapJournalReader::frames`].
+pub struct Frames<'a> {
+    reader: &'a MmapJournalReader,
+    offset: usize,
+    ordinal: u64,
+    chain: Option<ChainHash>,
+    done: bool,
+}
+
+impl<'a> Frames<'a> {
+    /// Returns the hash chain value covering every frame yielded so far.
+    ///
+    /// Returns `None` unless the journal is hash-chained.
+    pub fn chain_head(&self) -> Option<northroot_canonical::Digest> {
+        self.chain.as_ref().map(chain::to_digest)
+    }
+
+    fn next_frame(&mut self) -> Result<Option<FrameRef<'a>>, JournalError> {
+        let reader = self.reader;
+        let Some(span) = reader.span_at(self.offset)? else {
+            return Ok(None);
+        };
+        let offset = span.offset as u64;
+        let payload = &reader.mmap[span.payload.clone()];
+        span.ext.verify_checksum(&span.frame, payload, offset)?;
+        if let (Some(expected), Some(stored)) = (self.chain, span.ext.prev_chain) {
+            reader.check_link(&span, &stored, &expected, self.ordinal, payload)?;
+            self.chain = Some(reader.link(&span));
+        }
+        let frame = reader.frame_ref(&span, self.ordinal);
+        self.offset = span.payload.end;
+        self.ordinal += 1;
+        Ok(Some(frame))
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Result<FrameRef<'a>, JournalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_frame() {
+            Ok(Some(frame)) => Some(Ok(frame)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
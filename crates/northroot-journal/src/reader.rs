@@ -1,12 +1,22 @@
 //! Journal reader implementation.
 
+use crate::chain::{self, ChainHead, ChainState, CHAIN_HEAD_PAYLOAD_SIZE};
 use crate::errors::JournalError;
-use crate::event::EventJson;
-use crate::frame::{FrameKind, JournalHeader, RecordFrame};
+use crate::event::{self, EventJson};
+use crate::frame::{FrameExtension, FrameInfo, FrameKind, JournalHeader, RecordFrame};
+use crate::index::{self, IndexEntry, IndexSegment, JournalIndex, INDEX_FOOTER_SIZE};
+use crate::lock::{self, LockPolicy};
+use crate::storage::{JournalStorage, StorageStream};
+use northroot_canonical::Digest;
+use std::collections::VecDeque;
 use std::fs::File;
-use std::io::{self, Read, Seek};
+use std::io::{self, BufReader, Read, Seek};
 use std::path::Path;
 
+/// Most bytes a [`ReadMode::Salvage`] reader examines for one candidate frame
+/// while skipping damage.
+const SALVAGE_WINDOW: usize = 1024 * 1024;
+
 /// Read mode for handling truncation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReadMode {
@@ -14,13 +24,47 @@ pub enum ReadMode {
     Strict,
     /// Permissive mode: truncation is treated as end-of-file.
     Permissive,
+    /// Salvage mode for forensic recovery: damaged bytes are skipped.
+    ///
+    /// Where no valid frame starts, the reader scans forward byte by byte for
+    /// the next plausible one and records the skipped range as a
+    /// [`SalvageGap`]. A plausible frame has a known kind, a valid header and
+    /// checksum, and a payload that decodes for its kind; `EventJson` payloads
+    /// must be valid JSON. Frames of unknown kinds are skipped as damage.
+    /// While scanning, frames longer than 1 MiB are not considered, which
+    /// bounds the work per skipped byte; such a frame right after damage is
+    /// reported as part of the gap.
+    ///
+    /// The hash chain is not verified, since a gap breaks it, and frame
+    /// ordinals are unknown after the first gap. [`MmapJournalReader`]
+    /// does not resynchronize and refuses to open in this mode.
+    ///
+    /// [`MmapJournalReader`]: crate::MmapJournalReader
+    Salvage,
 }
 
-/// Journal reader for reading events from a journal file.
+/// Bytes a [`ReadMode::Salvage`] reader skipped because no plausible frame
+/// starts in them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SalvageGap {
+    /// Offset of the first skipped byte.
+    pub offset: u64,
+    /// Number of skipped bytes.
+    pub len: u64,
+    /// Why the bytes at `offset` are not a frame.
+    pub reason: String,
+}
+
+/// Journal reader for reading events from a journal file or any other byte source.
+///
+/// Sequential reads need only [`Read`], so a journal can be verified straight
+/// from stdin or an archive entry; seeking needs [`Read`] + [`Seek`].
+/// [`open`](JournalReader::open) reads a file through a [`BufReader`].
 ///
-/// The reader supports two modes:
+/// The reader supports three modes:
 /// - [`ReadMode::Strict`] - Truncated frames are errors
 /// - [`ReadMode::Permissive`] - Truncation is treated as end-of-file
+/// - [`ReadMode::Salvage`] - Damaged bytes are skipped and reported
 ///
 /// # Example
 ///
@@ -31,6 +75,9 @@ pub enum ReadMode {
 /// while let Some(event) = reader.read_event()? {
 ///     println!("Event: {}", event["event_id"]);
 /// }
+///
+/// // Any `Read` works, e.g. a journal piped over stdin.
+/// let mut reader = JournalReader::new(std::io::stdin().lock(), ReadMode::Strict)?;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 ///
@@ -38,17 +85,75 @@ pub enum ReadMode {
 ///
 /// - [`JournalWriter`](crate::JournalWriter) - Write events to journals
 /// - [Journal Format Reference](../../../docs/reference/format.md) - Format specification
-pub struct JournalReader {
-    file: File,
+pub struct JournalReader<R = BufReader<StorageStream<File>>> {
+    inner: R,
+    header: JournalHeader,
     mode: ReadMode,
     position: u64,
+    /// Ordinal of the next frame; unknown after a seek.
+    ordinal: Option<u64>,
+    chain: Option<ChainState>,
+    /// Event offsets, built on the first seek.
+    index: Option<JournalIndex>,
+    /// Bytes read ahead while salvaging, to be read again before `inner`.
+    replay: VecDeque<u8>,
+    /// Ranges skipped while salvaging.
+    gaps: Vec<SalvageGap>,
 }
 
-impl JournalReader {
+/// A frame read and checksum-verified at a known offset.
+struct RawFrame {
+    /// Offset of the record header.
+    offset: u64,
+    frame: RecordFrame,
+    ext: FrameExtension,
+    payload: Vec<u8>,
+    /// Offset just past the frame.
+    end: u64,
+}
+
+impl<R> JournalReader<R> {
     /// Returns the current read position in the file.
     pub fn position(&self) -> u64 {
         self.position
     }
+
+    /// Returns the validated file header.
+    pub fn header(&self) -> &JournalHeader {
+        &self.header
+    }
+
+    /// Returns the hash chain value covering every frame read so far.
+    ///
+    /// Returns `None` unless the journal is hash-chained.
+    pub fn chain_head(&self) -> Option<Digest> {
+        self.chain
+            .as_ref()
+            .map(|state| chain::to_digest(&state.head))
+    }
+
+    /// Returns the hash chain state after the frames read so far.
+    pub(crate) fn chain_state(&self) -> Option<ChainState> {
+        self.chain
+    }
+
+    /// Returns the ranges skipped so far in [`ReadMode::Salvage`], in file
+    /// order.
+    pub fn gaps(&self) -> &[SalvageGap] {
+        &self.gaps
+    }
+
+    /// Returns the underlying byte source.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns the underlying byte source without consuming the reader.
+    ///
+    /// Reading from or seeking it directly leaves the reader out of step.
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
 }
 
 impl JournalReader {
@@ -73,79 +178,331 @@ impl JournalReader {
     /// - File header is invalid
     /// - I/O error occurs
     pub fn open<P: AsRef<Path>>(path: P, mode: ReadMode) -> Result<Self, JournalError> {
-        let mut file = File::open(path)?;
-        let _header = Self::read_header(&mut file)?;
+        Self::from_storage(File::open(path)?, mode)
+    }
+
+    /// Opens a journal file like [`open`](Self::open) and holds a shared lock
+    /// on it until the reader is dropped.
+    ///
+    /// Writers cannot open the journal meanwhile, so the reader sees a
+    /// consistent snapshot. Other locked readers are not affected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError::Locked`] if a writer holds the journal and
+    /// `lock` is [`LockPolicy::Fail`].
+    pub fn open_locked<P: AsRef<Path>>(
+        path: P,
+        mode: ReadMode,
+        lock: LockPolicy,
+    ) -> Result<Self, JournalError> {
+        let file = File::open(path)?;
+        lock::lock_shared(&file, lock)?;
+        Self::from_storage(file, mode)
+    }
+}
+
+impl<S: JournalStorage> JournalReader<BufReader<StorageStream<S>>> {
+    /// Creates a reader over the journal kept in `storage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the storage does not start with a valid
+    /// journal header or cannot be read.
+    pub fn from_storage(storage: S, mode: ReadMode) -> Result<Self, JournalError> {
+        Self::new(BufReader::new(StorageStream::new(storage)), mode)
+    }
+}
+
+impl<R: Read> JournalReader<R> {
+    /// Creates a reader over a byte source positioned at the start of a journal.
+    ///
+    /// The file header is read and validated immediately.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use northroot_journal::{JournalReader, JournalWriter, ReadMode, WriteOptions};
+    /// use serde_json::json;
+    ///
+    /// let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default())?;
+    /// writer.append_event(&json!({"event_type": "test"}))?;
+    /// let bytes = writer.into_inner()?;
+    ///
+    /// let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict)?;
+    /// assert_eq!(reader.read_event()?.unwrap()["event_type"], "test");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the header cannot be read or is invalid.
+    pub fn new(mut inner: R, mode: ReadMode) -> Result<Self, JournalError> {
+        let mut header_bytes = [0u8; JournalHeader::HEADER_SIZE];
+        inner.read_exact(&mut header_bytes)?;
+        let header = JournalHeader::from_bytes(&header_bytes)?;
         let position = JournalHeader::HEADER_SIZE as u64;
+        let chain = (header.has_hash_chain() && mode != ReadMode::Salvage)
+            .then(|| ChainState::new(&header));
 
         Ok(Self {
-            file,
+            inner,
+            header,
             mode,
             position,
+            ordinal: Some(0),
+            chain,
+            index: None,
+            replay: VecDeque::new(),
+            gaps: Vec::new(),
         })
     }
 
-    fn read_header(file: &mut File) -> Result<JournalHeader, JournalError> {
-        file.seek(io::SeekFrom::Start(0))?;
-        let mut header_bytes = [0u8; JournalHeader::HEADER_SIZE];
-        file.read_exact(&mut header_bytes)?;
-        JournalHeader::from_bytes(&header_bytes)
-    }
-
     /// Reads the next frame from the journal.
     ///
     /// Returns `Ok(None)` when end-of-file is reached (or truncation in permissive mode).
+    /// In v2 journals the frame's CRC32C and hash chain value are verified before
+    /// the payload is returned, for every frame kind.
     pub fn read_frame(&mut self) -> Result<Option<(FrameKind, Vec<u8>)>, JournalError> {
-        self.file.seek(io::SeekFrom::Start(self.position))?;
+        Ok(self
+            .read_frame_with_info()?
+            .map(|(info, payload)| (info.kind(), payload)))
+    }
 
-        // Check if we're at EOF before trying to read
-        let file_size = self.file.metadata()?.len();
-        if self.position >= file_size {
+    /// Reads the next frame like [`read_frame`](Self::read_frame), together
+    /// with its location in the journal.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use northroot_journal::{JournalReader, ReadMode};
+    ///
+    /// let mut reader = JournalReader::open("events.nrj", ReadMode::Strict)?;
+    /// while let Some((info, _payload)) = reader.read_frame_with_info()? {
+    ///     println!("{:?} frame at offset {}", info.kind(), info.offset);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn read_frame_with_info(&mut self) -> Result<Option<(FrameInfo, Vec<u8>)>, JournalError> {
+        let raw = match self.mode {
+            ReadMode::Salvage => self.read_next_salvaged()?,
+            _ => self.read_next_raw(self.position)?,
+        };
+        let Some(RawFrame {
+            offset: frame_offset,
+            frame,
+            ext,
+            payload,
+            end,
+        }) = raw
+        else {
             return Ok(None);
-        }
+        };
+        self.position = end;
 
-        // Read frame header
-        let mut frame_header_bytes = [0u8; RecordFrame::FRAME_HEADER_SIZE];
-        match self.file.read_exact(&mut frame_header_bytes) {
-            Ok(()) => {}
-            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                if self.mode == ReadMode::Permissive {
-                    return Ok(None);
+        if let (Some(state), Some(prev)) = (self.chain.as_mut(), ext.prev_chain.as_ref()) {
+            let ordinal = state.frames;
+            state.advance(frame_offset, &frame, prev, &payload)?;
+            if frame.kind == FrameKind::ChainHead {
+                // A trailer must restate the chain it was appended after.
+                let trailer = ChainHead::from_bytes(&payload, frame_offset)?;
+                if trailer.head != *prev || trailer.frames != ordinal {
+                    return Err(JournalError::ChainBroken {
+                        offset: frame_offset,
+                        ordinal,
+                    });
                 }
-                return Err(JournalError::TruncatedFrame {
-                    offset: self.position,
-                });
             }
-            Err(e) => return Err(e.into()),
         }
 
-        let frame = RecordFrame::from_bytes(&frame_header_bytes).map_err(|e| match e {
-            JournalError::InvalidFrame { offset: _, reason } => JournalError::InvalidFrame {
-                offset: self.position,
-                reason,
-            },
-            other => other,
-        })?;
+        let info = FrameInfo {
+            ordinal: self.ordinal,
+            offset: frame_offset,
+            payload_len: frame.len,
+            header: frame,
+        };
+        self.ordinal = self.ordinal.map(|n| n + 1);
+        Ok(Some((info, payload)))
+    }
+
+    /// Reads and checksum-verifies the frame at the current stream position,
+    /// which the caller guarantees is `offset`, without advancing the hash chain.
+    fn read_next_raw(&mut self, offset: u64) -> Result<Option<RawFrame>, JournalError> {
+        // Read frame header (plus v2 extension fields)
+        let mut frame_header_bytes = vec![0u8; self.header.frame_header_len()];
+        match self.fill(&mut frame_header_bytes)? {
+            0 => return Ok(None),
+            n if n < frame_header_bytes.len() => return self.truncated(offset),
+            _ => {}
+        }
+
+        let (frame, ext) = self
+            .header
+            .decode_frame_header(&frame_header_bytes, offset)?;
 
-        self.position += RecordFrame::FRAME_HEADER_SIZE as u64;
+        let payload_offset = offset + frame_header_bytes.len() as u64;
 
         // Read payload
         let mut payload = vec![0u8; frame.len as usize];
-        match self.file.read_exact(&mut payload) {
-            Ok(()) => {}
-            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                if self.mode == ReadMode::Permissive {
-                    return Ok(None);
+        if self.fill(&mut payload)? < payload.len() {
+            return self.truncated(payload_offset);
+        }
+
+        ext.verify_checksum(&frame, &payload, offset)?;
+
+        Ok(Some(RawFrame {
+            offset,
+            end: payload_offset + frame.len as u64,
+            frame,
+            ext,
+            payload,
+        }))
+    }
+
+    fn truncated<T>(&self, offset: u64) -> Result<Option<T>, JournalError> {
+        match self.mode {
+            ReadMode::Permissive | ReadMode::Salvage => Ok(None),
+            ReadMode::Strict => Err(JournalError::TruncatedFrame { offset }),
+        }
+    }
+
+    /// Reads into `buf` from bytes read ahead while salvaging, then from the
+    /// source; returns the bytes read, fewer than requested only at end-of-file.
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let replayed = read_full(&mut self.replay, buf)?;
+        Ok(replayed + read_full(&mut self.inner, &mut buf[replayed..])?)
+    }
+
+    /// Reads ahead into the replay buffer until it holds `want` bytes or the
+    /// source is exhausted; returns the bytes buffered.
+    fn buffer(&mut self, want: usize) -> io::Result<usize> {
+        let have = self.replay.len();
+        if have < want {
+            self.replay.resize(want, 0);
+            let result = read_full(&mut self.inner, &mut self.replay.make_contiguous()[have..]);
+            self.replay
+                .truncate(have + result.as_ref().map_or(0, |&read| read));
+            result?;
+        }
+        Ok(self.replay.len())
+    }
+
+    /// Reads the next plausible frame at or after the current position,
+    /// recording the bytes skipped to reach it as a [`SalvageGap`].
+    ///
+    /// Candidates are checked in the replay buffer, so skipping a byte only
+    /// drops it from the front, and while resynchronizing no candidate longer
+    /// than [`SALVAGE_WINDOW`] is read.
+    fn read_next_salvaged(&mut self) -> Result<Option<RawFrame>, JournalError> {
+        let mut gap: Option<SalvageGap> = None;
+        let header_len = self.header.frame_header_len();
+        let found = loop {
+            let offset = self.position;
+            let buffered = self.buffer(header_len)?;
+            let result = match buffered {
+                0 => break None,
+                n if n < header_len => Err(JournalError::TruncatedFrame { offset }),
+                _ => self.read_plausible(offset, gap.is_some()),
+            };
+
+            match result {
+                Ok(frame) => break Some(frame),
+                Err(JournalError::Io(e)) => return Err(e.into()),
+                Err(e) => {
+                    gap.get_or_insert_with(|| SalvageGap {
+                        offset,
+                        len: 0,
+                        reason: e.to_string(),
+                    });
+                    if buffered < header_len {
+                        // Too short for any frame: the rest of the journal is lost
+                        self.replay.clear();
+                        self.position += buffered as u64;
+                        break None;
+                    }
+                    // Retry one byte further on
+                    self.replay.pop_front();
+                    self.position += 1;
                 }
-                return Err(JournalError::TruncatedFrame {
-                    offset: self.position,
-                });
             }
-            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(mut gap) = gap {
+            gap.len = self.position - gap.offset;
+            self.gaps.push(gap);
+            self.ordinal = None;
         }
+        Ok(found)
+    }
 
-        self.position += frame.len as u64;
+    /// Takes the frame at the front of the replay buffer, which holds at least
+    /// a frame header, if it is plausible enough to end a salvage scan.
+    fn read_plausible(&mut self, offset: u64, resyncing: bool) -> Result<RawFrame, JournalError> {
+        let header_len = self.header.frame_header_len();
+        let bytes = self.replay.make_contiguous();
+        let (frame, ext) = self
+            .header
+            .decode_frame_header(&bytes[..header_len], offset)?;
+        if let FrameKind::Unknown(kind) = frame.kind {
+            return Err(JournalError::InvalidFrame {
+                offset,
+                reason: format!("unknown frame kind 0x{:02x}", kind),
+            });
+        }
+        let frame_len = header_len + frame.len as usize;
+        if resyncing && frame_len > SALVAGE_WINDOW {
+            return Err(JournalError::InvalidFrame {
+                offset,
+                reason: format!(
+                    "frame of {} bytes is too long to resynchronize on",
+                    frame_len
+                ),
+            });
+        }
+        if self.buffer(frame_len)? < frame_len {
+            return Err(JournalError::TruncatedFrame {
+                offset: offset + header_len as u64,
+            });
+        }
 
-        Ok(Some((frame.kind, payload)))
+        let payload = &self.replay.make_contiguous()[header_len..frame_len];
+        ext.verify_checksum(&frame, payload, offset)?;
+        match frame.kind {
+            FrameKind::EventJson => {
+                serde_json::from_str::<EventJson>(std::str::from_utf8(payload)?)?;
+            }
+            FrameKind::ChainHead => {
+                ChainHead::from_bytes(payload, offset)?;
+            }
+            FrameKind::Index => {
+                IndexSegment::from_bytes(payload, offset)?;
+            }
+            FrameKind::Unknown(_) => unreachable!("rejected above"),
+        }
+        let payload = payload.to_vec();
+        self.replay.drain(..frame_len);
+        Ok(RawFrame {
+            offset,
+            end: offset + frame_len as u64,
+            frame,
+            ext,
+            payload,
+        })
+    }
+
+    /// Reads the remaining frames and returns the final chain head.
+    ///
+    /// Fails with [`JournalError::ChainBroken`] at the first frame whose stored
+    /// chain value does not match, and with [`JournalError::InvalidHeader`] if
+    /// the journal is not hash-chained.
+    pub fn verify_chain(&mut self) -> Result<Digest, JournalError> {
+        if self.chain.is_none() {
+            return Err(JournalError::InvalidHeader(
+                "journal is not hash-chained".to_string(),
+            ));
+        }
+        while self.read_frame()?.is_some() {}
+        Ok(self.chain_head().expect("chain state present"))
     }
 
     /// Reads the next event JSON from the journal.
@@ -172,22 +529,407 @@ impl JournalReader {
     /// - Truncation detected (in strict mode)
     /// - I/O error occurs
     pub fn read_event(&mut self) -> Result<Option<EventJson>, JournalError> {
+        Ok(self.read_event_with_info()?.map(|(_, event)| event))
+    }
+
+    /// Reads the next event like [`read_event`](Self::read_event), together
+    /// with the location of its frame.
+    pub fn read_event_with_info(&mut self) -> Result<Option<(FrameInfo, EventJson)>, JournalError> {
         loop {
-            match self.read_frame()? {
+            match self.read_frame_with_info()? {
                 None => return Ok(None),
-                Some((FrameKind::EventJson, payload)) => {
+                Some((info, payload)) if info.kind() == FrameKind::EventJson => {
                     // Validate UTF-8
                     let utf8_str = std::str::from_utf8(&payload)?;
                     // Parse JSON
                     let json: EventJson =
                         serde_json::from_str(utf8_str).map_err(JournalError::JsonParse)?;
-                    return Ok(Some(json));
+                    if self.mode == ReadMode::Strict && self.header.has_canonical_payloads() {
+                        event::ensure_canonical_payload(&payload, &json, info.offset)?;
+                    }
+                    return Ok(Some((info, json)));
                 }
-                Some((FrameKind::Unknown(_), _)) => {
-                    // Skip unknown frame kinds
+                Some(_) => {
+                    // Skip trailers, index segments and unknown frame kinds
                     continue;
                 }
             }
         }
     }
+
+    /// Iterates over the remaining frames with their locations.
+    ///
+    /// The iterator stops after the first error.
+    pub fn frames(&mut self) -> Frames<'_, R> {
+        Frames {
+            reader: self,
+            done: false,
+        }
+    }
+
+    /// Iterates over the remaining events with the locations of their frames.
+    ///
+    /// The iterator stops after the first error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use northroot_journal::{JournalReader, ReadMode};
+    ///
+    /// let mut reader = JournalReader::open("events.nrj", ReadMode::Strict)?;
+    /// for item in reader.events() {
+    ///     let (info, event) = item?;
+    ///     println!("{} at offset {}", event["event_id"], info.offset);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn events(&mut self) -> Events<'_, R> {
+        Events {
+            reader: self,
+            done: false,
+        }
+    }
+}
+
+/// Frame iterator returned by [`JournalReader::frames`].
+pub struct Frames<'a, R> {
+    reader: &'a mut JournalReader<R>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for Frames<'_, R> {
+    type Item = Result<(FrameInfo, Vec<u8>), JournalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.reader.read_frame_with_info().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+/// Event iterator returned by [`JournalReader::events`].
+pub struct Events<'a, R> {
+    reader: &'a mut JournalReader<R>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for Events<'_, R> {
+    type Item = Result<(FrameInfo, EventJson), JournalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.reader.read_event_with_info().transpose();
+        self.done = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+impl<R: Read + Seek> JournalReader<R> {
+    /// Positions the reader at the frame starting at `offset`, e.g. an offset
+    /// returned by a [`SidecarIndex`](crate::SidecarIndex).
+    ///
+    /// The offset is not validated until the next read. Like the other seeks,
+    /// this stops hash chain verification.
+    pub fn seek_offset(&mut self, offset: u64) -> Result<(), JournalError> {
+        self.seek_inner(io::SeekFrom::Start(offset))?;
+        self.position = offset;
+        self.ordinal = None;
+        self.chain = None;
+        Ok(())
+    }
+
+    /// Positions the reader at `offset`, the end of `frames` frames already
+    /// verified, and continues hash chain verification from `chain`.
+    pub(crate) fn resume(
+        &mut self,
+        offset: u64,
+        frames: u64,
+        chain: Option<ChainState>,
+    ) -> Result<(), JournalError> {
+        self.seek_inner(io::SeekFrom::Start(offset))?;
+        self.position = offset;
+        self.ordinal = Some(frames);
+        self.chain = chain;
+        Ok(())
+    }
+
+    /// Positions the reader at the event with zero-based ordinal `n`, counting
+    /// only `EventJson` frames.
+    ///
+    /// Uses the latest index segment when the journal has one and scans the
+    /// frames after it (or the whole file) otherwise. Returns `false`, leaving
+    /// the position unchanged, if the journal has fewer than `n + 1` events.
+    ///
+    /// Seeking skips frames, so hash chain verification stops:
+    /// [`chain_head`](Self::chain_head) returns `None` afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use northroot_journal::{JournalReader, ReadMode};
+    ///
+    /// let mut reader = JournalReader::open("events.nrj", ReadMode::Strict)?;
+    /// if reader.seek_ordinal(41)? {
+    ///     let event = reader.read_event()?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if scanning fails or the index points at a
+    /// frame that is not the expected event.
+    pub fn seek_ordinal(&mut self, n: u64) -> Result<bool, JournalError> {
+        self.restoring_position(|reader| {
+            let mut entry = reader.index_entry(n)?;
+            if entry.is_none() {
+                reader.extend_index()?;
+                entry = reader.index_entry(n)?;
+            }
+            reader.seek_to(entry)
+        })
+    }
+
+    /// Positions the reader at the first event whose `event_id` is `event_id`.
+    ///
+    /// Lookup works like [`seek_ordinal`](Self::seek_ordinal). Returns `false`,
+    /// leaving the position unchanged, if no event has that ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if scanning fails or the index points at a
+    /// frame that is not the expected event.
+    pub fn seek_event(&mut self, event_id: &Digest) -> Result<bool, JournalError> {
+        let Some(key) = index::digest_key(event_id) else {
+            return Ok(false);
+        };
+        self.restoring_position(|reader| {
+            let mut ordinal = reader.index_lookup(&key)?;
+            if ordinal.is_none() {
+                reader.extend_index()?;
+                ordinal = reader.index_lookup(&key)?;
+            }
+            let entry = match ordinal {
+                Some(n) => reader.index_entry(n)?,
+                None => None,
+            };
+            reader.seek_to(entry)
+        })
+    }
+
+    /// Seeks the source, dropping bytes read ahead while salvaging.
+    fn seek_inner(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.replay.clear();
+        self.inner.seek(pos)
+    }
+
+    /// Runs `f`, which may read anywhere in the stream, then moves the stream
+    /// back to the (possibly updated) read position.
+    fn restoring_position<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, JournalError>,
+    ) -> Result<T, JournalError> {
+        let result = f(self);
+        self.seek_inner(io::SeekFrom::Start(self.position))?;
+        result
+    }
+
+    /// Reads the frame at `offset`; see [`read_next_raw`](Self::read_next_raw).
+    fn read_raw_frame(&mut self, offset: u64) -> Result<Option<RawFrame>, JournalError> {
+        self.seek_inner(io::SeekFrom::Start(offset))?;
+        self.read_next_raw(offset)
+    }
+
+    fn index_entry(&mut self, n: u64) -> Result<Option<IndexEntry>, JournalError> {
+        let index = self.load_index()?;
+        Ok(index.entries.get(n as usize).copied())
+    }
+
+    fn index_lookup(&mut self, key: &index::IndexKey) -> Result<Option<u64>, JournalError> {
+        let index = self.load_index()?;
+        Ok(index.by_id.get(key).copied())
+    }
+
+    /// Moves to `entry` after checking that it points at the event it describes.
+    fn seek_to(&mut self, entry: Option<IndexEntry>) -> Result<bool, JournalError> {
+        let Some(entry) = entry else {
+            return Ok(false);
+        };
+        let mismatch = || JournalError::InvalidFrame {
+            offset: entry.offset,
+            reason: "index entry does not match the event at its offset".to_string(),
+        };
+        let raw = self.read_raw_frame(entry.offset)?.ok_or_else(mismatch)?;
+        if raw.frame.kind != FrameKind::EventJson {
+            return Err(mismatch());
+        }
+        if index::payload_key(&raw.payload) != entry.event_id {
+            return Err(mismatch());
+        }
+        self.position = entry.offset;
+        self.ordinal = None;
+        self.chain = None;
+        Ok(true)
+    }
+
+    /// Returns the event index, building it on first use.
+    fn load_index(&mut self) -> Result<&mut JournalIndex, JournalError> {
+        if self.index.is_none() {
+            let index = match self.locate_tail_segment()? {
+                Some(offset) => self.read_segments(offset)?,
+                None => None,
+            };
+            self.index = Some(index.unwrap_or_else(|| JournalIndex {
+                scanned_until: JournalHeader::HEADER_SIZE as u64,
+                ..Default::default()
+            }));
+            self.extend_index()?;
+        }
+        Ok(self.index.as_mut().expect("index loaded"))
+    }
+
+    /// Scans frames not yet accounted for by the index, up to end-of-file.
+    fn extend_index(&mut self) -> Result<(), JournalError> {
+        let Some(mut index) = self.index.take() else {
+            self.load_index()?;
+            return Ok(());
+        };
+        let result = self.scan_into(&mut index);
+        self.index = Some(index);
+        result
+    }
+
+    fn scan_into(&mut self, index: &mut JournalIndex) -> Result<(), JournalError> {
+        let mut offset = index.scanned_until;
+        self.seek_inner(io::SeekFrom::Start(offset))?;
+        while let Some(raw) = self.read_next_raw(offset)? {
+            match raw.frame.kind {
+                FrameKind::EventJson => {
+                    let event_id = index::payload_key(&raw.payload);
+                    index.push(IndexEntry { offset, event_id });
+                }
+                FrameKind::Index => index.mark_segment(offset),
+                FrameKind::ChainHead | FrameKind::Unknown(_) => {}
+            }
+            offset = raw.end;
+        }
+        index.scanned_until = offset;
+        Ok(())
+    }
+
+    /// Consumes the reader and returns the events covered by the index
+    /// segment at the end of the journal, if there is one, without scanning.
+    /// [`JournalIndex::scanned_until`] is the end of that segment.
+    pub(crate) fn into_segment_index(mut self) -> Result<Option<JournalIndex>, JournalError> {
+        match self.locate_tail_segment()? {
+            Some(offset) => self.read_segments(offset),
+            None => Ok(None),
+        }
+    }
+
+    /// Finds an index segment ending at end-of-file (before a `ChainHead`
+    /// trailer, if any).
+    fn locate_tail_segment(&mut self) -> Result<Option<u64>, JournalError> {
+        let header_len = JournalHeader::HEADER_SIZE as u64;
+        let frame_header_len = self.header.frame_header_len() as u64;
+        let mut end = self.seek_inner(io::SeekFrom::End(0))?;
+
+        if self.header.has_hash_chain() {
+            let trailer_len = ChainHead::frame_len(&self.header) as u64;
+            if end >= header_len + trailer_len {
+                let trailer = self.read_raw_frame(end - trailer_len).ok().flatten();
+                if trailer.is_some_and(|raw| {
+                    raw.frame.kind == FrameKind::ChainHead
+                        && raw.payload.len() == CHAIN_HEAD_PAYLOAD_SIZE
+                        && raw.end == end
+                }) {
+                    end -= trailer_len;
+                }
+            }
+        }
+
+        if end < header_len + frame_header_len + INDEX_FOOTER_SIZE as u64 {
+            return Ok(None);
+        }
+        let mut footer = [0u8; INDEX_FOOTER_SIZE];
+        self.inner
+            .seek(io::SeekFrom::Start(end - INDEX_FOOTER_SIZE as u64))?;
+        self.inner.read_exact(&mut footer)?;
+        let Some(count) = IndexSegment::footer_count(&footer) else {
+            return Ok(None);
+        };
+        let frame_len = (IndexSegment::payload_len(count) as u64).saturating_add(frame_header_len);
+        let Some(start) = end.checked_sub(frame_len).filter(|s| *s >= header_len) else {
+            return Ok(None);
+        };
+        let found = self.read_raw_frame(start).ok().flatten();
+        Ok(found
+            .is_some_and(|raw| raw.frame.kind == FrameKind::Index && raw.end == end)
+            .then_some(start))
+    }
+
+    /// Loads the chain of index segments ending at `offset`.
+    ///
+    /// Returns `None` if the segments are unreadable or do not cover a
+    /// contiguous range of ordinals starting at zero, so the caller can fall
+    /// back to scanning.
+    fn read_segments(&mut self, offset: u64) -> Result<Option<JournalIndex>, JournalError> {
+        let mut segments = Vec::new();
+        let mut next = Some(offset);
+        let mut scanned_until = None;
+        while let Some(offset) = next {
+            let Some(raw) = self.read_raw_frame(offset).ok().flatten() else {
+                return Ok(None);
+            };
+            if raw.frame.kind != FrameKind::Index {
+                return Ok(None);
+            }
+            let Ok(segment) = IndexSegment::from_bytes(&raw.payload, offset) else {
+                return Ok(None);
+            };
+            scanned_until.get_or_insert(raw.end);
+            // Segments link strictly backwards, which also rules out cycles.
+            next = match segment.prev_offset {
+                Some(prev) if prev < offset => Some(prev),
+                Some(_) => return Ok(None),
+                None => None,
+            };
+            segments.push((offset, segment));
+        }
+
+        let mut index = JournalIndex::default();
+        for (offset, segment) in segments.into_iter().rev() {
+            if segment.first_ordinal != index.entries.len() as u64 {
+                return Ok(None);
+            }
+            for entry in segment.entries {
+                if entry.offset >= offset {
+                    return Ok(None);
+                }
+                index.push(entry);
+            }
+            index.mark_segment(offset);
+        }
+        index.scanned_until = scanned_until.unwrap_or(JournalHeader::HEADER_SIZE as u64);
+        Ok(Some(index))
+    }
+}
+
+/// Reads until `buf` is full or the source is exhausted; returns the bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
 }
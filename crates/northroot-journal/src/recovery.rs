@@ -0,0 +1,413 @@
+//! Recovery of journals with a torn tail.
+//!
+//! A crash in the middle of an append can leave a partial frame at the end of
+//! a journal. [`ReadMode::Permissive`] reads stop just before it, but a writer
+//! appending after it would bury the damage in the middle of the file, where
+//! every strict reader fails. [`JournalRecovery`] finds the end of the last
+//! valid frame so the damaged tail can be inspected, copied aside and cut off;
+//! [`JournalWriter`](crate::JournalWriter) refuses to append until it is.
+//!
+//! Damage is only a torn tail if no valid frame follows it. Otherwise the
+//! journal is corrupted in the middle, and [`JournalRecovery::truncate`]
+//! refuses to discard the frames after the damage.
+//!
+//! Without checksums, space preallocated before a crash reads as frames of
+//! kind 0 and length 0, and a torn payload goes unnoticed until it is parsed.
+//! Both count as damage: a kind-0 empty frame is never valid, and the last
+//! event of a journal must hold JSON.
+
+use crate::chain::ChainState;
+use crate::errors::JournalError;
+use crate::event::EventJson;
+use crate::frame::{FrameKind, JournalHeader, RecordFrame};
+use crate::index::{self, IndexEntry, JournalIndex};
+use crate::lock::{self, LockPolicy};
+use crate::reader::{JournalReader, ReadMode};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+
+/// Result of scanning a journal for a damaged tail.
+///
+/// Scanning never modifies the journal. [`quarantine`](Self::quarantine) and
+/// [`truncate`](Self::truncate) act on the tail found by the scan and refuse
+/// to run if the journal has changed length since; `truncate` also refuses
+/// if valid frames follow the damage (see [`resumes_at`](Self::resumes_at)).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::JournalRecovery;
+///
+/// let recovery = JournalRecovery::scan("events.nrj")?;
+/// if let Some(damage) = recovery.damage().filter(|_| recovery.is_torn_tail()) {
+///     println!("discarding {} bytes: {}", recovery.discarded_len(), damage);
+///     recovery.quarantine("events.nrj.torn")?;
+///     recovery.truncate()?;
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct JournalRecovery {
+    path: PathBuf,
+    file_len: u64,
+    valid_len: u64,
+    frames: u64,
+    events: u64,
+    damage: Option<JournalError>,
+    resumes_at: Option<u64>,
+}
+
+impl JournalRecovery {
+    /// Reads the journal at `path` in strict mode up to the first damaged frame.
+    ///
+    /// Every frame before it has passed the same checks as a strict read,
+    /// including checksums and the hash chain, and every event holds JSON.
+    /// From the damaged frame on, the journal is read like in
+    /// [`ReadMode::Salvage`] to find out whether any valid frame follows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the file cannot be read or its header is
+    /// invalid; a journal without a valid header cannot be repaired by
+    /// truncation.
+    pub fn scan<P: AsRef<Path>>(path: P) -> Result<Self, JournalError> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        let file_len = file.metadata()?.len();
+        let mut reader = JournalReader::new(BufReader::new(file), ReadMode::Strict)?;
+
+        let mut valid_len = reader.position();
+        let (mut frames, mut events) = (0, 0);
+        let damage = loop {
+            match reader.read_frame_with_info() {
+                Ok(Some((info, payload))) => {
+                    if let Err(reason) = check_frame(&info.header, &payload) {
+                        let offset = info.offset;
+                        break Some(JournalError::InvalidFrame { offset, reason });
+                    }
+                    valid_len = reader.position();
+                    frames += 1;
+                    if info.kind() == FrameKind::EventJson {
+                        events += 1;
+                    }
+                }
+                Ok(None) => break None,
+                Err(JournalError::Io(e)) => return Err(e.into()),
+                Err(e) => break Some(e),
+            }
+        };
+
+        let resumes_at = match damage {
+            Some(_) => resync(&path, valid_len)?,
+            None => None,
+        };
+
+        Ok(Self {
+            path,
+            file_len,
+            valid_len,
+            frames,
+            events,
+            damage,
+            resumes_at,
+        })
+    }
+
+    /// Returns `true` if every byte of the journal belongs to a valid frame.
+    pub fn is_clean(&self) -> bool {
+        self.damage.is_none()
+    }
+
+    /// Returns `true` if the journal is damaged and nothing valid follows the
+    /// damage, so truncation only discards the damaged tail.
+    pub fn is_torn_tail(&self) -> bool {
+        self.damage.is_some() && self.resumes_at.is_none()
+    }
+
+    /// Returns the offset of the first valid frame after the damage, if any.
+    ///
+    /// `Some` means the journal is corrupted in the middle rather than torn
+    /// at the end; it cannot be repaired by truncation.
+    pub fn resumes_at(&self) -> Option<u64> {
+        self.resumes_at
+    }
+
+    /// Returns the error that ended the scan, if the tail is damaged.
+    pub fn damage(&self) -> Option<&JournalError> {
+        self.damage.as_ref()
+    }
+
+    /// Returns the journal length at the time of the scan.
+    pub fn file_len(&self) -> u64 {
+        self.file_len
+    }
+
+    /// Returns the length of the valid prefix, i.e. the end of the last valid frame.
+    pub fn valid_len(&self) -> u64 {
+        self.valid_len
+    }
+
+    /// Returns the number of bytes that truncation would discard.
+    pub fn discarded_len(&self) -> u64 {
+        self.file_len - self.valid_len
+    }
+
+    /// Returns the number of valid frames, of any kind.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Returns the number of valid `EventJson` frames.
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+
+    /// Copies the bytes that truncation would discard to a new file at `dest`.
+    ///
+    /// Returns the number of bytes copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if `dest` already exists, the journal changed
+    /// length since the scan, or an I/O error occurs.
+    pub fn quarantine<P: AsRef<Path>>(&self, dest: P) -> Result<u64, JournalError> {
+        let mut journal = File::open(&self.path)?;
+        self.check_unchanged(&journal)?;
+        journal.seek(io::SeekFrom::Start(self.valid_len))?;
+
+        let mut out = OpenOptions::new().write(true).create_new(true).open(dest)?;
+        let copied = io::copy(&mut journal.take(self.discarded_len()), &mut out)?;
+        out.sync_all()?;
+        Ok(copied)
+    }
+
+    /// Cuts the journal back to its valid prefix.
+    ///
+    /// Does nothing if the journal is clean. Any `.nrx` sidecar is rebuilt the
+    /// next time it is opened.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError::DamagedMidFile`] if valid frames follow the
+    /// damage, and [`JournalError`] if the journal changed length since the
+    /// scan, is locked by a writer or reader, or an I/O error occurs.
+    pub fn truncate(&self) -> Result<(), JournalError> {
+        if self.is_clean() {
+            return Ok(());
+        }
+        if let Some(resumes_at) = self.resumes_at {
+            return Err(JournalError::DamagedMidFile {
+                offset: self.valid_len,
+                resumes_at,
+            });
+        }
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+        lock::lock_exclusive(&file, LockPolicy::Fail)?;
+        self.check_unchanged(&file)?;
+        file.set_len(self.valid_len)?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    fn check_unchanged(&self, file: &File) -> Result<(), JournalError> {
+        if file.metadata()?.len() != self.file_len {
+            return Err(io::Error::other("journal changed since it was scanned").into());
+        }
+        Ok(())
+    }
+}
+
+/// Returns the offset of the first plausible frame at or after `offset`, read
+/// as in [`ReadMode::Salvage`].
+fn resync(path: &Path, offset: u64) -> Result<Option<u64>, JournalError> {
+    let file = File::open(path)?;
+    let mut reader = JournalReader::new(BufReader::new(file), ReadMode::Salvage)?;
+    reader.seek_offset(offset)?;
+    Ok(reader.read_frame_with_info()?.map(|(info, _)| info.offset))
+}
+
+/// Checks what a strict read does not: that the frame is not zero-filled
+/// space, and that an event holds JSON even without a checksum.
+fn check_frame(frame: &RecordFrame, payload: &[u8]) -> Result<(), String> {
+    if is_zero_fill(frame) {
+        return Err("empty frame of kind 0x00 (zero-filled space)".to_string());
+    }
+    if frame.kind == FrameKind::EventJson {
+        parse_event(payload).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Returns `true` for the frame that zero-filled space decodes as, which no
+/// writer produces.
+pub(crate) fn is_zero_fill(frame: &RecordFrame) -> bool {
+    frame.kind == FrameKind::Unknown(0) && frame.len == 0
+}
+
+fn parse_event(payload: &[u8]) -> Result<EventJson, JournalError> {
+    Ok(serde_json::from_str(std::str::from_utf8(payload)?)?)
+}
+
+/// Frames walked by [`check_tail`].
+#[derive(Debug, Default)]
+pub(crate) struct TailScan {
+    /// Number of `EventJson` frames walked.
+    pub events: u64,
+    /// Offset of the last `EventJson` frame walked.
+    pub last_event: Option<u64>,
+}
+
+/// Checks that `stream` (of length `len`) ends with a complete frame, walking
+/// the frames from `from`, which must be a frame boundary.
+///
+/// Only frame headers are read, and the last frame is fully verified, so a
+/// writer can rule out a torn tail without reading every payload; a last
+/// event must hold JSON, and zero-filled space is never a frame. Payloads
+/// are read as well where needed to extend `index` with the walked events and
+/// to advance `chain` over every walked frame; the same walk then serves all
+/// three. Fails with [`JournalError::TornTail`] at the first frame that does
+/// not fit.
+pub(crate) fn check_tail<S: Read + Seek>(
+    stream: &mut S,
+    header: &JournalHeader,
+    from: u64,
+    len: u64,
+    mut index: Option<&mut JournalIndex>,
+    mut chain: Option<&mut ChainState>,
+) -> Result<TailScan, JournalError> {
+    let frame_header_len = header.frame_header_len();
+    let mut stream = BufReader::new(stream);
+    let mut offset = stream.seek(io::SeekFrom::Start(from))?;
+    let mut scan = TailScan::default();
+
+    while offset < len {
+        let torn = JournalError::TornTail { offset };
+        if len - offset < frame_header_len as u64 {
+            return Err(torn);
+        }
+        let mut header_bytes = vec![0u8; frame_header_len];
+        stream.read_exact(&mut header_bytes)?;
+        let Ok((frame, ext)) = header.decode_frame_header(&header_bytes, offset) else {
+            return Err(torn);
+        };
+        if is_zero_fill(&frame) {
+            return Err(torn);
+        }
+        let end = offset + frame_header_len as u64 + frame.len as u64;
+        if end > len {
+            return Err(torn);
+        }
+
+        let is_event = frame.kind == FrameKind::EventJson;
+        let last = end == len;
+        if last || chain.is_some() || (is_event && index.is_some()) {
+            let mut payload = vec![0u8; frame.len as usize];
+            stream.read_exact(&mut payload)?;
+            if last {
+                ext.verify_checksum(&frame, &payload, offset)
+                    .map_err(|_| torn)?;
+                if is_event && parse_event(&payload).is_err() {
+                    return Err(JournalError::TornTail { offset });
+                }
+            } else {
+                ext.verify_checksum(&frame, &payload, offset)?;
+            }
+            if let Some(state) = chain.as_deref_mut() {
+                let prev = ext.prev_chain.expect("hash-chained header");
+                state.advance(offset, &frame, &prev, &payload)?;
+            }
+            if let (Some(index), true) = (index.as_deref_mut(), is_event) {
+                let event_id = index::payload_key(&payload);
+                index.push(IndexEntry { offset, event_id });
+            }
+        } else {
+            stream.seek_relative(frame.len as i64)?;
+        }
+        if is_event {
+            scan.events += 1;
+            scan.last_event = Some(offset);
+        } else if let (Some(index), FrameKind::Index) = (index.as_deref_mut(), frame.kind) {
+            index.mark_segment(offset);
+        }
+        offset = end;
+    }
+
+    if let Some(index) = index {
+        index.scanned_until = len;
+    }
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::{JournalWriter, WriteOptions};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn journal(checksums: bool) -> Vec<u8> {
+        let options = WriteOptions {
+            checksums,
+            ..Default::default()
+        };
+        let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+        for n in 0..3 {
+            writer.append_event(&json!({"n": n})).unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    fn check(bytes: &[u8]) -> Result<u64, JournalError> {
+        let header = JournalHeader::from_bytes(&bytes[..JournalHeader::HEADER_SIZE]).unwrap();
+        let from = JournalHeader::HEADER_SIZE as u64;
+        let len = bytes.len() as u64;
+        let scan = check_tail(&mut Cursor::new(bytes), &header, from, len, None, None)?;
+        Ok(scan.events)
+    }
+
+    #[test]
+    fn check_tail_accepts_complete_frames() {
+        assert_eq!(check(&journal(false)).unwrap(), 3);
+        assert_eq!(check(&journal(true)).unwrap(), 3);
+        assert_eq!(
+            check(&journal(false)[..JournalHeader::HEADER_SIZE]).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn check_tail_rejects_partial_frame() {
+        let bytes = journal(false);
+        for cut in [1, 4, 12] {
+            let err = check(&bytes[..bytes.len() - cut]).unwrap_err();
+            assert!(matches!(err, JournalError::TornTail { .. }), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn check_tail_rejects_zero_filled_space() {
+        let mut bytes = journal(false);
+        let len = bytes.len() as u64;
+        bytes.extend_from_slice(&[0; 64]);
+        assert!(matches!(check(&bytes), Err(JournalError::TornTail { offset }) if offset == len));
+    }
+
+    #[test]
+    fn check_tail_parses_last_event() {
+        let mut bytes = journal(false);
+        let len = bytes.len() as u64;
+        // A complete frame header whose payload was never written
+        bytes.extend_from_slice(&[0x01, 0, 0, 0, 16, 0, 0, 0]);
+        bytes.extend_from_slice(&[0; 16]);
+        assert!(matches!(check(&bytes), Err(JournalError::TornTail { offset }) if offset == len));
+    }
+
+    #[test]
+    fn check_tail_verifies_last_checksum() {
+        let mut bytes = journal(true);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(matches!(check(&bytes), Err(JournalError::TornTail { .. })));
+    }
+}
@@ -0,0 +1,844 @@
+//! External `.nrx` index files kept next to a journal.
+//!
+//! A sidecar maps `event_id`, `event_type`, `principal_id` and `occurred_at` to
+//! the byte offsets of event frames without writing anything to the journal
+//! itself. It is a cache: it can always be rebuilt from the journal, and
+//! [`SidecarIndex::open`] does so whenever it no longer matches. Writers opened
+//! with [`WriteOptions::sidecar`](crate::WriteOptions::sidecar) update it when
+//! they finish.
+//!
+//! File layout (little-endian):
+//!
+//! ```text
+//! header (96 bytes):
+//!   magic "NRX1" | version u16 | reserved u16 | run_count u32 | reserved u32
+//!   journal_len u64 | chain_digest [32] | tail_digest [32] | event_count u64
+//! runs (run_count times):
+//!   counts [u64; 4]
+//!   4 tables of entries, one per field: [key 32 bytes][offset u64], sorted
+//! ```
+//!
+//! Each run covers the events appended since the previous run, so updating a
+//! sidecar after an append only writes one small run. Lookups binary-search
+//! every run; once there are [`MAX_RUNS`] runs they are merged into one.
+//!
+//! The digests bind the sidecar to the journal prefix it covers. The prefix
+//! is split into 64 KiB blocks; `chain_digest` chains SHA-256 over every block
+//! but the last, and `tail_digest` covers the last, possibly partial, block
+//! together with `journal_len` and `chain_digest`. Opening a sidecar only
+//! hashes that last block again, so it costs the same for any journal size,
+//! and an update continues the chain over the appended bytes as it indexes
+//! them. A change further back in the prefix goes unnoticed until
+//! [`SidecarIndex::verify`] hashes every block again or
+//! [`SidecarIndex::rebuild`] replaces the sidecar. Entries are hints either
+//! way; callers should check the event they find at an offset.
+
+use crate::errors::JournalError;
+use crate::event::EventJson;
+use crate::frame::FrameKind;
+use crate::index;
+use crate::reader::{JournalReader, ReadMode};
+use northroot_canonical::Digest;
+use sha2::{Digest as Sha2Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+/// Magic bytes at the start of a sidecar file: `b"NRX1"`.
+pub const SIDECAR_MAGIC: &[u8; 4] = b"NRX1";
+
+/// Sidecar format version.
+pub const SIDECAR_VERSION: u16 = 0x0003;
+
+/// File extension of sidecar files.
+pub const SIDECAR_EXTENSION: &str = "nrx";
+
+/// Number of runs after which a sidecar is compacted into a single run.
+pub const MAX_RUNS: u32 = 8;
+
+const HEADER_SIZE: usize = 96;
+const KEY_SIZE: usize = 32;
+const ENTRY_SIZE: usize = KEY_SIZE + 8;
+const FIELD_COUNT: usize = 4;
+const RUN_HEADER_SIZE: usize = FIELD_COUNT * 8;
+const BLOCK_SIZE: u64 = 64 * 1024;
+const CHAIN_DOMAIN: &[u8] = b"northroot:nrx-chain:v3\0";
+const TAIL_DOMAIN: &[u8] = b"northroot:nrx-tail:v3\0";
+
+type Key = [u8; KEY_SIZE];
+
+/// Event fields covered by a sidecar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SidecarField {
+    /// `event_id` (exact match).
+    EventId,
+    /// `event_type` (exact match).
+    EventType,
+    /// `principal_id` (exact match).
+    PrincipalId,
+    /// `occurred_at` (ordered, supports ranges).
+    OccurredAt,
+}
+
+impl SidecarField {
+    const ALL: [SidecarField; FIELD_COUNT] = [
+        SidecarField::EventId,
+        SidecarField::EventType,
+        SidecarField::PrincipalId,
+        SidecarField::OccurredAt,
+    ];
+
+    fn table(self) -> usize {
+        self as usize
+    }
+}
+
+/// What [`SidecarIndex::open`] had to do to bring the sidecar up to date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidecarRefresh {
+    /// The sidecar already covered the whole journal.
+    Current,
+    /// Events appended to the journal were added to the sidecar.
+    Updated {
+        /// Number of events added.
+        events: u64,
+    },
+    /// The sidecar was missing, unreadable or stale and was rebuilt.
+    Rebuilt,
+}
+
+/// Sidecar index over a journal file.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::{JournalReader, ReadMode, SidecarIndex};
+///
+/// let mut sidecar = SidecarIndex::open("events.nrj")?;
+/// let mut reader = JournalReader::open("events.nrj", ReadMode::Strict)?;
+/// for offset in sidecar.find_event_type("authorization")? {
+///     reader.seek_offset(offset)?;
+///     let event = reader.read_event()?;
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct SidecarIndex {
+    file: File,
+    header: SidecarHeader,
+    runs: Vec<Run>,
+    refresh: SidecarRefresh,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SidecarHeader {
+    run_count: u32,
+    journal_len: u64,
+    chain_digest: [u8; 32],
+    tail_digest: [u8; 32],
+    event_count: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    counts: [u64; FIELD_COUNT],
+    /// Offset of each field's table.
+    tables: [u64; FIELD_COUNT],
+    /// Offset just past the run.
+    end: u64,
+}
+
+impl Run {
+    /// Lays out a run at `offset` with `counts` entries per table, or returns
+    /// `None` if its extent does not fit in a `u64`.
+    fn new(offset: u64, counts: [u64; FIELD_COUNT]) -> Option<Self> {
+        let mut tables = [0u64; FIELD_COUNT];
+        let mut next = offset.checked_add(RUN_HEADER_SIZE as u64)?;
+        for (table, count) in tables.iter_mut().zip(counts) {
+            *table = next;
+            next = next.checked_add(count.checked_mul(ENTRY_SIZE as u64)?)?;
+        }
+        Some(Self {
+            counts,
+            tables,
+            end: next,
+        })
+    }
+
+    fn table_offset(&self, table: usize) -> u64 {
+        self.tables[table]
+    }
+}
+
+/// Sorted entries for each field, ready to be written as a run.
+#[derive(Debug, Default)]
+struct RunBuilder {
+    tables: [Vec<(Key, u64)>; FIELD_COUNT],
+    events: u64,
+}
+
+impl RunBuilder {
+    fn add_event(&mut self, offset: u64, event: &EventJson) {
+        for field in SidecarField::ALL {
+            if let Some(key) = event_field_key(field, event) {
+                self.tables[field.table()].push((key, offset));
+            }
+        }
+        self.events += 1;
+    }
+
+    fn encode(&mut self) -> Vec<u8> {
+        let total: usize = self.tables.iter().map(Vec::len).sum();
+        let mut bytes = Vec::with_capacity(RUN_HEADER_SIZE + total * ENTRY_SIZE);
+        for table in &self.tables {
+            bytes.extend_from_slice(&(table.len() as u64).to_le_bytes());
+        }
+        for table in &mut self.tables {
+            table.sort_unstable();
+            for (key, offset) in table.iter() {
+                bytes.extend_from_slice(key);
+                bytes.extend_from_slice(&offset.to_le_bytes());
+            }
+        }
+        bytes
+    }
+}
+
+impl SidecarHeader {
+    fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(SIDECAR_MAGIC);
+        bytes[4..6].copy_from_slice(&SIDECAR_VERSION.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.run_count.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.journal_len.to_le_bytes());
+        bytes[24..56].copy_from_slice(&self.chain_digest);
+        bytes[56..88].copy_from_slice(&self.tail_digest);
+        bytes[88..96].copy_from_slice(&self.event_count.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Option<Self> {
+        if &bytes[0..4] != SIDECAR_MAGIC || bytes[4..6] != SIDECAR_VERSION.to_le_bytes() {
+            return None;
+        }
+        Some(Self {
+            run_count: u32::from_le_bytes(bytes[8..12].try_into().ok()?),
+            journal_len: read_u64(&bytes[16..24]),
+            chain_digest: bytes[24..56].try_into().ok()?,
+            tail_digest: bytes[56..88].try_into().ok()?,
+            event_count: read_u64(&bytes[88..96]),
+        })
+    }
+}
+
+impl SidecarIndex {
+    /// Returns the sidecar path for a journal: the journal path with a `.nrx`
+    /// extension.
+    pub fn path_for<P: AsRef<Path>>(journal: P) -> PathBuf {
+        journal.as_ref().with_extension(SIDECAR_EXTENSION)
+    }
+
+    /// Opens the sidecar for `journal`, creating, updating or rebuilding it as
+    /// needed so that it covers every complete frame in the journal.
+    ///
+    /// Only the last block of the prefix the sidecar covers is checked, so
+    /// opening reads at most 64 KiB plus whatever was appended since; see
+    /// [`verify`](Self::verify) for a full check.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the journal cannot be read or the sidecar
+    /// cannot be written.
+    pub fn open<P: AsRef<Path>>(journal: P) -> Result<Self, JournalError> {
+        let journal = journal.as_ref();
+        let path = Self::path_for(journal);
+        let mut journal_file = File::open(journal)?;
+        let journal_len = journal_file.metadata()?.len();
+
+        if let Some(mut sidecar) = Self::load(&path)? {
+            let header = &sidecar.header;
+            if header.journal_len <= journal_len {
+                let mut binding = Binding::resume(header.chain_digest, header.journal_len);
+                binding.extend(&mut journal_file, header.journal_len)?;
+                if binding.finish(header.journal_len)?.1 == header.tail_digest {
+                    if header.journal_len < journal_len {
+                        sidecar.update(journal_file, &path, binding)?;
+                    }
+                    return Ok(sidecar);
+                }
+            }
+        }
+
+        Self::rebuild_from(&path, journal_file)
+    }
+
+    /// Rebuilds the sidecar for `journal` from scratch, whether or not it
+    /// still matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the journal cannot be read or the sidecar
+    /// cannot be written.
+    pub fn rebuild<P: AsRef<Path>>(journal: P) -> Result<Self, JournalError> {
+        let journal = journal.as_ref();
+        Self::rebuild_from(&Self::path_for(journal), File::open(journal)?)
+    }
+
+    /// Hashes every byte of the journal prefix the sidecar covers and checks
+    /// it against the binding.
+    ///
+    /// Returns `false` if any of those bytes changed since the sidecar was
+    /// written, including further back than [`open`](Self::open) looks; the
+    /// sidecar should then be [rebuilt](Self::rebuild).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the journal cannot be read.
+    pub fn verify<P: AsRef<Path>>(&self, journal: P) -> Result<bool, JournalError> {
+        let mut journal_file = File::open(journal)?;
+        let len = self.header.journal_len;
+        if journal_file.metadata()?.len() < len {
+            return Ok(false);
+        }
+        let mut binding = Binding::new();
+        binding.extend(&mut journal_file, len)?;
+        Ok(binding.finish(len)? == (self.header.chain_digest, self.header.tail_digest))
+    }
+
+    /// Returns what [`open`](Self::open) did to the sidecar.
+    pub fn refresh(&self) -> SidecarRefresh {
+        self.refresh
+    }
+
+    /// Returns the number of events covered by the sidecar.
+    pub fn event_count(&self) -> u64 {
+        self.header.event_count
+    }
+
+    /// Returns the journal length the sidecar is bound to.
+    pub fn journal_len(&self) -> u64 {
+        self.header.journal_len
+    }
+
+    /// Returns the frame offset of the first event with this `event_id`.
+    pub fn find_event(&mut self, event_id: &Digest) -> Result<Option<u64>, JournalError> {
+        let Some(key) = index::digest_key(event_id) else {
+            return Ok(None);
+        };
+        Ok(self
+            .find_range(SidecarField::EventId, &key, &key)?
+            .into_iter()
+            .next())
+    }
+
+    /// Returns the frame offsets of events with this `event_type`, in journal order.
+    pub fn find_event_type(&mut self, event_type: &str) -> Result<Vec<u64>, JournalError> {
+        let key = string_key(SidecarField::EventType, event_type);
+        self.find_range(SidecarField::EventType, &key, &key)
+    }
+
+    /// Returns the frame offsets of events with this `principal_id`, in journal order.
+    pub fn find_principal(&mut self, principal_id: &str) -> Result<Vec<u64>, JournalError> {
+        let key = string_key(SidecarField::PrincipalId, principal_id);
+        self.find_range(SidecarField::PrincipalId, &key, &key)
+    }
+
+    /// Returns the frame offsets of events whose `occurred_at` lies within
+    /// `since..=until`, in journal order. Either bound may be omitted.
+    ///
+    /// Bounds are RFC 3339 UTC timestamps (`...Z`); events with a malformed
+    /// `occurred_at` are not indexed by time.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError::InvalidJson`] if a bound is not a valid timestamp.
+    pub fn find_occurred_between(
+        &mut self,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<u64>, JournalError> {
+        let bound = |value: Option<&str>, default: Key| match value {
+            None => Ok(default),
+            Some(ts) => timestamp_key(ts)
+                .ok_or_else(|| JournalError::InvalidJson(format!("invalid timestamp: {}", ts))),
+        };
+        let low = bound(since, [0u8; KEY_SIZE])?;
+        let high = bound(until, [0xffu8; KEY_SIZE])?;
+        if low > high {
+            return Ok(Vec::new());
+        }
+        self.find_range(SidecarField::OccurredAt, &low, &high)
+    }
+
+    /// Returns the offsets of entries with `low <= key <= high`, sorted.
+    fn find_range(
+        &mut self,
+        field: SidecarField,
+        low: &Key,
+        high: &Key,
+    ) -> Result<Vec<u64>, JournalError> {
+        let mut offsets = Vec::new();
+        for run in self.runs.clone() {
+            let table = run.table_offset(field.table());
+            let count = run.counts[field.table()];
+
+            // Binary search for the first entry >= low.
+            let (mut lo, mut hi) = (0u64, count);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if self.read_entry(table, mid)?.0 < *low {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            for i in lo..count {
+                let (key, offset) = self.read_entry(table, i)?;
+                if key > *high {
+                    break;
+                }
+                offsets.push(offset);
+            }
+        }
+        offsets.sort_unstable();
+        Ok(offsets)
+    }
+
+    fn read_entry(&mut self, table: u64, i: u64) -> Result<(Key, u64), JournalError> {
+        let mut bytes = [0u8; ENTRY_SIZE];
+        self.file
+            .seek(io::SeekFrom::Start(table + i * ENTRY_SIZE as u64))?;
+        self.file.read_exact(&mut bytes)?;
+        let mut key = [0u8; KEY_SIZE];
+        key.copy_from_slice(&bytes[..KEY_SIZE]);
+        Ok((key, read_u64(&bytes[KEY_SIZE..])))
+    }
+
+    /// Opens an existing sidecar, returning `None` if it is missing or malformed.
+    fn load(path: &Path) -> Result<Option<Self>, JournalError> {
+        let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let file_len = file.metadata()?.len();
+        let mut header_bytes = [0u8; HEADER_SIZE];
+        if file.read_exact(&mut header_bytes).is_err() {
+            return Ok(None);
+        }
+        let Some(header) = SidecarHeader::from_bytes(&header_bytes) else {
+            return Ok(None);
+        };
+
+        // Walk the runs; they must end exactly at end-of-file. Sidecars are
+        // compacted before exceeding MAX_RUNS.
+        if header.run_count > MAX_RUNS {
+            return Ok(None);
+        }
+        let mut runs = Vec::with_capacity(header.run_count as usize);
+        let mut offset = HEADER_SIZE as u64;
+        for _ in 0..header.run_count {
+            let mut counts_bytes = [0u8; RUN_HEADER_SIZE];
+            if file_len - offset < RUN_HEADER_SIZE as u64 {
+                return Ok(None);
+            }
+            file.seek(io::SeekFrom::Start(offset))?;
+            file.read_exact(&mut counts_bytes)?;
+            let mut counts = [0u64; FIELD_COUNT];
+            for (i, count) in counts.iter_mut().enumerate() {
+                *count = read_u64(&counts_bytes[i * 8..(i + 1) * 8]);
+            }
+            let run = match Run::new(offset, counts) {
+                Some(run) if run.end <= file_len => run,
+                _ => return Ok(None),
+            };
+            offset = run.end;
+            runs.push(run);
+        }
+        if offset != file_len {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            file,
+            header,
+            runs,
+            refresh: SidecarRefresh::Current,
+        }))
+    }
+
+    /// Rebuilds the sidecar from scratch and replaces the file atomically.
+    fn rebuild_from(path: &Path, mut journal_file: File) -> Result<Self, JournalError> {
+        let mut builder = RunBuilder::default();
+        let (journal_len, mut binding) = scan(&mut journal_file, Binding::new(), &mut builder)?;
+        let (chain_digest, tail_digest) = binding.finish(journal_len)?;
+        let header = SidecarHeader {
+            run_count: 1,
+            journal_len,
+            chain_digest,
+            tail_digest,
+            event_count: builder.events,
+        };
+
+        let mut sidecar = Self::write_single_run(path, &header, &mut builder)?;
+        sidecar.refresh = SidecarRefresh::Rebuilt;
+        Ok(sidecar)
+    }
+
+    /// Adds the events appended since the sidecar was last written.
+    ///
+    /// `binding` covers the journal prefix the sidecar covers.
+    fn update(
+        &mut self,
+        mut journal_file: File,
+        path: &Path,
+        binding: Binding,
+    ) -> Result<(), JournalError> {
+        let mut builder = RunBuilder::default();
+        let (journal_len, mut binding) = scan(&mut journal_file, binding, &mut builder)?;
+        if journal_len == self.header.journal_len {
+            // Only a torn tail was added; nothing to index yet.
+            return Ok(());
+        }
+        let (chain_digest, tail_digest) = binding.finish(journal_len)?;
+        let bound = SidecarHeader {
+            run_count: self.header.run_count + 1,
+            journal_len,
+            chain_digest,
+            tail_digest,
+            event_count: self.header.event_count + builder.events,
+        };
+
+        if self.header.run_count >= MAX_RUNS {
+            return self.compact(path, builder, bound);
+        }
+
+        let end = self.runs.last().map_or(HEADER_SIZE as u64, |run| run.end);
+        let run_bytes = builder.encode();
+        self.file.seek(io::SeekFrom::Start(end))?;
+        self.file.write_all(&run_bytes)?;
+        self.file.sync_data()?;
+
+        // The header is rewritten last: until then, the extra bytes make the
+        // sidecar fail validation and it is rebuilt.
+        self.header = bound;
+        self.file.seek(io::SeekFrom::Start(0))?;
+        self.file.write_all(&self.header.to_bytes())?;
+        self.file.sync_data()?;
+
+        let mut counts = [0u64; FIELD_COUNT];
+        for (count, table) in counts.iter_mut().zip(&builder.tables) {
+            *count = table.len() as u64;
+        }
+        self.runs
+            .push(Run::new(end, counts).expect("run was just written"));
+        self.refresh = SidecarRefresh::Updated {
+            events: builder.events,
+        };
+        Ok(())
+    }
+
+    /// Merges every run plus `builder` into a single run bound as in `bound`.
+    fn compact(
+        &mut self,
+        path: &Path,
+        mut builder: RunBuilder,
+        bound: SidecarHeader,
+    ) -> Result<(), JournalError> {
+        let added = builder.events;
+        for run in self.runs.clone() {
+            for field in SidecarField::ALL {
+                let table = run.table_offset(field.table());
+                for i in 0..run.counts[field.table()] {
+                    let entry = self.read_entry(table, i)?;
+                    builder.tables[field.table()].push(entry);
+                }
+            }
+        }
+        builder.events += self.header.event_count;
+
+        let header = SidecarHeader {
+            run_count: 1,
+            ..bound
+        };
+        *self = Self::write_single_run(path, &header, &mut builder)?;
+        self.refresh = SidecarRefresh::Updated { events: added };
+        Ok(())
+    }
+
+    /// Writes a sidecar with one run via a temporary file and rename.
+    fn write_single_run(
+        path: &Path,
+        header: &SidecarHeader,
+        builder: &mut RunBuilder,
+    ) -> Result<Self, JournalError> {
+        let tmp_path = path.with_extension(format!("{}.tmp", SIDECAR_EXTENSION));
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(&header.to_bytes())?;
+        tmp.write_all(&builder.encode())?;
+        tmp.sync_all()?;
+        drop(tmp);
+        fs::rename(&tmp_path, path)?;
+
+        Self::load(path)?.ok_or_else(|| {
+            JournalError::InvalidHeader("sidecar could not be read back".to_string())
+        })
+    }
+}
+
+/// Adds every event from the end of `binding` to the last complete frame to
+/// `builder`, extending `binding` over the bytes read on the way.
+///
+/// A binding of an empty prefix means the first frame. Returns the offset
+/// just past the last complete frame and the extended binding.
+fn scan(
+    journal_file: &mut File,
+    binding: Binding,
+    builder: &mut RunBuilder,
+) -> Result<(u64, Binding), JournalError> {
+    let start = binding.end();
+    journal_file.seek(io::SeekFrom::Start(0))?;
+    let source = HashingReader {
+        file: journal_file,
+        position: 0,
+        binding,
+    };
+    let mut reader = JournalReader::new(BufReader::new(source), ReadMode::Permissive)?;
+    if start > 0 {
+        reader.seek_offset(start)?;
+    }
+    loop {
+        let offset = reader.position();
+        // Frames before `offset` are complete, so their blocks can be chained
+        reader.get_mut().get_mut().binding.commit(offset);
+        match reader.read_frame()? {
+            None => return Ok((offset, reader.into_inner().into_inner().binding)),
+            Some((FrameKind::EventJson, payload)) => {
+                if let Ok(event) = serde_json::from_slice::<EventJson>(&payload) {
+                    builder.add_event(offset, &event);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Hash of a journal prefix in blocks of [`BLOCK_SIZE`], which binds a
+/// sidecar to it.
+///
+/// Blocks are chained into `chain` once they are known to lie before the end
+/// of the prefix; the bytes after them are kept until then.
+struct Binding {
+    /// Chain value over the first `blocks` blocks.
+    chain: [u8; 32],
+    blocks: u64,
+    /// Journal bytes from the end of the chained blocks on.
+    pending: Vec<u8>,
+}
+
+impl Binding {
+    fn new() -> Self {
+        Self::resume([0u8; 32], 0)
+    }
+
+    /// Continues a binding of a prefix of length `len` from its chain value;
+    /// the bytes of its last block still have to be added.
+    fn resume(chain: [u8; 32], len: u64) -> Self {
+        Self {
+            chain,
+            blocks: tail_start(len) / BLOCK_SIZE,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the length of the prefix added so far.
+    fn end(&self) -> u64 {
+        self.blocks * BLOCK_SIZE + self.pending.len() as u64
+    }
+
+    /// Adds the part of `bytes`, read at journal offset `at`, that continues
+    /// the prefix.
+    fn add(&mut self, at: u64, bytes: &[u8]) {
+        let end = self.end();
+        if (at..at + bytes.len() as u64).contains(&end) {
+            self.pending
+                .extend_from_slice(&bytes[(end - at) as usize..]);
+        }
+    }
+
+    /// Chains every block that ends before the last block of a prefix of
+    /// length `len`.
+    fn commit(&mut self, len: u64) {
+        let block = BLOCK_SIZE as usize;
+        while (self.blocks + 1) * BLOCK_SIZE <= tail_start(len) && self.pending.len() >= block {
+            let mut hasher = Sha256::new();
+            hasher.update(CHAIN_DOMAIN);
+            hasher.update(self.chain);
+            hasher.update(&self.pending[..block]);
+            self.chain = hasher.finalize().into();
+            self.pending.drain(..block);
+            self.blocks += 1;
+        }
+    }
+
+    /// Reads the journal from the end of the prefix so far up to `len`.
+    fn extend(&mut self, file: &mut File, len: u64) -> Result<(), JournalError> {
+        let mut at = self.end();
+        file.seek(io::SeekFrom::Start(at))?;
+        let mut buf = vec![0u8; BLOCK_SIZE as usize];
+        while at < len {
+            let want = buf.len().min((len - at) as usize);
+            file.read_exact(&mut buf[..want])?;
+            self.add(at, &buf[..want]);
+            at += want as u64;
+            self.commit(at);
+        }
+        Ok(())
+    }
+
+    /// Returns the chain and tail digests of the prefix of length `len`.
+    fn finish(&mut self, len: u64) -> Result<([u8; 32], [u8; 32]), JournalError> {
+        self.commit(len);
+        let tail = (len - self.blocks * BLOCK_SIZE) as usize;
+        if self.blocks * BLOCK_SIZE != tail_start(len) || self.pending.len() < tail {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(TAIL_DOMAIN);
+        hasher.update(len.to_le_bytes());
+        hasher.update(self.chain);
+        hasher.update(&self.pending[..tail]);
+        Ok((self.chain, hasher.finalize().into()))
+    }
+}
+
+/// Returns where the last block of a prefix of length `len` starts; it holds
+/// between 1 and [`BLOCK_SIZE`] bytes of any non-empty prefix.
+fn tail_start(len: u64) -> u64 {
+    len.saturating_sub(1) / BLOCK_SIZE * BLOCK_SIZE
+}
+
+/// Journal file reader that adds the bytes it reads to a [`Binding`].
+struct HashingReader<'a> {
+    file: &'a mut File,
+    position: u64,
+    binding: Binding,
+}
+
+impl Read for HashingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.file.read(buf)?;
+        self.binding.add(self.position, &buf[..read]);
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for HashingReader<'_> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.position = self.file.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+fn event_field_key(field: SidecarField, event: &EventJson) -> Option<Key> {
+    match field {
+        SidecarField::EventId => {
+            let key = index::event_key(event);
+            (key != [0u8; KEY_SIZE]).then_some(key)
+        }
+        SidecarField::EventType => event
+            .get("event_type")?
+            .as_str()
+            .map(|s| string_key(field, s)),
+        SidecarField::PrincipalId => event
+            .get("principal_id")?
+            .as_str()
+            .map(|s| string_key(field, s)),
+        SidecarField::OccurredAt => timestamp_key(event.get("occurred_at")?.as_str()?),
+    }
+}
+
+/// Hashes a string field value; these fields only support exact matches.
+fn string_key(field: SidecarField, value: &str) -> Key {
+    let mut hasher = Sha256::new();
+    hasher.update(b"northroot:nrx-key:v1\0");
+    hasher.update([field.table() as u8]);
+    hasher.update(value.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Encodes an RFC 3339 UTC timestamp so that byte order is time order.
+///
+/// `YYYY-MM-DDTHH:MM:SS[.fffffffff]Z` becomes the date-time followed by a
+/// nine-digit fraction, zero-padded to 32 bytes. Returns `None` for anything
+/// else. This is the key the sidecar orders `occurred_at` by, so callers can
+/// apply the same comparison when filtering events themselves.
+pub fn timestamp_key(ts: &str) -> Option<[u8; 32]> {
+    let bytes = ts.as_bytes();
+    let (datetime, rest) = (bytes.get(..19)?, bytes.get(19..)?);
+    let shape_ok = datetime.iter().enumerate().all(|(i, b)| match i {
+        4 | 7 => *b == b'-',
+        10 => *b == b'T',
+        13 | 16 => *b == b':',
+        _ => b.is_ascii_digit(),
+    });
+    let fraction = match rest {
+        [b'Z'] => &[][..],
+        [b'.', digits @ .., b'Z'] if (1..=9).contains(&digits.len()) => digits,
+        _ => return None,
+    };
+    if !shape_ok || !fraction.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let mut key = [0u8; KEY_SIZE];
+    key[..19].copy_from_slice(datetime);
+    key[19] = b'.';
+    key[20..29].fill(b'0');
+    key[20..20 + fraction.len()].copy_from_slice(fraction);
+    Some(key)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_key_orders_fractions() {
+        let a = timestamp_key("2024-01-01T00:00:00Z").unwrap();
+        let b = timestamp_key("2024-01-01T00:00:00.5Z").unwrap();
+        let c = timestamp_key("2024-01-01T00:00:01Z").unwrap();
+        assert!(a < b && b < c);
+        assert_eq!(a, timestamp_key("2024-01-01T00:00:00.000Z").unwrap());
+        assert!(timestamp_key("2024-01-01 00:00:00Z").is_none());
+        assert!(timestamp_key("2024-01-01T00:00:00+01:00").is_none());
+        assert!(timestamp_key("2024-01-01T00:00:00.Z").is_none());
+    }
+
+    #[test]
+    fn header_round_trip() {
+        let header = SidecarHeader {
+            run_count: 3,
+            journal_len: 1234,
+            chain_digest: [9u8; 32],
+            tail_digest: [8u8; 32],
+            event_count: 7,
+        };
+        assert_eq!(SidecarHeader::from_bytes(&header.to_bytes()), Some(header));
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[..4].copy_from_slice(b"NRJ1");
+        assert_eq!(SidecarHeader::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn string_keys_are_field_specific() {
+        assert_ne!(
+            string_key(SidecarField::EventType, "x"),
+            string_key(SidecarField::PrincipalId, "x")
+        );
+    }
+}
@@ -0,0 +1,497 @@
+//! Storage backends for journals.
+//!
+//! [`JournalStorage`] is the append-only byte store a journal lives in. Files
+//! implement it directly; [`MemoryStorage`] keeps a journal in memory, and
+//! [`FaultyStorage`] wraps any storage to inject the failures real disks
+//! produce: short writes, a full disk, failing syncs and torn writes.
+//!
+//! Use [`JournalWriter::with_storage`](crate::JournalWriter::with_storage) and
+//! [`JournalReader::from_storage`](crate::JournalReader::from_storage) to put
+//! a journal on any storage. [`StorageStream`] adapts a storage to the
+//! standard I/O traits.
+
+use crate::durability::SyncKind;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// An append-only byte store that holds a journal.
+///
+/// Bytes are only ever added at the end; [`truncate`](Self::truncate) is the
+/// one way to remove them, used to roll back a failed append.
+pub trait JournalStorage {
+    /// Appends bytes from `buf` at the end and returns how many were written.
+    ///
+    /// Like [`Write::write`], this may write fewer bytes than `buf` holds.
+    fn append(&mut self, buf: &[u8]) -> io::Result<usize>;
+
+    /// Reads bytes starting at `offset` into `buf` and returns how many were
+    /// read, `0` at or past the end.
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Returns the number of bytes stored.
+    fn len(&mut self) -> io::Result<u64>;
+
+    /// Returns `true` if nothing is stored.
+    fn is_empty(&mut self) -> io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Makes the bytes appended so far durable.
+    fn sync(&mut self, kind: SyncKind) -> io::Result<()>;
+
+    /// Discards everything past the first `len` bytes.
+    fn truncate(&mut self, len: u64) -> io::Result<()>;
+}
+
+impl JournalStorage for File {
+    fn append(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.seek(SeekFrom::End(0))?;
+        self.write(buf)
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.seek(SeekFrom::Start(offset))?;
+        self.read(buf)
+    }
+
+    fn len(&mut self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+
+    fn sync(&mut self, kind: SyncKind) -> io::Result<()> {
+        match kind {
+            SyncKind::Data => self.sync_data(),
+            SyncKind::Full => self.sync_all(),
+        }
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.set_len(len)
+    }
+}
+
+impl<S: JournalStorage + ?Sized> JournalStorage for &mut S {
+    fn append(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).append(buf)
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn len(&mut self) -> io::Result<u64> {
+        (**self).len()
+    }
+
+    fn sync(&mut self, kind: SyncKind) -> io::Result<()> {
+        (**self).sync(kind)
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        (**self).truncate(len)
+    }
+}
+
+/// A journal kept in memory.
+///
+/// Syncs are recorded, so tests can tell which bytes a crash would keep: see
+/// [`synced_len`](Self::synced_len) and [`crash`](Self::crash).
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    bytes: Vec<u8>,
+    synced_len: u64,
+    syncs: u64,
+}
+
+impl MemoryStorage {
+    /// Creates an empty storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a storage holding `bytes`, all of them considered synced.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self {
+            synced_len: bytes.len() as u64,
+            bytes,
+            syncs: 0,
+        }
+    }
+
+    /// Returns the stored bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the stored bytes, consuming the storage.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns how many bytes were stored at the last sync.
+    pub fn synced_len(&self) -> u64 {
+        self.synced_len
+    }
+
+    /// Returns how many syncs were issued.
+    pub fn syncs(&self) -> u64 {
+        self.syncs
+    }
+
+    /// Drops the bytes appended since the last sync, as a crash would.
+    pub fn crash(&mut self) {
+        self.bytes.truncate(self.synced_len as usize);
+    }
+}
+
+impl JournalStorage for MemoryStorage {
+    fn append(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let start = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(self.bytes.len());
+        let n = buf.len().min(self.bytes.len() - start);
+        buf[..n].copy_from_slice(&self.bytes[start..start + n]);
+        Ok(n)
+    }
+
+    fn len(&mut self) -> io::Result<u64> {
+        Ok(self.bytes.len() as u64)
+    }
+
+    fn sync(&mut self, _kind: SyncKind) -> io::Result<()> {
+        self.synced_len = self.bytes.len() as u64;
+        self.syncs += 1;
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.bytes.truncate(len as usize);
+        self.synced_len = self.synced_len.min(len);
+        Ok(())
+    }
+}
+
+/// Failures for a [`FaultyStorage`] to inject.
+///
+/// The default injects nothing.
+#[derive(Debug, Clone, Default)]
+pub struct Faults {
+    /// Writes at most this many bytes per append (short writes).
+    pub max_write: Option<usize>,
+    /// Fails appends with [`io::ErrorKind::StorageFull`] (`ENOSPC`) once the
+    /// storage holds this many bytes. An append that does not fit is cut
+    /// short at the limit.
+    pub capacity: Option<u64>,
+    /// Fails every sync with an I/O error (`EIO`).
+    pub fail_sync: bool,
+    /// Simulates a crash once the storage reaches this many bytes: the
+    /// append that crosses it is torn at exactly this byte, and every
+    /// operation after that fails.
+    pub crash_at: Option<u64>,
+}
+
+/// Storage that injects [`Faults`] into another storage.
+///
+/// # Example
+///
+/// ```rust
+/// use northroot_journal::{Faults, FaultyStorage, JournalWriter, MemoryStorage, WriteOptions};
+/// use serde_json::json;
+///
+/// let faults = Faults {
+///     capacity: Some(64),
+///     ..Default::default()
+/// };
+/// let storage = FaultyStorage::new(MemoryStorage::new(), faults);
+/// let mut writer = JournalWriter::with_storage(storage, WriteOptions::default())?;
+/// let event = json!({"event_type": "test", "padding": "x".repeat(64)});
+/// assert!(writer.append_event(&event).is_err());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct FaultyStorage<S> {
+    inner: S,
+    faults: Faults,
+    crashed: bool,
+}
+
+impl<S> FaultyStorage<S> {
+    /// Wraps `inner`, injecting `faults`.
+    pub fn new(inner: S, faults: Faults) -> Self {
+        Self {
+            inner,
+            faults,
+            crashed: false,
+        }
+    }
+
+    /// Returns the faults being injected.
+    pub fn faults(&self) -> &Faults {
+        &self.faults
+    }
+
+    /// Returns the faults being injected, e.g. to free up space mid-test.
+    pub fn faults_mut(&mut self) -> &mut Faults {
+        &mut self.faults
+    }
+
+    /// Returns `true` once a crash was simulated.
+    pub fn has_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    /// Returns the wrapped storage.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Returns the wrapped storage, consuming the wrapper.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn check_crashed(&self) -> io::Result<()> {
+        if self.crashed {
+            Err(crashed())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<S: JournalStorage> JournalStorage for FaultyStorage<S> {
+    fn append(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check_crashed()?;
+        let mut buf = buf;
+        if let Some(max) = self.faults.max_write {
+            buf = &buf[..buf.len().min(max)];
+        }
+        let len = self.inner.len()?;
+        let end = len + buf.len() as u64;
+
+        if let Some(at) = self.faults.crash_at.filter(|&at| end > at) {
+            let torn = at.saturating_sub(len) as usize;
+            write_all(&mut self.inner, &buf[..torn])?;
+            self.crashed = true;
+            return Err(crashed());
+        }
+        if let Some(capacity) = self.faults.capacity.filter(|&capacity| end > capacity) {
+            let room = capacity.saturating_sub(len) as usize;
+            if room == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::StorageFull,
+                    "no space left on device (injected)",
+                ));
+            }
+            buf = &buf[..room];
+        }
+        self.inner.append(buf)
+    }
+
+    fn read_at(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.check_crashed()?;
+        self.inner.read_at(offset, buf)
+    }
+
+    fn len(&mut self) -> io::Result<u64> {
+        self.check_crashed()?;
+        self.inner.len()
+    }
+
+    fn sync(&mut self, kind: SyncKind) -> io::Result<()> {
+        self.check_crashed()?;
+        if self.faults.fail_sync {
+            return Err(io::Error::other("input/output error (injected)"));
+        }
+        self.inner.sync(kind)
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.check_crashed()?;
+        self.inner.truncate(len)
+    }
+}
+
+fn crashed() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "storage crashed (injected)")
+}
+
+fn write_all<S: JournalStorage>(storage: &mut S, mut buf: &[u8]) -> io::Result<()> {
+    while !buf.is_empty() {
+        match storage.append(buf)? {
+            0 => return Err(io::ErrorKind::WriteZero.into()),
+            n => buf = &buf[n..],
+        }
+    }
+    Ok(())
+}
+
+/// Reads, appends to and seeks in a [`JournalStorage`] through the standard
+/// I/O traits.
+///
+/// Writes are only accepted at the end of the storage; writing anywhere else
+/// fails with [`io::ErrorKind::InvalidInput`].
+#[derive(Debug)]
+pub struct StorageStream<S> {
+    storage: S,
+    position: u64,
+}
+
+impl<S> StorageStream<S> {
+    /// Creates a stream positioned at the start of `storage`.
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            position: 0,
+        }
+    }
+
+    /// Returns the storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the storage mutably.
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Returns the storage, consuming the stream.
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+}
+
+impl<S: JournalStorage> StorageStream<S> {
+    /// Syncs the storage.
+    pub(crate) fn sync(&mut self, kind: SyncKind) -> io::Result<()> {
+        self.storage.sync(kind)
+    }
+
+    /// Truncates the storage to `len` bytes and moves the stream there.
+    pub(crate) fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.storage.truncate(len)?;
+        self.position = len;
+        Ok(())
+    }
+}
+
+impl<S: JournalStorage> Read for StorageStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.storage.read_at(self.position, buf)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl<S: JournalStorage> Write for StorageStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.position != self.storage.len()? {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "journal storage is append-only",
+            ));
+        }
+        let n = self.storage.append(buf)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<S: JournalStorage> Seek for StorageStream<S> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(offset) => {
+                self.position = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(delta) => (self.storage.len()?, delta),
+            SeekFrom::Current(delta) => (self.position, delta),
+        };
+        self.position = base.checked_add_signed(delta).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faulty_storage_writes_short() {
+        let faults = Faults {
+            max_write: Some(3),
+            ..Default::default()
+        };
+        let mut storage = FaultyStorage::new(MemoryStorage::new(), faults);
+        assert_eq!(storage.append(b"abcdef").unwrap(), 3);
+        write_all(&mut storage, b"defgh").unwrap();
+        assert_eq!(storage.inner().as_bytes(), b"abcdefgh");
+    }
+
+    #[test]
+    fn faulty_storage_fills_up() {
+        let faults = Faults {
+            capacity: Some(4),
+            ..Default::default()
+        };
+        let mut storage = FaultyStorage::new(MemoryStorage::new(), faults);
+        assert_eq!(storage.append(b"abcdef").unwrap(), 4);
+        let err = storage.append(b"ef").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::StorageFull);
+
+        storage.faults_mut().capacity = None;
+        assert_eq!(storage.append(b"ef").unwrap(), 2);
+    }
+
+    #[test]
+    fn faulty_storage_tears_write_and_stops() {
+        let faults = Faults {
+            crash_at: Some(5),
+            ..Default::default()
+        };
+        let mut storage = FaultyStorage::new(MemoryStorage::new(), faults);
+        storage.append(b"abc").unwrap();
+        assert!(storage.append(b"defg").is_err());
+        assert!(storage.has_crashed());
+        assert!(storage.len().is_err());
+        assert_eq!(storage.into_inner().into_bytes(), b"abcde");
+    }
+
+    #[test]
+    fn memory_storage_crash_keeps_synced_bytes() {
+        let mut storage = MemoryStorage::new();
+        storage.append(b"abc").unwrap();
+        storage.sync(SyncKind::Data).unwrap();
+        storage.append(b"def").unwrap();
+        storage.crash();
+        assert_eq!(storage.as_bytes(), b"abc");
+        assert_eq!(storage.syncs(), 1);
+    }
+
+    #[test]
+    fn stream_is_append_only() {
+        let mut stream = StorageStream::new(MemoryStorage::new());
+        stream.write_all(b"abcdef").unwrap();
+        stream.seek(SeekFrom::Start(2)).unwrap();
+        let mut buf = [0u8; 2];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"cd");
+        assert!(stream.write(b"x").is_err());
+        assert!(stream.seek(SeekFrom::Current(-10)).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! Checks a journal writer applies to events before writing them.
+
+use crate::event::{EventJson, REQUIRED_FIELDS};
+use northroot_canonical::{compute_event_id, Canonicalizer, Digest, ProfileId, Timestamp};
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// Which checks a [`JournalWriter`](crate::JournalWriter) applies to events
+/// before writing them.
+///
+/// The default checks nothing, so any JSON value can be appended. A rejected
+/// event fails with [`JournalError::Rejected`](crate::JournalError::Rejected)
+/// and no bytes are written.
+///
+/// # Example
+///
+/// ```rust
+/// use northroot_journal::{JournalError, JournalWriter, ValidationPolicy, WriteOptions};
+/// use serde_json::json;
+///
+/// let options = WriteOptions::default().with_validation(ValidationPolicy::strict());
+/// let mut writer = JournalWriter::new(Vec::new(), options)?;
+/// let result = writer.append_event(&json!({"event_type": "test"}));
+/// assert!(matches!(result, Err(JournalError::Rejected(_))));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ValidationPolicy {
+    /// Require a JSON object with every required event field, as
+    /// [`is_valid_event_structure`](crate::event::is_valid_event_structure) does.
+    pub structure: bool,
+    /// Recompute `event_id` with the profile named by `canonical_profile_id`
+    /// and require it to match.
+    pub event_id: bool,
+    /// Require `occurred_at` to be a UTC RFC 3339 timestamp no earlier than
+    /// the previous event's. Resuming a journal reads its last event.
+    pub monotonic_time: bool,
+}
+
+impl ValidationPolicy {
+    /// Applies every check.
+    pub fn strict() -> Self {
+        Self {
+            structure: true,
+            event_id: true,
+            monotonic_time: true,
+        }
+    }
+
+    /// Returns `true` if any check is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.structure || self.event_id || self.monotonic_time
+    }
+
+    /// Checks `event` against this policy.
+    ///
+    /// `previous` is the `occurred_at` of the event appended before it, if
+    /// any; it is only used by [`monotonic_time`](Self::monotonic_time).
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Rejection`] that applies.
+    pub fn check(&self, event: &EventJson, previous: Option<&str>) -> Result<(), Rejection> {
+        if self.structure {
+            let obj = event.as_object().ok_or(Rejection::NotAnObject)?;
+            if let Some(field) = REQUIRED_FIELDS.iter().find(|f| !obj.contains_key(**f)) {
+                return Err(Rejection::MissingField(field));
+            }
+        }
+        if self.event_id {
+            check_event_id(event)?;
+        }
+        if self.monotonic_time {
+            let occurred_at = occurred_at(event)?;
+            if let Some(previous) = previous {
+                if compare_timestamps(occurred_at, previous) == Ordering::Less {
+                    return Err(Rejection::OutOfOrder {
+                        occurred_at: occurred_at.to_string(),
+                        previous: previous.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a [`ValidationPolicy`] or [`DuplicatePolicy`](crate::DuplicatePolicy)
+/// rejected an event.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    /// The event is not a JSON object.
+    #[error("event is not a JSON object")]
+    NotAnObject,
+    /// A required field is missing.
+    #[error("event is missing required field `{0}`")]
+    MissingField(&'static str),
+    /// `event_id` is not a digest object.
+    #[error("event_id is not a valid digest")]
+    InvalidEventId,
+    /// `canonical_profile_id` does not name a valid profile.
+    #[error("invalid canonical_profile_id `{0}`")]
+    InvalidProfile(String),
+    /// The event cannot be canonicalized under its profile.
+    #[error("event_id cannot be computed: {0}")]
+    Canonicalization(String),
+    /// `event_id` does not match the canonical event.
+    #[error("event_id mismatch: computed {}, event has {}", computed.b64, claimed.b64)]
+    EventIdMismatch {
+        /// The `event_id` carried by the event.
+        claimed: Digest,
+        /// The `event_id` computed from the event.
+        computed: Digest,
+    },
+    /// `occurred_at` is not a UTC RFC 3339 timestamp.
+    #[error("invalid occurred_at `{0}`")]
+    InvalidTimestamp(String),
+    /// `occurred_at` is earlier than the previous event's.
+    #[error("occurred_at {occurred_at} is earlier than the previous event's {previous}")]
+    OutOfOrder {
+        /// The event's `occurred_at`.
+        occurred_at: String,
+        /// The previous event's `occurred_at`.
+        previous: String,
+    },
+    /// An event with the same `event_id` is already in the journal.
+    #[error("event_id {} is already in the journal at ordinal {ordinal}", event_id.b64)]
+    DuplicateEventId {
+        /// The repeated `event_id`.
+        event_id: Digest,
+        /// Ordinal of the earlier event.
+        ordinal: u64,
+    },
+}
+
+fn check_event_id(event: &EventJson) -> Result<(), Rejection> {
+    let claimed = event
+        .get("event_id")
+        .ok_or(Rejection::MissingField("event_id"))?;
+    let claimed: Digest =
+        serde_json::from_value(claimed.clone()).map_err(|_| Rejection::InvalidEventId)?;
+
+    let profile = event
+        .get("canonical_profile_id")
+        .ok_or(Rejection::MissingField("canonical_profile_id"))?;
+    let profile = profile
+        .as_str()
+        .and_then(|s| ProfileId::parse(s).ok())
+        .ok_or_else(|| Rejection::InvalidProfile(profile.to_string()))?;
+
+    let computed = compute_event_id(event, &Canonicalizer::new(profile))
+        .map_err(|e| Rejection::Canonicalization(e.to_string()))?;
+    if claimed != computed {
+        return Err(Rejection::EventIdMismatch { claimed, computed });
+    }
+    Ok(())
+}
+
+/// Returns the event's `occurred_at` if it is a valid timestamp.
+pub(crate) fn occurred_at(event: &EventJson) -> Result<&str, Rejection> {
+    let value = event
+        .get("occurred_at")
+        .ok_or(Rejection::MissingField("occurred_at"))?;
+    match value.as_str() {
+        Some(s) if Timestamp::parse(s).is_ok() => Ok(s),
+        _ => Err(Rejection::InvalidTimestamp(value.to_string())),
+    }
+}
+
+/// Orders two valid timestamps, which may differ in fractional digits.
+fn compare_timestamps(a: &str, b: &str) -> Ordering {
+    // "YYYY-MM-DDTHH:MM:SS" compares as text; the fraction as nanoseconds
+    let split = |ts: &str| {
+        let (seconds, rest) = ts.split_at(19);
+        let digits = rest.trim_start_matches('.').trim_end_matches('Z');
+        let nanos = format!("{:0<9}", digits).parse::<u32>().unwrap_or(0);
+        (seconds.to_string(), nanos)
+    };
+    split(a).cmp(&split(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event() -> EventJson {
+        let mut event = json!({
+            "event_type": "test",
+            "event_version": "1",
+            "occurred_at": "2024-01-01T00:00:00Z",
+            "principal_id": "service:test",
+            "canonical_profile_id": "northroot-canonical-v1"
+        });
+        let profile = ProfileId::parse("northroot-canonical-v1").unwrap();
+        let event_id = compute_event_id(&event, &Canonicalizer::new(profile)).unwrap();
+        event["event_id"] = serde_json::to_value(event_id).unwrap();
+        event
+    }
+
+    #[test]
+    fn strict_accepts_valid_event() {
+        assert_eq!(ValidationPolicy::strict().check(&event(), None), Ok(()));
+        assert!(!ValidationPolicy::default().is_enabled());
+    }
+
+    #[test]
+    fn structure_rejects_missing_fields() {
+        let policy = ValidationPolicy {
+            structure: true,
+            ..Default::default()
+        };
+        assert_eq!(policy.check(&json!([1]), None), Err(Rejection::NotAnObject));
+        let mut event = event();
+        event.as_object_mut().unwrap().remove("principal_id");
+        assert_eq!(
+            policy.check(&event, None),
+            Err(Rejection::MissingField("principal_id"))
+        );
+    }
+
+    #[test]
+    fn event_id_rejects_tampering() {
+        let policy = ValidationPolicy {
+            event_id: true,
+            ..Default::default()
+        };
+        let mut event = event();
+        event["event_type"] = json!("tampered");
+        assert!(matches!(
+            policy.check(&event, None),
+            Err(Rejection::EventIdMismatch { .. })
+        ));
+        event["event_id"] = json!("not a digest");
+        assert_eq!(policy.check(&event, None), Err(Rejection::InvalidEventId));
+        event["event_id"] = json!({"alg": "sha-256", "b64": "x"});
+        event["canonical_profile_id"] = json!("short");
+        assert_eq!(
+            policy.check(&event, None),
+            Err(Rejection::InvalidProfile("\"short\"".to_string()))
+        );
+    }
+
+    #[test]
+    fn monotonic_time_compares_fractions() {
+        let policy = ValidationPolicy {
+            monotonic_time: true,
+            ..Default::default()
+        };
+        let mut event = event();
+        event["occurred_at"] = json!("2024-01-01T00:00:00.5Z");
+        assert_eq!(policy.check(&event, Some("2024-01-01T00:00:00Z")), Ok(()));
+        assert_eq!(
+            policy.check(&event, Some("2024-01-01T00:00:00.500000000Z")),
+            Ok(())
+        );
+        assert!(matches!(
+            policy.check(&event, Some("2024-01-01T00:00:00.51Z")),
+            Err(Rejection::OutOfOrder { .. })
+        ));
+        event["occurred_at"] = json!("yesterday");
+        assert!(matches!(
+            policy.check(&event, None),
+            Err(Rejection::InvalidTimestamp(_))
+        ));
+    }
+}
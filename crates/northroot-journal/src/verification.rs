@@ -1,8 +1,22 @@
-//! Verification helpers for journal events.
+//! Verification of events and whole journals.
 
+use crate::chain::{self, ChainState};
 use crate::errors::JournalError;
-use crate::event::EventJson;
-use northroot_canonical::{compute_event_id, Canonicalizer};
+use crate::event::{self, EventJson};
+use crate::frame::{FrameKind, FRAME_KIND_EVENT_JSON};
+use crate::index::{self, IndexKey};
+use crate::lock::LockPolicy;
+use crate::reader::{JournalReader, ReadMode};
+use northroot_canonical::{
+    compute_event_id, compute_event_id_with_report, Canonicalizer, Digest, HygieneReport, ProfileId,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as Sha2Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::thread::ScopedJoinHandle;
 
 /// Verifies an event JSON against its claimed event_id.
 ///
@@ -24,3 +38,731 @@ pub fn verify_event_id(
 
     Ok(claimed_id == computed_id)
 }
+
+/// Options for [`verify_journal`].
+#[derive(Debug, Clone)]
+pub struct VerifyOptions {
+    /// Profile used to recompute event IDs (default: `northroot-canonical-v1`).
+    pub profile: ProfileId,
+    /// Stop after this many events (default: no limit).
+    pub max_events: Option<u64>,
+    /// Worker threads that canonicalize and hash events (default: 1).
+    /// Frames are still read in order on the calling thread; `0` uses the
+    /// available parallelism. The report does not depend on this setting.
+    pub threads: usize,
+    /// Whether an event repeating an earlier event's `event_id` is
+    /// [`Invalid`](FrameOutcome::Invalid) and fails verification (default:
+    /// false). Repeats are listed in
+    /// [`duplicate_event_ids`](VerificationReport::duplicate_event_ids) either
+    /// way.
+    pub reject_duplicates: bool,
+    /// Whether repeated `event_id`s are looked for at all (default: true).
+    /// This is the only part of verification whose memory grows with the
+    /// journal, by about 40 bytes per distinct `event_id`; turn it off for
+    /// archives too large for that. Ignored with
+    /// [`reject_duplicates`](Self::reject_duplicates).
+    pub detect_duplicates: bool,
+    /// What [`verify_incremental`] does if a writer holds the journal
+    /// (default: [`LockPolicy::Fail`]). It holds a shared lock while it
+    /// reads, so the bytes it hashes are the bytes it verifies.
+    pub lock: LockPolicy,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            profile: ProfileId::parse("northroot-canonical-v1").expect("valid profile ID"),
+            max_events: None,
+            threads: 1,
+            reject_duplicates: false,
+            detect_duplicates: true,
+            lock: LockPolicy::Fail,
+        }
+    }
+}
+
+/// What verification found for one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameOutcome {
+    /// An event whose `event_id` matches its canonical bytes, or an intact
+    /// trailer or index segment.
+    Valid,
+    /// An event whose `event_id` does not match its canonical bytes.
+    Mismatch,
+    /// An event that is not valid JSON, lacks a usable `event_id`, cannot be
+    /// canonicalized, or is not stored canonically where the journal requires it.
+    /// With [`VerifyOptions::reject_duplicates`], also an event repeating an
+    /// earlier `event_id`.
+    Invalid,
+    /// A frame of a kind this implementation does not know; it is skipped.
+    UnknownKind,
+    /// A frame that failed structural checks (header, checksum or hash
+    /// chain). Verification stops there.
+    Damaged,
+}
+
+/// Verification result for one frame.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrameReport {
+    /// Zero-based position among all frames of the journal.
+    pub ordinal: u64,
+    /// Offset of the frame's record header.
+    pub offset: u64,
+    /// Frame kind byte, or `None` for a damaged frame.
+    pub kind: Option<u8>,
+    /// What verification found.
+    pub outcome: FrameOutcome,
+    /// The `event_id` the event claims, if it carries a valid digest.
+    pub event_id: Option<Digest>,
+    /// Why the frame is not valid.
+    pub error: Option<String>,
+    /// Hygiene report of canonicalizing the event, if it could be canonicalized.
+    pub hygiene: Option<HygieneReport>,
+}
+
+/// Number of frames by outcome.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerificationTotals {
+    /// Frames read, of any kind and outcome.
+    pub frames: u64,
+    /// `EventJson` frames read.
+    pub events: u64,
+    /// Frames with [`FrameOutcome::Valid`].
+    pub valid: u64,
+    /// Frames with [`FrameOutcome::Mismatch`].
+    pub mismatch: u64,
+    /// Frames with [`FrameOutcome::Invalid`].
+    pub invalid: u64,
+    /// Frames with [`FrameOutcome::UnknownKind`].
+    pub unknown_kind: u64,
+    /// Frames with [`FrameOutcome::Damaged`].
+    pub damaged: u64,
+}
+
+/// An `event_id` carried by more than one event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateEventId {
+    /// The repeated `event_id`.
+    pub event_id: Digest,
+    /// Offsets of every frame carrying it, in file order.
+    pub offsets: Vec<u64>,
+}
+
+/// Result of verifying a whole journal, returned by [`verify_journal`].
+///
+/// Only frames that failed verification are kept in full; the rest are
+/// counted in [`totals`](Self::totals), so the report stays small however
+/// long the journal is. [`verify_journal_with`] hands every frame's result to
+/// a callback as it is verified.
+///
+/// Serializes to JSON with fields in declaration order, so the output is
+/// stable for tools that parse it.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::{verify_journal, VerifyOptions};
+///
+/// let report = verify_journal("events.nrj", &VerifyOptions::default())?;
+/// println!("{}", serde_json::to_string_pretty(&report)?);
+/// if !report.passed() {
+///     std::process::exit(1);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationReport {
+    /// Journal format version from the file header.
+    pub version: u16,
+    /// Feature flags from the file header.
+    pub flags: u16,
+    /// Whether every frame up to end-of-file was verified, i.e. verification
+    /// did not stop at damage, truncation or [`VerifyOptions::max_events`].
+    pub complete: bool,
+    /// Offset of a partial frame at the end of the journal, if any.
+    pub truncated_at: Option<u64>,
+    /// Final hash chain value, if the journal is hash-chained and complete.
+    pub chain_head: Option<Digest>,
+    /// Number of frames by outcome.
+    pub totals: VerificationTotals,
+    /// `event_id`s carried by more than one event, by first occurrence.
+    pub duplicate_event_ids: Vec<DuplicateEventId>,
+    /// Unknown frame kind bytes seen, in ascending order.
+    pub unknown_frame_kinds: Vec<u8>,
+    /// Results of the frames that are mismatched, invalid or damaged, in
+    /// file order.
+    pub failures: Vec<FrameReport>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if every frame is valid or of an unknown kind and the
+    /// journal is not truncated.
+    pub fn passed(&self) -> bool {
+        let totals = &self.totals;
+        totals.mismatch == 0
+            && totals.invalid == 0
+            && totals.damaged == 0
+            && self.truncated_at.is_none()
+    }
+
+    fn push(&mut self, frame: FrameReport) {
+        let totals = &mut self.totals;
+        totals.frames += 1;
+        match frame.outcome {
+            FrameOutcome::Valid => totals.valid += 1,
+            FrameOutcome::Mismatch => totals.mismatch += 1,
+            FrameOutcome::Invalid => totals.invalid += 1,
+            FrameOutcome::UnknownKind => totals.unknown_kind += 1,
+            FrameOutcome::Damaged => totals.damaged += 1,
+        }
+        if matches!(
+            frame.outcome,
+            FrameOutcome::Mismatch | FrameOutcome::Invalid | FrameOutcome::Damaged
+        ) {
+            self.failures.push(frame);
+        }
+    }
+}
+
+/// Verifies every frame of the journal at `path`.
+///
+/// Frames are read in strict mode, so checksums and the hash chain are
+/// verified; every event's `event_id` is recomputed from its canonical bytes.
+/// Damage and truncation are recorded in the report rather than returned as
+/// errors, so the report covers everything before them.
+///
+/// # Errors
+///
+/// Returns [`JournalError`] if the file cannot be opened or read, or its
+/// header is invalid.
+pub fn verify_journal<P: AsRef<Path>>(
+    path: P,
+    options: &VerifyOptions,
+) -> Result<VerificationReport, JournalError> {
+    verify_journal_with(path, options, |_| {})
+}
+
+/// Verifies the journal at `path` like [`verify_journal`], passing each
+/// frame's result to `on_frame` as soon as it is known, in file order.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::{verify_journal_with, FrameOutcome, VerifyOptions};
+///
+/// let report = verify_journal_with("events.nrj", &VerifyOptions::default(), |frame| {
+///     if frame.outcome != FrameOutcome::Valid {
+///         println!("frame at offset {}: {:?}", frame.offset, frame.outcome);
+///     }
+/// })?;
+/// println!("{} frames verified", report.totals.frames);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns [`JournalError`] like [`verify_journal`].
+pub fn verify_journal_with<P, F>(
+    path: P,
+    options: &VerifyOptions,
+    on_frame: F,
+) -> Result<VerificationReport, JournalError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&FrameReport),
+{
+    let mut reader = JournalReader::open(path, ReadMode::Strict)?;
+    verify_reader_with(&mut reader, options, on_frame)
+}
+
+/// Verifies the remaining frames of `reader` like [`verify_journal`].
+///
+/// The reader should be in [`ReadMode::Strict`]; other modes hide truncation
+/// or damage.
+///
+/// # Errors
+///
+/// Returns [`JournalError`] if the source cannot be read.
+pub fn verify_reader<R: Read>(
+    reader: &mut JournalReader<R>,
+    options: &VerifyOptions,
+) -> Result<VerificationReport, JournalError> {
+    verify_reader_with(reader, options, |_| {})
+}
+
+/// Verifies the remaining frames of `reader` like [`verify_journal_with`].
+///
+/// # Errors
+///
+/// Returns [`JournalError`] if the source cannot be read.
+pub fn verify_reader_with<R: Read, F: FnMut(&FrameReport)>(
+    reader: &mut JournalReader<R>,
+    options: &VerifyOptions,
+    mut on_frame: F,
+) -> Result<VerificationReport, JournalError> {
+    verify_from(reader, options, 0, &mut on_frame)
+}
+
+/// The part of a journal already verified, kept between runs of
+/// [`verify_incremental`].
+///
+/// Saved as JSON, by default next to the journal (see
+/// [`path_for`](Self::path_for)).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifiedPrefix {
+    /// Length of the prefix in bytes, ending after its last frame.
+    pub len: u64,
+    /// Number of frames in the prefix.
+    pub frames: u64,
+    /// SHA-256 of the raw bytes of the prefix, file header included.
+    pub sha256: Digest,
+    /// Hash chain value after the prefix, if the journal is hash-chained.
+    pub chain_head: Option<Digest>,
+}
+
+impl VerifiedPrefix {
+    /// Returns the default state file path for a journal: the journal path
+    /// with `.verified` appended.
+    pub fn path_for<P: AsRef<Path>>(journal: P) -> PathBuf {
+        let mut path = journal.as_ref().as_os_str().to_owned();
+        path.push(".verified");
+        PathBuf::from(path)
+    }
+
+    /// Loads a state file, returning `None` if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the file cannot be read or is not a valid
+    /// state file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Option<Self>, JournalError> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the state file via a temporary file and rename.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), JournalError> {
+        let path = path.as_ref();
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let mut tmp = File::create(&tmp_path)?;
+        serde_json::to_writer_pretty(&mut tmp, self)?;
+        tmp.sync_all()?;
+        drop(tmp);
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Verifies only the frames of the journal at `path` that follow `prefix`.
+///
+/// The prefix is confirmed by hashing its raw bytes, which is much cheaper
+/// than recomputing event IDs. Frames after it are then verified like
+/// [`verify_journal`], continuing the hash chain from the prefix; the report
+/// covers those frames only, with ordinals counted from the start of the
+/// journal. With no prefix the whole journal is verified.
+///
+/// Returns the prefix extended over the new frames, ready for the next run,
+/// unless one of them is mismatched, invalid or damaged. A partial frame at
+/// the end is left for the next run.
+///
+/// The prefix state does not record which `event_id`s it contains, so an
+/// event repeating one from the prefix is not reported as a duplicate;
+/// duplicates are only detected among the new frames. Run [`verify_journal`]
+/// to check the whole journal for them.
+///
+/// The journal is locked shared for the whole run according to
+/// [`VerifyOptions::lock`], so no writer can append in between.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use northroot_journal::{verify_incremental, VerifiedPrefix, VerifyOptions};
+///
+/// let state = VerifiedPrefix::path_for("events.nrj");
+/// let prefix = VerifiedPrefix::load(&state)?;
+/// let (report, verified) =
+///     verify_incremental("events.nrj", prefix.as_ref(), &VerifyOptions::default())?;
+/// if let Some(verified) = verified {
+///     verified.save(&state)?;
+/// }
+/// println!("{} new frames verified", report.totals.frames);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns [`JournalError::PrefixChanged`] if the journal no longer starts
+/// with the verified prefix, and other [`JournalError`]s like
+/// [`verify_journal`].
+pub fn verify_incremental<P: AsRef<Path>>(
+    path: P,
+    prefix: Option<&VerifiedPrefix>,
+    options: &VerifyOptions,
+) -> Result<(VerificationReport, Option<VerifiedPrefix>), JournalError> {
+    verify_incremental_with(path, prefix, options, |_| {})
+}
+
+/// Verifies the frames after `prefix` like [`verify_incremental`], passing
+/// each frame's result to `on_frame` like [`verify_journal_with`].
+///
+/// # Errors
+///
+/// Returns [`JournalError`] like [`verify_incremental`].
+pub fn verify_incremental_with<P, F>(
+    path: P,
+    prefix: Option<&VerifiedPrefix>,
+    options: &VerifyOptions,
+    mut on_frame: F,
+) -> Result<(VerificationReport, Option<VerifiedPrefix>), JournalError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&FrameReport),
+{
+    let path = path.as_ref();
+    let mut reader = JournalReader::open_locked(path, ReadMode::Strict, options.lock)?;
+    let mut hashed = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let (start, first_ordinal) = match prefix {
+        Some(prefix) => {
+            let changed = || JournalError::PrefixChanged { len: prefix.len };
+            if !hash_bytes(&mut hashed, &mut hasher, prefix.len)?
+                || chain::to_digest(&hasher.clone().finalize().into()) != prefix.sha256
+            {
+                return Err(changed());
+            }
+            let chain = match (reader.header().has_hash_chain(), &prefix.chain_head) {
+                (false, None) => None,
+                (true, Some(head)) => Some(ChainState {
+                    head: chain::from_digest(head).ok_or_else(changed)?,
+                    frames: prefix.frames,
+                }),
+                _ => return Err(changed()),
+            };
+            reader.resume(prefix.len, prefix.frames, chain)?;
+            (prefix.len, prefix.frames)
+        }
+        None => (0, 0),
+    };
+
+    let report = verify_from(&mut reader, options, first_ordinal, &mut on_frame)?;
+    let totals = &report.totals;
+    if totals.mismatch + totals.invalid + totals.damaged > 0 {
+        return Ok((report, None));
+    }
+    let end = reader.position();
+    if !hash_bytes(&mut hashed, &mut hasher, end - start)? {
+        return Err(JournalError::PrefixChanged { len: start });
+    }
+    let verified = VerifiedPrefix {
+        len: end,
+        frames: first_ordinal + totals.frames,
+        sha256: chain::to_digest(&hasher.finalize().into()),
+        chain_head: reader
+            .chain_state()
+            .map(|state| chain::to_digest(&state.head)),
+    };
+    Ok((report, Some(verified)))
+}
+
+/// Feeds the next `len` bytes of `source` to `hasher`. Returns `false` if
+/// the source ends first.
+fn hash_bytes<R: Read>(source: &mut R, hasher: &mut Sha256, len: u64) -> io::Result<bool> {
+    Ok(io::copy(&mut source.take(len), hasher)? == len)
+}
+
+/// Verifies the remaining frames of `reader`, the first of which has
+/// ordinal `first_ordinal`.
+fn verify_from<R: Read>(
+    reader: &mut JournalReader<R>,
+    options: &VerifyOptions,
+    first_ordinal: u64,
+    on_frame: &mut dyn FnMut(&FrameReport),
+) -> Result<VerificationReport, JournalError> {
+    let header = reader.header().clone();
+    let canonicalizer = Canonicalizer::new(options.profile.clone());
+    let mut scan = Scan {
+        canonical: header.has_canonical_payloads(),
+        max_events: options.max_events,
+        reject_duplicates: options.reject_duplicates,
+        detect_duplicates: options.detect_duplicates || options.reject_duplicates,
+        events_read: 0,
+        frames_read: first_ordinal,
+        done: false,
+        report: VerificationReport {
+            version: header.version,
+            flags: header.flags,
+            complete: false,
+            truncated_at: None,
+            chain_head: None,
+            totals: VerificationTotals::default(),
+            duplicate_event_ids: Vec::new(),
+            unknown_frame_kinds: Vec::new(),
+            failures: Vec::new(),
+        },
+        event_ids: HashMap::new(),
+        duplicates: HashMap::new(),
+        unknown_kinds: BTreeSet::new(),
+        on_frame,
+    };
+
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    if threads == 1 {
+        while let Some((mut frame, payload)) = scan.next_frame(reader)? {
+            if let Some(payload) = payload {
+                check_event(&payload, scan.canonical, &canonicalizer, &mut frame);
+            }
+            scan.record(frame);
+        }
+    } else {
+        scan.run_parallel(reader, &canonicalizer, threads)?;
+    }
+    Ok(scan.finish())
+}
+
+/// Frames handed to each worker at a time by the parallel path.
+const FRAMES_PER_WORKER: usize = 64;
+
+/// A frame read but not yet recorded, with the payload of an event still to
+/// be checked.
+type Pending = (FrameReport, Option<Vec<u8>>);
+
+/// State of one verification pass.
+struct Scan<'a> {
+    canonical: bool,
+    max_events: Option<u64>,
+    reject_duplicates: bool,
+    detect_duplicates: bool,
+    events_read: u64,
+    frames_read: u64,
+    done: bool,
+    report: VerificationReport,
+    /// Offset of the first event with each `event_id`.
+    event_ids: HashMap<IndexKey, u64>,
+    /// `event_id`s seen more than once.
+    duplicates: HashMap<IndexKey, DuplicateEventId>,
+    unknown_kinds: BTreeSet<u8>,
+    on_frame: &'a mut dyn FnMut(&FrameReport),
+}
+
+impl Scan<'_> {
+    /// Reads the next frame. Events come with their payload, which still
+    /// has to be checked; everything else is final. Returns `None` once
+    /// verification stops, after noting why in the report.
+    fn next_frame<R: Read>(
+        &mut self,
+        reader: &mut JournalReader<R>,
+    ) -> Result<Option<Pending>, JournalError> {
+        if self.done || self.max_events.is_some_and(|max| self.events_read >= max) {
+            self.done = true;
+            return Ok(None);
+        }
+        let offset = reader.position();
+        let ordinal = self.frames_read;
+        let mut frame = FrameReport {
+            ordinal,
+            offset,
+            kind: None,
+            outcome: FrameOutcome::Valid,
+            event_id: None,
+            error: None,
+            hygiene: None,
+        };
+        let (info, payload) = match reader.read_frame_with_info() {
+            Ok(Some(frame)) => frame,
+            Ok(None) => {
+                self.done = true;
+                self.report.complete = true;
+                self.report.chain_head = reader.chain_head();
+                return Ok(None);
+            }
+            Err(JournalError::TruncatedFrame { .. }) => {
+                self.done = true;
+                self.report.truncated_at = Some(offset);
+                return Ok(None);
+            }
+            Err(JournalError::Io(e)) => return Err(e.into()),
+            Err(e) => {
+                // Damage ends verification, but the frame is still reported
+                self.done = true;
+                self.frames_read += 1;
+                frame.outcome = FrameOutcome::Damaged;
+                frame.error = Some(e.to_string());
+                return Ok(Some((frame, None)));
+            }
+        };
+
+        self.frames_read += 1;
+        frame.kind = Some(info.kind().to_byte());
+        match info.kind() {
+            FrameKind::EventJson => {
+                self.events_read += 1;
+                return Ok(Some((frame, Some(payload))));
+            }
+            FrameKind::ChainHead | FrameKind::Index => {}
+            FrameKind::Unknown(_) => frame.outcome = FrameOutcome::UnknownKind,
+        }
+        Ok(Some((frame, None)))
+    }
+
+    /// Reads frames into windows of `threads * FRAMES_PER_WORKER` and checks
+    /// each window on `threads` workers while the next one is read, so at
+    /// most two windows of payloads are held at once.
+    fn run_parallel<R: Read>(
+        &mut self,
+        reader: &mut JournalReader<R>,
+        canonicalizer: &Canonicalizer,
+        threads: usize,
+    ) -> Result<(), JournalError> {
+        let window_len = threads * FRAMES_PER_WORKER;
+        let canonical = self.canonical;
+        std::thread::scope(|scope| {
+            let mut in_flight: Vec<ScopedJoinHandle<'_, Vec<FrameReport>>> = Vec::new();
+            loop {
+                let mut window = Vec::with_capacity(window_len);
+                while window.len() < window_len {
+                    match self.next_frame(reader)? {
+                        Some(pending) => window.push(pending),
+                        None => break,
+                    }
+                }
+                for worker in in_flight.drain(..) {
+                    for frame in worker.join().expect("verification worker panicked") {
+                        self.record(frame);
+                    }
+                }
+                if window.is_empty() {
+                    return Ok(());
+                }
+
+                let mut window = window.into_iter();
+                let chunk_len = window.len().div_ceil(threads);
+                for _ in 0..threads {
+                    let chunk: Vec<Pending> = window.by_ref().take(chunk_len).collect();
+                    if chunk.is_empty() {
+                        break;
+                    }
+                    in_flight.push(scope.spawn(move || {
+                        chunk
+                            .into_iter()
+                            .map(|(mut frame, payload)| {
+                                if let Some(payload) = payload {
+                                    check_event(&payload, canonical, canonicalizer, &mut frame);
+                                }
+                                frame
+                            })
+                            .collect()
+                    }));
+                }
+            }
+        })
+    }
+
+    /// Adds a checked frame to the report.
+    fn record(&mut self, mut frame: FrameReport) {
+        if frame.kind == Some(FRAME_KIND_EVENT_JSON) {
+            self.report.totals.events += 1;
+            if let Some(first) = self.record_event_id(&frame) {
+                if self.reject_duplicates && frame.outcome == FrameOutcome::Valid {
+                    frame.outcome = FrameOutcome::Invalid;
+                    frame.error = Some(format!("duplicate event_id (first at offset {first})"));
+                }
+            }
+        }
+        if let (FrameOutcome::UnknownKind, Some(kind)) = (frame.outcome, frame.kind) {
+            self.unknown_kinds.insert(kind);
+        }
+        (self.on_frame)(&frame);
+        self.report.push(frame);
+    }
+
+    /// Notes the event's `event_id` and returns the offset of an earlier
+    /// event with the same one.
+    fn record_event_id(&mut self, frame: &FrameReport) -> Option<u64> {
+        if !self.detect_duplicates {
+            return None;
+        }
+        let event_id = frame.event_id.as_ref()?;
+        let key = index::digest_key(event_id)?;
+        let first = *self.event_ids.entry(key).or_insert(frame.offset);
+        if first == frame.offset {
+            return None;
+        }
+        self.duplicates
+            .entry(key)
+            .or_insert_with(|| DuplicateEventId {
+                event_id: event_id.clone(),
+                offsets: vec![first],
+            })
+            .offsets
+            .push(frame.offset);
+        Some(first)
+    }
+
+    fn finish(self) -> VerificationReport {
+        let mut report = self.report;
+        let mut duplicates: Vec<_> = self.duplicates.into_values().collect();
+        duplicates.sort_by_key(|duplicate| duplicate.offsets[0]);
+        report.duplicate_event_ids = duplicates;
+        report.unknown_frame_kinds = self.unknown_kinds.into_iter().collect();
+        report
+    }
+}
+
+/// Fills in `frame` for the event stored as `payload`.
+fn check_event(
+    payload: &[u8],
+    canonical: bool,
+    canonicalizer: &Canonicalizer,
+    frame: &mut FrameReport,
+) {
+    let mut fail = |outcome, error: String| {
+        frame.outcome = outcome;
+        frame.error = Some(error);
+    };
+    let event: EventJson = match std::str::from_utf8(payload)
+        .map_err(JournalError::from)
+        .and_then(|text| Ok(serde_json::from_str(text)?))
+    {
+        Ok(event) => event,
+        Err(e) => return fail(FrameOutcome::Invalid, e.to_string()),
+    };
+    let claimed = event
+        .get("event_id")
+        .and_then(|id| serde_json::from_value::<Digest>(id.clone()).ok());
+    frame.event_id.clone_from(&claimed);
+
+    if canonical && !event::is_canonical_payload(payload, &event) {
+        return fail(FrameOutcome::Invalid, "non-canonical payload".to_string());
+    }
+    let Some(claimed) = claimed else {
+        return fail(
+            FrameOutcome::Invalid,
+            "missing or invalid event_id".to_string(),
+        );
+    };
+    match compute_event_id_with_report(&event, canonicalizer) {
+        Ok((computed, hygiene)) => {
+            frame.hygiene = Some(hygiene);
+            if computed != claimed {
+                frame.outcome = FrameOutcome::Mismatch;
+                frame.error = Some("event_id mismatch".to_string());
+            }
+        }
+        Err(e) => fail(
+            FrameOutcome::Invalid,
+            format!("event ID computation failed: {}", e),
+        ),
+    }
+}
@@ -1,38 +1,274 @@
 //! Journal writer implementation.
 
+use crate::chain::{self, ChainHead, ChainState};
+use crate::durability::{Durability, SyncKind, SyncSchedule};
 use crate::errors::JournalError;
-use crate::event::EventJson;
-use crate::frame::{FrameKind, JournalHeader, RecordFrame};
-use std::fs::{File, OpenOptions};
+use crate::event::{self, EventJson};
+use crate::frame::{
+    FrameKind, JournalHeader, RecordFrame, FLAG_CANONICAL_PAYLOAD, FLAG_FRAME_CRC32C,
+    FLAG_HASH_CHAIN,
+};
+use crate::index::{self, IndexEntry, IndexKey, IndexSegment, JournalIndex, MAX_INDEX_ENTRIES};
+use crate::lock::{self, LockPolicy};
+use crate::reader::{JournalReader, ReadMode};
+use crate::recovery;
+use crate::sidecar::SidecarIndex;
+use crate::storage::{JournalStorage, StorageStream};
+use crate::validation::{self, Rejection, ValidationPolicy};
+use northroot_canonical::Digest;
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Seek, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 /// Options for journal writing.
+///
+/// New options are added over time, so the struct cannot be built with a
+/// literal outside this crate; start from [`WriteOptions::default`] and use
+/// the `with_*` setters.
+///
+/// ```rust
+/// use northroot_journal::{Durability, WriteOptions};
+///
+/// let options = WriteOptions::default()
+///     .with_durability(Durability::Data)
+///     .with_checksums(true);
+/// assert!(options.checksums);
+/// ```
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct WriteOptions {
-    /// Whether to fsync after each append (default: false).
+    /// Whether to fsync after each append (default: false). When set, it
+    /// overrides [`durability`](Self::durability) with [`Durability::Full`].
+    #[deprecated(note = "use `durability` with `Durability::Full` instead")]
     pub sync: bool,
+    /// When appended frames are synced to disk (default: [`Durability::None`]).
+    pub durability: Durability,
     /// Whether to create the file if it doesn't exist (default: true).
     pub create: bool,
     /// Whether to append to an existing file (default: true).
     pub append: bool,
+    /// Whether a newly created journal uses the v2 format with per-frame
+    /// CRC32C checksums (default: false). Existing journals keep their format.
+    pub checksums: bool,
+    /// Whether a newly created journal uses the v2 format with a running hash
+    /// chain over frames (default: false). Existing journals keep their format.
+    pub hash_chain: bool,
+    /// Whether a newly created journal uses the v2 format and stores every
+    /// event as its canonical bytes (default: false), so journals with the
+    /// same events are byte-identical. Existing journals keep their format.
+    pub canonical_payloads: bool,
+    /// Whether to write an index segment for the events appended in this
+    /// session when the writer finishes (default: false). Enables
+    /// [`JournalReader::seek_ordinal`] and [`JournalReader::seek_event`]
+    /// without a full scan.
+    pub index: bool,
+    /// Whether [`JournalWriter::finish`] brings the journal's `.nrx` sidecar
+    /// up to date (default: false), creating it if needed; see
+    /// [`SidecarIndex`]. Only applies to writers opened
+    /// with [`JournalWriter::open`].
+    pub sidecar: bool,
+    /// What [`JournalWriter::open`] does if another writer or a locked reader
+    /// holds the journal (default: [`LockPolicy::Fail`]). The lock is held
+    /// until the writer is dropped.
+    pub lock: LockPolicy,
+    /// Checks events must pass before they are written (default: none).
+    pub validation: ValidationPolicy,
+    /// Number of appended frames collected in memory and then written
+    /// together, with one write and at most one sync (default: 0, every frame
+    /// is written as it is appended). Collected frames are written once the
+    /// buffer is full and by [`JournalWriter::flush`], and are lost if the
+    /// writer is dropped without finishing.
+    pub buffer: usize,
+    /// What happens to an event whose `event_id` is already in the journal
+    /// (default: [`DuplicatePolicy::Append`]).
+    pub duplicates: DuplicatePolicy,
 }
 
 impl Default for WriteOptions {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             sync: false,
+            durability: Durability::None,
             create: true,
             append: true,
+            checksums: false,
+            hash_chain: false,
+            canonical_payloads: false,
+            index: false,
+            sidecar: false,
+            lock: LockPolicy::Fail,
+            validation: ValidationPolicy::default(),
+            buffer: 0,
+            duplicates: DuplicatePolicy::Append,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Sets [`durability`](Self::durability).
+    pub fn with_durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    /// Sets [`create`](Self::create).
+    pub fn with_create(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+
+    /// Sets [`append`](Self::append).
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Sets [`checksums`](Self::checksums).
+    pub fn with_checksums(mut self, checksums: bool) -> Self {
+        self.checksums = checksums;
+        self
+    }
+
+    /// Sets [`hash_chain`](Self::hash_chain).
+    pub fn with_hash_chain(mut self, hash_chain: bool) -> Self {
+        self.hash_chain = hash_chain;
+        self
+    }
+
+    /// Sets [`canonical_payloads`](Self::canonical_payloads).
+    pub fn with_canonical_payloads(mut self, canonical_payloads: bool) -> Self {
+        self.canonical_payloads = canonical_payloads;
+        self
+    }
+
+    /// Sets [`index`](Self::index).
+    pub fn with_index(mut self, index: bool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Sets [`sidecar`](Self::sidecar).
+    pub fn with_sidecar(mut self, sidecar: bool) -> Self {
+        self.sidecar = sidecar;
+        self
+    }
+
+    /// Sets [`lock`](Self::lock).
+    pub fn with_lock(mut self, lock: LockPolicy) -> Self {
+        self.lock = lock;
+        self
+    }
+
+    /// Sets [`validation`](Self::validation).
+    pub fn with_validation(mut self, validation: ValidationPolicy) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Sets [`buffer`](Self::buffer).
+    pub fn with_buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer;
+        self
+    }
+
+    /// Sets [`duplicates`](Self::duplicates).
+    pub fn with_duplicates(mut self, duplicates: DuplicatePolicy) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Returns the durability in effect, honoring the deprecated `sync` flag.
+    #[allow(deprecated)]
+    fn effective_durability(&self) -> Durability {
+        if self.sync {
+            Durability::Full
+        } else {
+            self.durability
+        }
+    }
+
+    /// Whether the writer keeps the offset and `event_id` of every event.
+    fn tracks_events(&self) -> bool {
+        self.index || self.duplicates != DuplicatePolicy::Append
+    }
+
+    fn header_flags(&self) -> u16 {
+        let mut flags = 0;
+        if self.checksums {
+            flags |= FLAG_FRAME_CRC32C;
+        }
+        if self.hash_chain {
+            flags |= FLAG_HASH_CHAIN;
         }
+        if self.canonical_payloads {
+            flags |= FLAG_CANONICAL_PAYLOAD;
+        }
+        flags
     }
 }
 
+/// What [`JournalWriter`] does with an event whose `event_id` is already in
+/// the journal.
+///
+/// Any policy other than `Append` makes the writer keep the `event_id` of
+/// every event: on open it loads them from the journal's index segments and
+/// scans the frames after the latest one, or the whole journal if it has
+/// none. Events without a valid `event_id` are never duplicates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Append the event again.
+    #[default]
+    Append,
+    /// Leave the journal unchanged and return the receipt of the earlier
+    /// event, so retried appends are idempotent.
+    Skip,
+    /// Fail with [`Rejection::DuplicateEventId`].
+    Reject,
+}
+
+/// Where an appended event landed, returned by
+/// [`JournalWriter::append_event`].
+///
+/// The receipt is returned once the frame is written and, as far as
+/// [`WriteOptions::durability`] asks for it, synced; with
+/// [`Durability::None`] a crash can still lose the event. With
+/// [`WriteOptions::buffer`] the receipt describes where the event will land
+/// once the buffer is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppendReceipt {
+    /// Zero-based position among the journal's events, as used by
+    /// [`JournalReader::seek_ordinal`].
+    pub ordinal: u64,
+    /// Offset of the frame's record header, as used by
+    /// [`JournalReader::seek_offset`].
+    pub offset: u64,
+    /// Length of the whole frame on disk, including record header, extension
+    /// fields and payload.
+    pub frame_len: u64,
+    /// The event's `event_id`, if it carries a valid digest.
+    pub event_id: Option<Digest>,
+    /// Hash chain value covering every frame up to and including this one,
+    /// in hash-chained journals.
+    pub chain_head: Option<Digest>,
+    /// Whether the event was already in the journal and skipped under
+    /// [`DuplicatePolicy::Skip`]. The receipt then describes the earlier
+    /// event, with a `frame_len` of 0 and no `chain_head`.
+    pub duplicate: bool,
+}
+
 /// Journal writer for append-only event storage.
 ///
 /// The writer appends events to a journal file (`.nrj` format) in a framed,
 /// append-only manner. Events are stored as JSON objects within record frames.
 ///
+/// Any [`Write`] sink can receive a new journal through
+/// [`new`](JournalWriter::new); appending to existing bytes through
+/// [`resume`](JournalWriter::resume) also needs [`Read`] + [`Seek`].
+/// [`open`](JournalWriter::open) does the same for a journal file.
+///
 /// # Example
 ///
 /// ```rust,no_run
@@ -64,12 +300,94 @@ impl Default for WriteOptions {
 ///
 /// - [`JournalReader`](crate::JournalReader) - Read events from journals
 /// - [Journal Format Reference](../../../docs/reference/format.md) - Format specification
-pub struct JournalWriter {
-    file: File,
-    sync: bool,
-    header_written: bool,
+pub struct JournalWriter<W = StorageStream<File>> {
+    sink: W,
+    header: JournalHeader,
+    /// Offset at which the next frame is written.
+    position: u64,
+    schedule: SyncSchedule,
+    /// Syncs and rollback; `None` for sinks that can only be written and flushed.
+    ops: Option<SinkOps<W>>,
+    chain: Option<ChainState>,
+    /// Whether frames were appended since the last `ChainHead` trailer.
+    chain_dirty: bool,
+    /// Event offsets and IDs, tracked when indexing or duplicate detection
+    /// is enabled.
+    index: Option<JournalIndex>,
+    /// Whether index segments are written; see [`WriteOptions::index`].
+    segments: bool,
+    /// Journal whose sidecar is updated on finish; see [`WriteOptions::sidecar`].
+    sidecar: Option<PathBuf>,
+    duplicates: DuplicatePolicy,
+    /// Number of events in the journal, i.e. the ordinal of the next one.
+    events: u64,
+    validation: ValidationPolicy,
+    /// `occurred_at` of the last event, tracked for monotonic time checks.
+    last_occurred_at: Option<String>,
+    /// Frames encoded but not yet written.
+    batch: Batch,
+    /// Frames to collect before writing them; see [`WriteOptions::buffer`].
+    buffer: usize,
 }
 
+/// Frames encoded for writing in one go, after the writer's committed state.
+#[derive(Debug, Default)]
+struct Batch {
+    bytes: Vec<u8>,
+    frames: Vec<BatchFrame>,
+    /// Number of `EventJson` frames in `frames`.
+    events: u64,
+    /// The writer's `last_occurred_at` before the batch started.
+    last_occurred_at: Option<String>,
+}
+
+/// Where a batched frame goes and the writer state after it.
+#[derive(Debug)]
+struct BatchFrame {
+    kind: FrameKind,
+    offset: u64,
+    end: u64,
+    chain: Option<ChainState>,
+    /// Index key of an event frame.
+    key: IndexKey,
+    /// `occurred_at` of an event frame, tracked for monotonic time checks.
+    occurred_at: Option<String>,
+}
+
+impl Batch {
+    /// Drops every frame after the first `frames`.
+    fn truncate(&mut self, frames: usize) {
+        let Some(first) = self.frames.first() else {
+            return;
+        };
+        let len = self.frames[..frames]
+            .last()
+            .map_or(0, |last| (last.end - first.offset) as usize);
+        self.bytes.truncate(len);
+        self.frames.truncate(frames);
+        self.events = self.count_events(frames);
+    }
+
+    /// Number of `EventJson` frames among the first `frames`.
+    fn count_events(&self, frames: usize) -> u64 {
+        self.frames[..frames]
+            .iter()
+            .filter(|frame| frame.kind == FrameKind::EventJson)
+            .count() as u64
+    }
+}
+
+/// Operations only storage-backed sinks support.
+struct SinkOps<W> {
+    /// Makes appended bytes durable.
+    sync: fn(&mut W, SyncKind) -> io::Result<()>,
+    /// Cuts the sink back to the given length, undoing a failed append.
+    truncate: fn(&mut W, u64) -> io::Result<()>,
+}
+
+/// Distinguishes temporary files of journals created concurrently in one process.
+static CREATE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
 impl JournalWriter {
     /// Opens or creates a journal file for writing.
     ///
@@ -77,16 +395,28 @@ impl JournalWriter {
     /// file is created with a header. If the file exists, it is opened for appending
     /// (if `options.append` is `true`) or truncated (if `false`).
     ///
+    /// New journals are written to a temporary file in the same directory and
+    /// then linked into place, so a crash leaves either no journal or one with
+    /// a complete header. Unless [`WriteOptions::durability`] is [`Durability::None`],
+    /// the header and the directory entry are synced before returning.
+    ///
+    /// The file is locked exclusively before anything is read or written; see
+    /// [`WriteOptions::lock`].
+    ///
     /// # Example
     ///
     /// ```rust,no_run
-    /// use northroot_journal::{JournalWriter, WriteOptions};
-    ///
-    /// let options = WriteOptions {
-    ///     sync: false,
-    ///     create: true,
-    ///     append: true,
+    /// use northroot_journal::{
+    ///     DuplicatePolicy, Durability, JournalWriter, LockPolicy, ValidationPolicy,
+    ///     WriteOptions,
     /// };
+    ///
+    /// let options = WriteOptions::default()
+    ///     .with_durability(Durability::Data)
+    ///     .with_checksums(true)
+    ///     .with_lock(LockPolicy::Wait)
+    ///     .with_validation(ValidationPolicy::strict())
+    ///     .with_duplicates(DuplicatePolicy::Skip);
     /// let writer = JournalWriter::open("events.nrj", options)?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
@@ -95,64 +425,240 @@ impl JournalWriter {
     ///
     /// Returns [`JournalError`] if:
     /// - File cannot be opened/created
+    /// - File is locked and `options.lock` is [`LockPolicy::Fail`]
     /// - Existing file is not a valid journal
     /// - File is not empty but too small to be valid
     pub fn open<P: AsRef<Path>>(path: P, options: WriteOptions) -> Result<Self, JournalError> {
-        let file = OpenOptions::new()
-            .create(options.create)
-            .write(true)
-            .read(true)
-            .open(path)?;
-
-        let mut writer = Self {
-            file,
-            sync: options.sync,
-            header_written: false,
-        };
+        let path = path.as_ref();
+        if options.create && !path.exists() {
+            create_journal(path, &options)?;
+        }
+        let file = OpenOptions::new().write(true).read(true).open(path)?;
+        lock::lock_exclusive(&file, options.lock)?;
+        let sidecar = options.sidecar.then(|| path.to_path_buf());
+        let mut writer = Self::with_storage(file, options)?;
+        writer.sidecar = sidecar;
+        Ok(writer)
+    }
+}
 
-        // Check if file is empty; if so, write header
-        let metadata = writer.file.metadata()?;
-        if metadata.len() == 0 {
-            writer.write_header()?;
-        } else if metadata.len() < JournalHeader::HEADER_SIZE as u64 {
-            return Err(JournalError::FileNotEmpty);
-        } else {
-            // File exists and has at least header size; verify it's a valid journal
+impl<S: JournalStorage> JournalWriter<StorageStream<S>> {
+    /// Opens the journal kept in `storage` for writing.
+    ///
+    /// Empty storage gets a new header. Otherwise the journal is resumed like
+    /// [`resume`](Self::resume), or truncated to its header if
+    /// `options.append` is `false`. `options.create` and `options.lock` only
+    /// apply to [`open`](Self::open).
+    ///
+    /// Appended frames are synced through [`JournalStorage::sync`] as
+    /// `options.durability` asks. If writing or syncing a frame fails, the
+    /// storage is truncated back to where the frame started, so the error
+    /// means the frame was not appended.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use northroot_journal::{JournalReader, JournalWriter, MemoryStorage, ReadMode, WriteOptions};
+    /// use serde_json::json;
+    ///
+    /// let mut writer = JournalWriter::with_storage(MemoryStorage::new(), WriteOptions::default())?;
+    /// writer.append_event(&json!({"event_type": "test"}))?;
+    /// let storage = writer.into_inner()?.into_storage();
+    ///
+    /// let mut reader = JournalReader::from_storage(storage, ReadMode::Strict)?;
+    /// assert!(reader.read_event()?.is_some());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] for the same reasons as
+    /// [`resume`](Self::resume), or if the storage fails.
+    pub fn with_storage(mut storage: S, options: WriteOptions) -> Result<Self, JournalError> {
+        if !options.append && storage.len()? > JournalHeader::HEADER_SIZE as u64 {
+            // Only truncate storage that holds a valid journal; keep its header
             let mut header_bytes = [0u8; JournalHeader::HEADER_SIZE];
-            writer.file.seek(io::SeekFrom::Start(0))?;
-            writer.file.read_exact(&mut header_bytes)?;
+            StorageStream::new(&mut storage).read_exact(&mut header_bytes)?;
             JournalHeader::from_bytes(&header_bytes)?;
-            writer.header_written = true;
-            // Seek to end for appending
-            if options.append {
-                writer.file.seek(io::SeekFrom::End(0))?;
-            } else {
-                writer.file.seek(io::SeekFrom::Start(0))?;
-                writer.file.set_len(JournalHeader::HEADER_SIZE as u64)?;
-                writer
-                    .file
-                    .seek(io::SeekFrom::Start(JournalHeader::HEADER_SIZE as u64))?;
+            storage.truncate(JournalHeader::HEADER_SIZE as u64)?;
+        }
+
+        let ops = SinkOps {
+            sync: StorageStream::sync,
+            truncate: StorageStream::truncate,
+        };
+        Self::resume_with(StorageStream::new(storage), options, Some(ops))
+    }
+}
+
+/// Creates a journal file at `path` that contains only a header.
+///
+/// The header is written to a temporary file that is then hard-linked into
+/// place, which unlike a rename never replaces a journal another process
+/// created in the meantime. A crash can leave the temporary file behind but
+/// never a journal with a partial header.
+fn create_journal(path: &Path, options: &WriteOptions) -> Result<(), JournalError> {
+    let header = JournalHeader::with_flags(options.header_flags())?;
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "journal path has no file name")
+    })?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let temp = dir.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        CREATE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let durable = options.effective_durability() != Durability::None;
+
+    let result = (|| -> Result<(), JournalError> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temp)?;
+        file.write_all(&header.to_bytes())?;
+        if durable {
+            file.sync_all()?;
+        }
+        match fs::hard_link(&temp, path) {
+            Ok(()) => {}
+            // Another writer created the journal first; append to theirs
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(()),
+            // File systems without hard links
+            Err(_) if !path.exists() => fs::rename(&temp, path)?,
+            Err(_) => return Ok(()),
+        }
+        if durable {
+            sync_dir(dir)?;
+        }
+        Ok(())
+    })();
+    let _ = fs::remove_file(&temp);
+    result
+}
+
+/// Writes `buf` like [`Write::write_all`], counting the bytes the sink
+/// accepted in `written` so a failed write shows how far it got.
+fn write_counted<W: Write>(sink: &mut W, mut buf: &[u8], written: &mut usize) -> io::Result<()> {
+    while !buf.is_empty() {
+        match sink.write(buf) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => {
+                *written += n;
+                buf = &buf[n..];
             }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
         }
+    }
+    Ok(())
+}
+
+/// Makes a new directory entry durable.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
 
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+impl<W: Write> JournalWriter<W> {
+    /// Starts a new journal in `sink`, writing the file header immediately.
+    ///
+    /// The sink is assumed to be empty. [`WriteOptions::create`] and
+    /// [`WriteOptions::append`] only apply to existing journals and are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use northroot_journal::{JournalWriter, WriteOptions};
+    /// use serde_json::json;
+    ///
+    /// let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default())?;
+    /// writer.append_event(&json!({"event_type": "test"}))?;
+    /// let bytes = writer.into_inner()?;
+    /// assert_eq!(&bytes[..4], b"NRJ1");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the header cannot be written.
+    pub fn new(sink: W, options: WriteOptions) -> Result<Self, JournalError> {
+        Self::create_with(sink, options, None)
+    }
+
+    fn create_with(
+        sink: W,
+        options: WriteOptions,
+        ops: Option<SinkOps<W>>,
+    ) -> Result<Self, JournalError> {
+        let header = JournalHeader::with_flags(options.header_flags())?;
+        let mut writer = Self {
+            sink,
+            chain: header.has_hash_chain().then(|| ChainState::new(&header)),
+            header,
+            position: 0,
+            schedule: SyncSchedule::new(options.effective_durability()),
+            ops,
+            chain_dirty: false,
+            index: options.tracks_events().then(JournalIndex::default),
+            segments: options.index,
+            sidecar: None,
+            duplicates: options.duplicates,
+            events: 0,
+            validation: options.validation,
+            last_occurred_at: None,
+            batch: Batch::default(),
+            buffer: options.buffer,
+        };
+        writer.write_header()?;
         Ok(writer)
     }
 
     fn write_header(&mut self) -> Result<(), JournalError> {
-        let header = JournalHeader::new();
-        let bytes = header.to_bytes();
-        self.file.write_all(&bytes)?;
-        self.file.flush()?;
-        if self.sync {
-            self.file.sync_all()?;
+        let bytes = self.header.to_bytes();
+        self.sink.write_all(&bytes)?;
+        self.position = bytes.len() as u64;
+        self.flush_sink(1)?;
+        Ok(())
+    }
+
+    /// Flushes the sink after writing `frames` frames and syncs it if
+    /// [`WriteOptions::durability`] calls for it.
+    fn flush_sink(&mut self, frames: u32) -> io::Result<()> {
+        self.sink.flush()?;
+        let now = Instant::now();
+        let due = (0..frames).fold(None, |due, _| self.schedule.record(now).or(due));
+        match due {
+            // Frames that could not be synced are rolled back by the caller
+            Some(kind) => self
+                .sync(kind)
+                .inspect_err(|_| self.schedule.forget(frames)),
+            None => Ok(()),
+        }
+    }
+
+    fn sync(&mut self, kind: SyncKind) -> io::Result<()> {
+        if let Some(ops) = &self.ops {
+            (ops.sync)(&mut self.sink, kind)?;
         }
-        self.header_written = true;
+        self.schedule.synced(Instant::now());
         Ok(())
     }
 
     /// Appends an event JSON payload to the journal.
     ///
-    /// The event is serialized to JSON and written as an `EventJson` frame.
+    /// The event is serialized to JSON and written as an `EventJson` frame. In
+    /// journals created with [`WriteOptions::canonical_payloads`] the payload
+    /// is the event's [canonical bytes](crate::event::canonical_payload).
     /// The event should include an `event_id` field computed via [`compute_event_id`](northroot_canonical::compute_event_id).
     ///
     /// # Example
@@ -170,7 +676,8 @@ impl JournalWriter {
     /// event["event_id"] = serde_json::to_value(&event_id)?;
     ///
     /// let mut writer = JournalWriter::open("events.nrj", WriteOptions::default())?;
-    /// writer.append_event(&event)?;
+    /// let receipt = writer.append_event(&event)?;
+    /// println!("event {} at offset {}", receipt.ordinal, receipt.offset);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     ///
@@ -180,50 +687,586 @@ impl JournalWriter {
     /// - Header not written
     /// - JSON serialization fails
     /// - I/O error occurs
-    pub fn append_event(&mut self, event: &EventJson) -> Result<(), JournalError> {
-        let json_bytes = serde_json::to_vec(event)?;
-        self.append_raw(FrameKind::EventJson, &json_bytes)
+    pub fn append_event(&mut self, event: &EventJson) -> Result<AppendReceipt, JournalError> {
+        let receipt = self.push_event(event)?;
+        self.commit_if_full()?;
+        Ok(receipt)
+    }
+
+    /// Appends several events as one group commit.
+    ///
+    /// Every event is checked and encoded first, so a rejected or unencodable
+    /// event fails the call before anything is written. The frames, together
+    /// with any frames still held by [`WriteOptions::buffer`], are then written
+    /// with a single write and synced at most once, which makes bulk loads far
+    /// cheaper than appending events one by one. The resulting journal is
+    /// byte-identical either way.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use northroot_journal::{JournalWriter, WriteOptions};
+    /// use serde_json::json;
+    ///
+    /// let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default())?;
+    /// let events: Vec<_> = (0..3).map(|n| json!({"event_type": "test", "n": n})).collect();
+    /// let receipts = writer.append_batch(&events)?;
+    /// assert_eq!(receipts[2].ordinal, 2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] for the same reasons as
+    /// [`append_event`](Self::append_event); no event of the batch is written
+    /// then. Writers created by [`open`](JournalWriter::open) or
+    /// [`with_storage`](Self::with_storage) roll back a batch that cannot be
+    /// written or synced. Other sinks cannot be rolled back: if part of the
+    /// batch was written, [`JournalError::BatchInterrupted`] says how many of
+    /// its events are in the journal.
+    pub fn append_batch(
+        &mut self,
+        events: &[EventJson],
+    ) -> Result<Vec<AppendReceipt>, JournalError> {
+        let mark = self.batch.frames.len();
+        let last_occurred_at = self.last_occurred_at.clone();
+        let receipts = events
+            .iter()
+            .map(|event| self.push_event(event))
+            .collect::<Result<Vec<_>, _>>()
+            .inspect_err(|_| {
+                self.batch.truncate(mark);
+                self.last_occurred_at = last_occurred_at;
+            })?;
+        self.commit()?;
+        Ok(receipts)
+    }
+
+    /// Checks and encodes an event after the batched frames.
+    fn push_event(&mut self, event: &EventJson) -> Result<AppendReceipt, JournalError> {
+        // Repeats are looked up first, so a retried event is not rejected as
+        // out of order
+        if let Some(receipt) = self.check_duplicate(event)? {
+            return Ok(receipt);
+        }
+        self.validate(event)?;
+        let json_bytes = if self.header.has_canonical_payloads() {
+            event::canonical_payload(event)?
+        } else {
+            serde_json::to_vec(event)?
+        };
+        self.push_frame(FrameKind::EventJson, &json_bytes, Some(event))?;
+        let frame = self.batch.frames.last().expect("frame just pushed");
+        Ok(AppendReceipt {
+            ordinal: self.events + self.batch.events - 1,
+            offset: frame.offset,
+            frame_len: frame.end - frame.offset,
+            event_id: event
+                .get("event_id")
+                .and_then(|id| serde_json::from_value(id.clone()).ok()),
+            chain_head: frame.chain.map(|state| chain::to_digest(&state.head)),
+            duplicate: false,
+        })
+    }
+
+    /// Looks `event` up among the written and batched events under
+    /// [`WriteOptions::duplicates`], returning the earlier event's receipt if
+    /// the event is to be skipped.
+    fn check_duplicate(&self, event: &EventJson) -> Result<Option<AppendReceipt>, JournalError> {
+        if self.duplicates == DuplicatePolicy::Append {
+            return Ok(None);
+        }
+        let Some((ordinal, offset)) = self.find_event(&index::event_key(event)) else {
+            return Ok(None);
+        };
+        let event_id = event
+            .get("event_id")
+            .and_then(|id| serde_json::from_value(id.clone()).ok())
+            .expect("events with a key have a valid event_id");
+        match self.duplicates {
+            DuplicatePolicy::Reject => {
+                Err(Rejection::DuplicateEventId { event_id, ordinal }.into())
+            }
+            _ => Ok(Some(AppendReceipt {
+                ordinal,
+                offset,
+                frame_len: 0,
+                event_id: Some(event_id),
+                chain_head: None,
+                duplicate: true,
+            })),
+        }
+    }
+
+    /// Returns the ordinal and offset of the first event with index key
+    /// `key`, if duplicates are tracked.
+    fn find_event(&self, key: &IndexKey) -> Option<(u64, u64)> {
+        let index = self.index.as_ref()?;
+        if *key == [0u8; 32] {
+            return None;
+        }
+        if let Some(&ordinal) = index.by_id.get(key) {
+            return Some((ordinal, index.entries[ordinal as usize].offset));
+        }
+        self.batch
+            .frames
+            .iter()
+            .filter(|frame| frame.kind == FrameKind::EventJson)
+            .zip(self.events..)
+            .find(|(frame, _)| frame.key == *key)
+            .map(|(frame, ordinal)| (ordinal, frame.offset))
     }
 
     /// Appends a raw frame with the given kind and payload.
+    ///
+    /// `ChainHead` trailers and `Index` segments are maintained by the writer
+    /// and cannot be appended directly, and an empty frame of kind 0 is
+    /// refused because it reads as zero-filled space. `EventJson` payloads
+    /// are parsed and checked if [`WriteOptions::validation`] enables any
+    /// check, and looked up under [`WriteOptions::duplicates`]; a skipped
+    /// duplicate is not written.
     pub fn append_raw(&mut self, kind: FrameKind, payload: &[u8]) -> Result<(), JournalError> {
-        if !self.header_written {
-            return Err(JournalError::InvalidHeader(
-                "header not written".to_string(),
-            ));
+        if matches!(kind, FrameKind::ChainHead | FrameKind::Index) {
+            return Err(JournalError::InvalidFrame {
+                offset: self.position(),
+                reason: format!("{:?} frames are written by the journal writer", kind),
+            });
         }
+        if kind == FrameKind::Unknown(0) && payload.is_empty() {
+            return Err(JournalError::InvalidFrame {
+                offset: self.position(),
+                reason: "an empty frame of kind 0x00 reads as zero-filled space".to_string(),
+            });
+        }
+        let canonical = self.header.has_canonical_payloads();
+        let parse =
+            canonical || self.validation.is_enabled() || self.duplicates != DuplicatePolicy::Append;
+        let event = match kind {
+            FrameKind::EventJson if parse => {
+                let event: EventJson = serde_json::from_slice(payload)?;
+                if canonical && !event::is_canonical_payload(payload, &event) {
+                    return Err(JournalError::NonCanonicalPayload {
+                        offset: self.position(),
+                    });
+                }
+                if self.check_duplicate(&event)?.is_some() {
+                    return Ok(());
+                }
+                self.validate(&event)?;
+                Some(event)
+            }
+            _ => None,
+        };
+        self.push_frame(kind, payload, event.as_ref())?;
+        self.commit_if_full()
+    }
 
+    /// Writes frames held back by [`WriteOptions::buffer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the frames cannot be written or synced; see
+    /// [`append_batch`](Self::append_batch) for what then reached the journal.
+    pub fn flush(&mut self) -> Result<(), JournalError> {
+        self.commit()
+    }
+
+    /// Checks `event` against [`WriteOptions::validation`].
+    fn validate(&self, event: &EventJson) -> Result<(), JournalError> {
+        Ok(self
+            .validation
+            .check(event, self.last_occurred_at.as_deref())?)
+    }
+
+    /// Encodes a frame after the batched frames.
+    ///
+    /// `event` is the parsed payload of an `EventJson` frame, if available.
+    fn push_frame(
+        &mut self,
+        kind: FrameKind,
+        payload: &[u8],
+        event: Option<&EventJson>,
+    ) -> Result<(), JournalError> {
         let frame = RecordFrame::new(kind, payload.len() as u32)?;
-        let frame_bytes = frame.to_bytes();
+        let offset = self.position();
+        let mut chain = self
+            .batch
+            .frames
+            .last()
+            .map_or(self.chain, |last| last.chain);
+        let prev = chain.as_ref().map(|state| state.head);
+        let bytes = self.header.encode_frame(&frame, prev.as_ref(), payload)?;
+        if let Some(state) = chain.as_mut() {
+            state.head = chain::next(&state.head, &frame.to_bytes(), payload);
+            state.frames += 1;
+        }
 
-        // Write frame header
-        self.file.write_all(&frame_bytes)?;
-        // Write payload
-        self.file.write_all(payload)?;
-        self.file.flush()?;
+        let mut key = [0u8; 32];
+        let mut occurred_at = None;
+        if kind == FrameKind::EventJson {
+            if self.index.is_some() {
+                key = event.map_or_else(|| index::payload_key(payload), index::event_key);
+            }
+            if self.validation.monotonic_time {
+                occurred_at = event
+                    .and_then(|event| validation::occurred_at(event).ok())
+                    .map(String::from);
+            }
+            self.batch.events += 1;
+        }
 
-        if self.sync {
-            self.file.sync_all()?;
+        if self.batch.frames.is_empty() {
+            self.batch.last_occurred_at = self.last_occurred_at.clone();
+        }
+        if occurred_at.is_some() {
+            self.last_occurred_at.clone_from(&occurred_at);
         }
+        self.batch.bytes.extend_from_slice(&bytes);
+        self.batch.frames.push(BatchFrame {
+            kind,
+            offset,
+            end: offset + bytes.len() as u64,
+            chain,
+            key,
+            occurred_at,
+        });
+        Ok(())
+    }
 
+    /// Writes the batch once it holds [`WriteOptions::buffer`] frames.
+    fn commit_if_full(&mut self) -> Result<(), JournalError> {
+        if self.batch.frames.len() >= self.buffer {
+            self.commit()?;
+        }
         Ok(())
     }
 
+    /// Writes the batched frames with a single write and at most one sync.
+    fn commit(&mut self) -> Result<(), JournalError> {
+        if self.batch.frames.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.batch);
+        let start = self.position;
+        let mut written = 0;
+        let result = write_counted(&mut self.sink, &batch.bytes, &mut written)
+            .and_then(|()| self.flush_sink(batch.frames.len() as u32));
+        let Err(e) = result else {
+            self.apply(&batch.frames);
+            return Ok(());
+        };
+
+        // Roll back the whole batch if the sink allows it; otherwise keep
+        // the frames that were written completely
+        let rolled_back = self
+            .ops
+            .as_ref()
+            .is_some_and(|ops| (ops.truncate)(&mut self.sink, start).is_ok());
+        let committed = if rolled_back {
+            0
+        } else {
+            let written = start + written as u64;
+            batch.frames.partition_point(|frame| frame.end <= written)
+        };
+        self.apply(&batch.frames[..committed]);
+        self.last_occurred_at = batch.frames[..committed]
+            .iter()
+            .rev()
+            .find_map(|frame| frame.occurred_at.clone())
+            .or(batch.last_occurred_at.clone());
+        match batch.count_events(committed) {
+            0 => Err(e.into()),
+            committed => Err(JournalError::BatchInterrupted {
+                committed,
+                source: e,
+            }),
+        }
+    }
+
+    /// Advances the writer past frames that were written.
+    fn apply(&mut self, frames: &[BatchFrame]) {
+        for frame in frames {
+            self.position = frame.end;
+            self.chain = frame.chain;
+            self.chain_dirty = frame.kind != FrameKind::ChainHead;
+            if frame.kind == FrameKind::EventJson {
+                self.events += 1;
+                if let Some(index) = self.index.as_mut() {
+                    index.push(IndexEntry {
+                        offset: frame.offset,
+                        event_id: frame.key,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Writes a frame maintained by the writer, after any batched frames.
+    fn write_frame(&mut self, kind: FrameKind, payload: &[u8]) -> Result<(), JournalError> {
+        self.push_frame(kind, payload, None)?;
+        self.commit()
+    }
+
+    /// Appends an index segment covering the events not yet indexed.
+    ///
+    /// Does nothing if every event is already covered. [`finish`](Self::finish)
+    /// calls this automatically; call it directly to checkpoint the index of a
+    /// long-running writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if indexing is not enabled in [`WriteOptions`]
+    /// or an I/O error occurs.
+    pub fn write_index(&mut self) -> Result<(), JournalError> {
+        if !self.segments {
+            return Err(JournalError::InvalidFrame {
+                offset: self.position(),
+                reason: "indexing is not enabled for this writer".to_string(),
+            });
+        }
+        // Batched events must be covered too
+        self.commit()?;
+        let index = self.index.as_ref().expect("indexing enabled");
+        let pending = index.pending().to_vec();
+        for entries in pending.chunks(MAX_INDEX_ENTRIES) {
+            let index = self.index.as_ref().expect("indexing enabled");
+            let segment = IndexSegment {
+                first_ordinal: index.indexed,
+                prev_offset: index.last_segment,
+                entries: entries.to_vec(),
+            };
+            let offset = self.position;
+            self.write_frame(FrameKind::Index, &segment.to_bytes())?;
+
+            let index = self.index.as_mut().expect("indexing enabled");
+            index.indexed += entries.len() as u64;
+            index.last_segment = Some(offset);
+        }
+        Ok(())
+    }
+
+    /// Returns the hash chain value covering every frame written so far.
+    ///
+    /// Returns `None` unless the journal is hash-chained. Frames held back by
+    /// [`WriteOptions::buffer`] count as written.
+    pub fn chain_head(&self) -> Option<Digest> {
+        self.batch
+            .frames
+            .last()
+            .map_or(self.chain, |last| last.chain)
+            .map(|state| chain::to_digest(&state.head))
+    }
+
+    /// Returns the journal file header in effect for this writer.
+    pub fn header(&self) -> &JournalHeader {
+        &self.header
+    }
+
+    /// Returns the offset at which the next frame will be written.
+    ///
+    /// Frames held back by [`WriteOptions::buffer`] count as written.
+    pub fn position(&self) -> u64 {
+        self.position + self.batch.bytes.len() as u64
+    }
+
     /// Finishes writing and closes the file.
+    ///
+    /// With [`WriteOptions::index`] an index segment is appended for events not
+    /// yet indexed. In hash-chained journals a `ChainHead` trailer is then
+    /// appended if any frames were written, so the next writer can resume
+    /// without rescanning. Finally, frames not yet synced under
+    /// [`WriteOptions::durability`] are synced. With [`WriteOptions::sidecar`]
+    /// the `.nrx` sidecar is updated last; if that fails the error is
+    /// returned, but the events are already written. Dropping the writer
+    /// without calling `finish` skips all of these.
     pub fn finish(mut self) -> Result<(), JournalError> {
-        self.file.flush()?;
-        if self.sync {
-            self.file.sync_all()?;
+        self.finish_in_place()
+    }
+
+    /// Finishes writing like [`finish`](Self::finish) and returns the sink.
+    pub fn into_inner(mut self) -> Result<W, JournalError> {
+        self.finish_in_place()?;
+        Ok(self.sink)
+    }
+
+    fn finish_in_place(&mut self) -> Result<(), JournalError> {
+        self.commit()?;
+        if self.segments {
+            self.write_index()?;
+        }
+        if let (Some(state), true) = (self.chain, self.chain_dirty) {
+            let trailer = ChainHead {
+                head: state.head,
+                frames: state.frames,
+            };
+            self.write_frame(FrameKind::ChainHead, &trailer.to_bytes())?;
+        }
+        self.sink.flush()?;
+        if let Some(kind) = self.schedule.remaining() {
+            self.sync(kind)?;
+        }
+        if let Some(path) = &self.sidecar {
+            // Adds a run for this session's events; the journal is complete
+            // even if this fails
+            SidecarIndex::open(path)?;
         }
         Ok(())
     }
 }
 
-impl Drop for JournalWriter {
-    fn drop(&mut self) {
-        let _ = self.file.flush();
-        if self.sync {
-            let _ = self.file.sync_all();
+impl<W: Read + Write + Seek> JournalWriter<W> {
+    /// Continues the journal in `stream`, appending after its last frame.
+    ///
+    /// An empty stream gets a new header. Otherwise the existing header is
+    /// kept, the hash chain is resumed and, with [`WriteOptions::index`] or
+    /// [`WriteOptions::duplicates`], the existing events are indexed, so
+    /// [`AppendReceipt::ordinal`]s continue the journal's. This takes one
+    /// walk over the frames, which starts after the index segment at the end
+    /// of the journal if there is one and reads only the frame headers unless
+    /// events are indexed or the journal ends without a `ChainHead` trailer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JournalError`] if the stream is not empty but does not start
+    /// with a valid journal header, or an I/O error occurs. Returns
+    /// [`JournalError::TornTail`] if the journal ends in a partial or damaged
+    /// frame; see [`JournalRecovery`](crate::JournalRecovery).
+    pub fn resume(stream: W, options: WriteOptions) -> Result<Self, JournalError> {
+        Self::resume_with(stream, options, None)
+    }
+
+    fn resume_with(
+        mut stream: W,
+        options: WriteOptions,
+        ops: Option<SinkOps<W>>,
+    ) -> Result<Self, JournalError> {
+        let len = stream.seek(io::SeekFrom::End(0))?;
+        if len == 0 {
+            return Self::create_with(stream, options, ops);
+        } else if len < JournalHeader::HEADER_SIZE as u64 {
+            return Err(JournalError::FileNotEmpty);
+        }
+
+        // Stream has at least header size; verify it's a valid journal
+        let mut header_bytes = [0u8; JournalHeader::HEADER_SIZE];
+        stream.seek(io::SeekFrom::Start(0))?;
+        stream.read_exact(&mut header_bytes)?;
+        let header = JournalHeader::from_bytes(&header_bytes)?;
+
+        let mut writer = Self {
+            sink: stream,
+            chain: header.has_hash_chain().then(|| ChainState::new(&header)),
+            header,
+            position: len,
+            schedule: SyncSchedule::new(options.effective_durability()),
+            ops,
+            chain_dirty: false,
+            index: None,
+            segments: options.index,
+            sidecar: None,
+            duplicates: options.duplicates,
+            events: 0,
+            validation: options.validation,
+            last_occurred_at: None,
+            batch: Batch::default(),
+            buffer: options.buffer,
+        };
+        let trailer = if writer.header.has_hash_chain() {
+            writer.read_trailer(len)?
+        } else {
+            None
+        };
+        // Without a trailer the chain is recomputed from the first frame.
+        // Otherwise an index segment at the end covers the frames before it,
+        // and only the frames after it are walked.
+        let mut chain = match (writer.header.has_hash_chain(), trailer) {
+            (true, None) => Some(ChainState::new(&writer.header)),
+            _ => None,
+        };
+        let segments = if chain.is_none() {
+            writer.sink.seek(io::SeekFrom::Start(0))?;
+            JournalReader::new(&mut writer.sink, ReadMode::Permissive)?.into_segment_index()?
+        } else {
+            None
+        };
+        let header_len = JournalHeader::HEADER_SIZE as u64;
+        let from = segments
+            .as_ref()
+            .map_or(header_len, |index| index.scanned_until);
+        let segment_events = segments
+            .as_ref()
+            .map_or(0, |index| index.entries.len() as u64);
+        let segment_last = segments
+            .as_ref()
+            .and_then(|index| index.entries.last())
+            .map(|entry| entry.offset);
+        let mut index = options.tracks_events().then(|| {
+            segments.unwrap_or_else(|| JournalIndex {
+                scanned_until: header_len,
+                ..Default::default()
+            })
+        });
+
+        // Walking the frames also rules out a torn tail
+        let scan = recovery::check_tail(
+            &mut writer.sink,
+            &writer.header,
+            from,
+            len,
+            index.as_mut(),
+            chain.as_mut(),
+        )?;
+        writer.events = segment_events + scan.events;
+        writer.index = index;
+        if let Some(state) = trailer.or(chain) {
+            writer.chain = Some(state);
+        }
+        if options.validation.monotonic_time {
+            if let Some(offset) = scan.last_event.or(segment_last) {
+                writer.sink.seek(io::SeekFrom::Start(0))?;
+                let mut reader = JournalReader::new(&mut writer.sink, ReadMode::Permissive)?;
+                reader.seek_offset(offset)?;
+                if let Some(event) = reader.read_event()? {
+                    writer.last_occurred_at =
+                        validation::occurred_at(&event).ok().map(String::from);
+                }
+            }
+        }
+        // Seek to end for appending
+        writer.sink.seek(io::SeekFrom::Start(len))?;
+        Ok(writer)
+    }
+
+    /// Returns the chain state recorded by a `ChainHead` trailer, if the
+    /// stream ends with a consistent one.
+    fn read_trailer(&mut self, file_len: u64) -> Result<Option<ChainState>, JournalError> {
+        let header_len = JournalHeader::HEADER_SIZE as u64;
+        let trailer_len = ChainHead::frame_len(&self.header) as u64;
+        if file_len < header_len + trailer_len {
+            return Ok(None);
+        }
+        let offset = file_len - trailer_len;
+        let mut bytes = vec![0u8; trailer_len as usize];
+        self.sink.seek(io::SeekFrom::Start(offset))?;
+        self.sink.read_exact(&mut bytes)?;
+        Ok(self.parse_trailer(&bytes, offset))
+    }
+
+    /// Returns the chain state after `bytes` if they form a consistent trailer frame.
+    fn parse_trailer(&self, bytes: &[u8], offset: u64) -> Option<ChainState> {
+        let (frame, ext) = self.header.decode_frame_header(bytes, offset).ok()?;
+        let payload = &bytes[self.header.frame_header_len()..];
+        if frame.kind != FrameKind::ChainHead || frame.len as usize != payload.len() {
+            return None;
+        }
+        ext.verify_checksum(&frame, payload, offset).ok()?;
+        let trailer = ChainHead::from_bytes(payload, offset).ok()?;
+        if ext.prev_chain != Some(trailer.head) {
+            return None;
         }
+        Some(ChainState {
+            head: chain::next(&trailer.head, &frame.to_bytes(), payload),
+            frames: trailer.frames + 1,
+        })
     }
 }
@@ -0,0 +1,201 @@
+mod common;
+
+use northroot_journal::{
+    Durability, EventJson, Faults, FaultyStorage, JournalError, JournalReader, JournalWriter,
+    MemoryStorage, ReadMode, Rejection, ValidationPolicy, WriteOptions,
+};
+use serde_json::json;
+use std::io::{self, Write};
+
+fn make_test_event(n: usize) -> EventJson {
+    // Distinct times, so the monotonic time check passes
+    let mut event = common::make_test_event(n);
+    event["occurred_at"] = json!(format!("2024-01-01T00:00:{:02}Z", n));
+    event
+}
+
+fn events(range: std::ops::Range<usize>) -> Vec<EventJson> {
+    range.map(make_test_event).collect()
+}
+
+fn v2_options() -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+        .with_index(true)
+}
+
+fn read_numbers(bytes: &[u8]) -> Vec<u64> {
+    let storage = MemoryStorage::from_bytes(bytes.to_vec());
+    let mut reader = JournalReader::from_storage(storage, ReadMode::Strict).unwrap();
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    numbers
+}
+
+/// Accepts `limit` bytes, then fails every write.
+struct LimitedSink {
+    bytes: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for LimitedSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.limit - self.bytes.len());
+        if n == 0 {
+            return Err(io::ErrorKind::StorageFull.into());
+        }
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_batch_matches_single_appends() {
+    let mut single = JournalWriter::new(Vec::new(), v2_options()).unwrap();
+    let single_receipts: Vec<_> = events(0..5)
+        .iter()
+        .map(|event| single.append_event(event).unwrap())
+        .collect();
+    let expected = single.into_inner().unwrap();
+
+    let mut batched = JournalWriter::new(Vec::new(), v2_options()).unwrap();
+    let mut receipts = batched.append_batch(&events(0..2)).unwrap();
+    receipts.extend(batched.append_batch(&events(2..5)).unwrap());
+    assert_eq!(receipts, single_receipts);
+    assert_eq!(batched.into_inner().unwrap(), expected);
+
+    let options = v2_options().with_buffer(3);
+    let mut buffered = JournalWriter::new(Vec::new(), options).unwrap();
+    let receipts: Vec<_> = events(0..5)
+        .iter()
+        .map(|event| buffered.append_event(event).unwrap())
+        .collect();
+    assert_eq!(receipts, single_receipts);
+    assert_eq!(buffered.into_inner().unwrap(), expected);
+}
+
+#[test]
+fn test_batch_syncs_once() {
+    let options = WriteOptions::default().with_durability(Durability::Data);
+    let mut storage = MemoryStorage::new();
+    let mut writer = JournalWriter::with_storage(&mut storage, options).unwrap();
+    writer.append_batch(&events(0..50)).unwrap();
+    writer.finish().unwrap();
+    // One sync for the header, one for the batch
+    assert_eq!(storage.syncs(), 2);
+    assert_eq!(
+        read_numbers(storage.as_bytes()),
+        (0..50).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_buffered_frames_wait_for_flush() {
+    let options = WriteOptions::default()
+        .with_durability(Durability::Data)
+        .with_buffer(4);
+    let mut storage = MemoryStorage::new();
+    let mut writer = JournalWriter::with_storage(&mut storage, options).unwrap();
+    for event in events(0..6) {
+        writer.append_event(&event).unwrap();
+    }
+    writer.flush().unwrap();
+    writer.append_event(&make_test_event(6)).unwrap();
+    drop(writer);
+
+    // The header, the first four events and the flushed two were synced;
+    // the last event never left the buffer
+    assert_eq!(storage.syncs(), 3);
+    storage.crash();
+    assert_eq!(read_numbers(storage.as_bytes()), (0..6).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_rejected_event_fails_whole_batch() {
+    let options = WriteOptions::default().with_validation(ValidationPolicy {
+        monotonic_time: true,
+        ..Default::default()
+    });
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    writer.append_event(&make_test_event(1)).unwrap();
+    let position = writer.position();
+
+    // Ordered within the batch, but the last event predates the journal's
+    let result = writer.append_batch(&[make_test_event(2), make_test_event(3), make_test_event(0)]);
+    assert!(matches!(
+        result,
+        Err(JournalError::Rejected(Rejection::OutOfOrder { .. }))
+    ));
+    assert_eq!(writer.position(), position);
+
+    // The batch left no trace, not even in the monotonic time check
+    let receipts = writer.append_batch(&events(1..3)).unwrap();
+    assert_eq!(receipts[0].ordinal, 1);
+    assert_eq!(read_numbers(&writer.into_inner().unwrap()), vec![1, 1, 2]);
+}
+
+#[test]
+fn test_failed_batch_rolls_back() {
+    let one_event = {
+        let mut writer = JournalWriter::with_storage(MemoryStorage::new(), v2_options()).unwrap();
+        writer.append_event(&make_test_event(0)).unwrap();
+        writer.into_inner().unwrap().storage().as_bytes().len()
+    };
+    let faults = Faults {
+        capacity: Some(one_event as u64 + 200),
+        ..Default::default()
+    };
+    let mut storage = FaultyStorage::new(MemoryStorage::new(), faults);
+    let mut writer = JournalWriter::with_storage(&mut storage, v2_options()).unwrap();
+    writer.append_event(&make_test_event(0)).unwrap();
+    let position = writer.position();
+
+    // The first events of the batch would fit, but none of them is kept
+    let err = writer.append_batch(&events(1..10)).unwrap_err();
+    assert!(matches!(err, JournalError::Io(ref e) if e.kind() == io::ErrorKind::StorageFull));
+    assert_eq!(writer.position(), position);
+    assert_eq!(storage.inner().as_bytes().len() as u64, position);
+
+    storage.faults_mut().capacity = None;
+    let mut writer = JournalWriter::with_storage(&mut storage, v2_options()).unwrap();
+    let receipts = writer.append_batch(&events(1..3)).unwrap();
+    assert_eq!(receipts[0].ordinal, 1);
+    writer.finish().unwrap();
+    let bytes = storage.into_inner().into_bytes();
+    assert_eq!(read_numbers(&bytes), vec![0, 1, 2]);
+    let mut reader =
+        JournalReader::from_storage(MemoryStorage::from_bytes(bytes), ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+}
+
+#[test]
+fn test_interrupted_batch_reports_committed_events() {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    let receipts = writer.append_batch(&events(0..4)).unwrap();
+    let complete = writer.into_inner().unwrap();
+
+    // A sink that cannot be rolled back, cut off halfway into the third event
+    let limit = (receipts[2].offset + receipts[2].frame_len / 2) as usize;
+    let sink = LimitedSink {
+        bytes: Vec::new(),
+        limit,
+    };
+    let mut writer = JournalWriter::new(sink, WriteOptions::default()).unwrap();
+    match writer.append_batch(&events(0..4)) {
+        Err(JournalError::BatchInterrupted { committed, source }) => {
+            assert_eq!(committed, 2);
+            assert_eq!(source.kind(), io::ErrorKind::StorageFull);
+        }
+        other => panic!("expected an interrupted batch, got {:?}", other),
+    }
+    // The writer continues after the committed events
+    assert_eq!(writer.position(), receipts[2].offset);
+    assert_eq!(writer.into_inner().unwrap().bytes, complete[..limit]);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use northroot_journal::event::canonical_payload;
+use northroot_journal::frame::{FLAG_CANONICAL_PAYLOAD, VERSION_2};
+use northroot_journal::{
+    EventJson, FrameKind, JournalError, JournalHeader, JournalReader, JournalWriter, ReadMode,
+    WriteOptions,
+};
+use serde_json::json;
+
+fn make_test_event(n: usize) -> EventJson {
+    let mut event = common::make_test_event(n);
+    // serde_json and the canonical form disagree on numbers and escapes
+    event["amount"] = json!(1.0);
+    event["note"] = json!("caf\u{e9} </>");
+    event
+}
+
+fn canonical_options() -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_canonical_payloads(true)
+}
+
+#[test]
+fn test_payloads_are_canonical_bytes() {
+    let event = make_test_event(0);
+    let mut writer = JournalWriter::new(Vec::new(), canonical_options()).unwrap();
+    assert!(writer.header().has_canonical_payloads());
+    writer.append_event(&event).unwrap();
+    let bytes = writer.into_inner().unwrap();
+
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict).unwrap();
+    let payload = reader.read_frame().unwrap().unwrap().1;
+    assert_eq!(payload, canonical_payload(&event).unwrap());
+    // serde_json writes 1.0 where the canonical form has 1
+    assert_ne!(payload, serde_json::to_vec(&event).unwrap());
+}
+
+#[test]
+fn test_same_events_give_identical_journals() {
+    let mut by_event = JournalWriter::new(Vec::new(), canonical_options()).unwrap();
+    let mut by_raw = JournalWriter::new(Vec::new(), canonical_options()).unwrap();
+    for n in 0..3 {
+        let event = make_test_event(n);
+        by_event.append_event(&event).unwrap();
+        // A producer that parsed the event from its own serialization
+        let reparsed: EventJson =
+            serde_json::from_slice(&serde_json::to_vec_pretty(&event).unwrap()).unwrap();
+        by_raw
+            .append_raw(FrameKind::EventJson, &canonical_payload(&reparsed).unwrap())
+            .unwrap();
+    }
+    assert_eq!(by_event.into_inner().unwrap(), by_raw.into_inner().unwrap());
+}
+
+#[test]
+fn test_writer_rejects_non_canonical_raw_payload() {
+    let mut writer = JournalWriter::new(Vec::new(), canonical_options()).unwrap();
+    let payload = serde_json::to_vec_pretty(&make_test_event(0)).unwrap();
+    let result = writer.append_raw(FrameKind::EventJson, &payload);
+    assert!(matches!(
+        result,
+        Err(JournalError::NonCanonicalPayload { offset }) if offset == JournalHeader::HEADER_SIZE as u64
+    ));
+    assert_eq!(writer.position(), JournalHeader::HEADER_SIZE as u64);
+}
+
+#[test]
+fn test_strict_reader_rejects_non_canonical_payload() {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    writer.append_event(&make_test_event(0)).unwrap();
+    let mut bytes = writer.into_inner().unwrap();
+    // Mark the v1 journal as canonical; the frame layout is unchanged
+    bytes[4..6].copy_from_slice(&VERSION_2.to_le_bytes());
+    bytes[6..8].copy_from_slice(&FLAG_CANONICAL_PAYLOAD.to_le_bytes());
+
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict).unwrap();
+    assert!(matches!(
+        reader.read_event(),
+        Err(JournalError::NonCanonicalPayload { offset }) if offset == JournalHeader::HEADER_SIZE as u64
+    ));
+
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Permissive).unwrap();
+    assert_eq!(reader.read_event().unwrap().unwrap(), make_test_event(0));
+}
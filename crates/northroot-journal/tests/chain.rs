@@ -0,0 +1,182 @@
+// Note: Tests here use file I/O via TempDir. Skip under Miri; chain arithmetic
+// is covered by the unit tests in chain.rs.
+
+use northroot_journal::{
+    EventJson, FrameKind, JournalError, JournalReader, JournalWriter, ReadMode, WriteOptions,
+};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn make_test_event(id: &str) -> EventJson {
+    json!({
+        "event_id": { "alg": "sha-256", "b64": id },
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": "service:test",
+        "canonical_profile_id": "northroot-canonical-v1"
+    })
+}
+
+fn chained() -> WriteOptions {
+    WriteOptions::default()
+        .with_hash_chain(true)
+        .with_checksums(true)
+}
+
+fn write_events(path: &Path, ids: &[&str], finish: bool) {
+    let mut writer = JournalWriter::open(path, chained()).unwrap();
+    for id in ids {
+        writer.append_event(&make_test_event(id)).unwrap();
+    }
+    if finish {
+        writer.finish().unwrap();
+    }
+}
+
+/// Returns `(start, end)` byte ranges of every frame in the journal.
+fn frame_ranges(path: &Path) -> Vec<(u64, u64)> {
+    let mut reader = JournalReader::open(path, ReadMode::Strict).unwrap();
+    let mut ranges = Vec::new();
+    let mut start = reader.position();
+    while reader.read_frame().unwrap().is_some() {
+        ranges.push((start, reader.position()));
+        start = reader.position();
+    }
+    ranges
+}
+
+fn read_ids(path: &Path) -> Vec<String> {
+    let mut reader = JournalReader::open(path, ReadMode::Strict).unwrap();
+    let mut ids = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        ids.push(event["event_id"]["b64"].as_str().unwrap().to_string());
+    }
+    ids
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_chain_round_trip_with_trailer() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, &["e1", "e2", "e3"], true);
+
+    // Three events plus one trailer.
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let kinds: Vec<_> = std::iter::from_fn(|| reader.read_frame().unwrap())
+        .map(|(kind, _)| kind)
+        .collect();
+    assert_eq!(kinds.last(), Some(&FrameKind::ChainHead));
+    assert_eq!(kinds.len(), 4);
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let head = reader.verify_chain().unwrap();
+    assert_eq!(reader.chain_head(), Some(head));
+    assert_eq!(read_ids(&journal_path), ["e1", "e2", "e3"]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_writer_resumes_chain_from_trailer_and_by_scanning() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    write_events(&journal_path, &["e1"], true);
+    // Resume from the trailer, then drop without finishing (no trailer).
+    write_events(&journal_path, &["e2"], false);
+    // Resume by rescanning the whole file.
+    write_events(&journal_path, &["e3"], true);
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+    assert_eq!(read_ids(&journal_path), ["e1", "e2", "e3"]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_chain_head_matches_between_writer_and_reader() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let mut writer = JournalWriter::open(&journal_path, chained()).unwrap();
+    writer.append_event(&make_test_event("e1")).unwrap();
+    writer.append_event(&make_test_event("e2")).unwrap();
+    let head = writer.chain_head().unwrap();
+    drop(writer);
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert_eq!(reader.verify_chain().unwrap(), head);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_deleted_frame_breaks_chain() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, &["e1", "e2", "e3"], true);
+
+    let ranges = frame_ranges(&journal_path);
+    let mut bytes = fs::read(&journal_path).unwrap();
+    let (start, end) = ranges[1];
+    bytes.drain(start as usize..end as usize);
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    match reader.verify_chain() {
+        Err(JournalError::ChainBroken { offset, ordinal }) => {
+            assert_eq!(offset, start);
+            assert_eq!(ordinal, 1);
+        }
+        other => panic!("expected ChainBroken, got {:?}", other),
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_reordered_frames_break_chain() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, &["e1", "e2", "e3"], true);
+
+    let ranges = frame_ranges(&journal_path);
+    let bytes = fs::read(&journal_path).unwrap();
+    let slice = |(s, e): (u64, u64)| bytes[s as usize..e as usize].to_vec();
+    let mut swapped = bytes[..ranges[0].0 as usize].to_vec();
+    for idx in [1, 0, 2, 3] {
+        swapped.extend(slice(ranges[idx]));
+    }
+    fs::write(&journal_path, &swapped).unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert!(matches!(
+        reader.verify_chain(),
+        Err(JournalError::ChainBroken { ordinal: 0, .. })
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_unchained_journal_has_no_chain() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+    writer.append_event(&make_test_event("e1")).unwrap();
+    assert!(writer.chain_head().is_none());
+    writer.finish().unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert!(reader.chain_head().is_none());
+    assert!(reader.verify_chain().is_err());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_chain_head_frames_cannot_be_appended_directly() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let mut writer = JournalWriter::open(&journal_path, chained()).unwrap();
+    assert!(writer.append_raw(FrameKind::ChainHead, &[0u8; 40]).is_err());
+}
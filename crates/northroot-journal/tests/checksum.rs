@@ -0,0 +1,135 @@
+// Note: Tests here use file I/O via TempDir. Skip under Miri; checksum
+// arithmetic is covered by the unit tests in frame.rs.
+
+use northroot_journal::frame::{FLAG_FRAME_CRC32C, VERSION, VERSION_2};
+use northroot_journal::{
+    EventJson, FrameKind, JournalError, JournalReader, JournalWriter, ReadMode, WriteOptions,
+};
+use serde_json::json;
+use std::fs;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn make_test_event(id: &str) -> EventJson {
+    json!({
+        "event_id": { "alg": "sha-256", "b64": id },
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": "service:test",
+        "canonical_profile_id": "northroot-canonical-v1"
+    })
+}
+
+fn checksummed() -> WriteOptions {
+    WriteOptions::default().with_checksums(true)
+}
+
+fn flip_byte(path: &Path, offset: u64) {
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .unwrap();
+    let mut byte = [0u8; 1];
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    std::io::Read::read_exact(&mut file, &mut byte).unwrap();
+    file.seek(SeekFrom::Start(offset)).unwrap();
+    file.write_all(&[byte[0] ^ 0x01]).unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_checksummed_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let mut writer = JournalWriter::open(&journal_path, checksummed()).unwrap();
+    assert_eq!(writer.header().version, VERSION_2);
+    writer.append_event(&make_test_event("event1")).unwrap();
+    writer.append_event(&make_test_event("event2")).unwrap();
+    writer.finish().unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert_eq!(reader.header().flags, FLAG_FRAME_CRC32C);
+    assert_eq!(
+        reader.read_event().unwrap().unwrap()["event_id"]["b64"],
+        "event1"
+    );
+    assert_eq!(
+        reader.read_event().unwrap().unwrap()["event_id"]["b64"],
+        "event2"
+    );
+    assert!(reader.read_event().unwrap().is_none());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_payload_bit_flip_detected() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let mut writer = JournalWriter::open(&journal_path, checksummed()).unwrap();
+    writer.append_event(&make_test_event("event1")).unwrap();
+    writer.finish().unwrap();
+
+    // Header (16) + frame header (8) + crc (4) + a few bytes into the JSON payload.
+    flip_byte(&journal_path, 16 + 12 + 20);
+
+    for mode in [ReadMode::Strict, ReadMode::Permissive] {
+        let mut reader = JournalReader::open(&journal_path, mode).unwrap();
+        match reader.read_event() {
+            Err(JournalError::ChecksumMismatch { offset }) => assert_eq!(offset, 16),
+            other => panic!("expected ChecksumMismatch, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_unknown_kind_corruption_detected() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let mut writer = JournalWriter::open(&journal_path, checksummed()).unwrap();
+    writer
+        .append_raw(FrameKind::Unknown(0x7f), b"opaque extension data")
+        .unwrap();
+    writer.append_event(&make_test_event("event1")).unwrap();
+    writer.finish().unwrap();
+
+    flip_byte(&journal_path, 16 + 12 + 3);
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert!(matches!(
+        reader.read_event(),
+        Err(JournalError::ChecksumMismatch { offset: 16 })
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_append_keeps_existing_format() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    // Created as v1; later writers asking for checksums keep the v1 layout.
+    {
+        let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+        writer.append_event(&make_test_event("event1")).unwrap();
+        writer.finish().unwrap();
+    }
+    {
+        let mut writer = JournalWriter::open(&journal_path, checksummed()).unwrap();
+        assert_eq!(writer.header().version, VERSION);
+        writer.append_event(&make_test_event("event2")).unwrap();
+        writer.finish().unwrap();
+    }
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert_eq!(reader.header().version, VERSION);
+    assert!(reader.read_event().unwrap().is_some());
+    assert!(reader.read_event().unwrap().is_some());
+    assert!(reader.read_event().unwrap().is_none());
+}
@@ -0,0 +1,39 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use northroot_canonical::{Digest, DigestAlg};
+use northroot_journal::{EventJson, JournalWriter, WriteOptions};
+use serde_json::json;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// A well-formed event_id for event `n`, recomputable in assertions.
+pub fn event_id(n: usize) -> Digest {
+    Digest::new(DigestAlg::Sha256, format!("{:A<43}", format!("event{}", n))).unwrap()
+}
+
+/// A minimal event numbered `n`, identified by [`event_id`].
+pub fn make_test_event(n: usize) -> EventJson {
+    json!({
+        "event_id": event_id(n),
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": "service:test",
+        "canonical_profile_id": "northroot-canonical-v1",
+        "n": n
+    })
+}
+
+/// Writes the events numbered `range` to the journal at `path` and finishes
+/// the writer; returns the journal length.
+pub fn write_events(path: &Path, range: Range<usize>, options: WriteOptions) -> u64 {
+    let mut writer = JournalWriter::open(path, options).unwrap();
+    for n in range {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    writer.finish().unwrap();
+    fs::metadata(path).unwrap().len()
+}
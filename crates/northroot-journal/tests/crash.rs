@@ -0,0 +1,191 @@
+// Fault injection: simulates a crash at every byte of a journal's write history
+// and checks that what is left on disk is always detected, recoverable and
+// appendable. Uses file I/O via TempDir; skip under Miri.
+
+mod common;
+
+use common::make_test_event;
+use northroot_journal::{
+    Durability, JournalError, JournalReader, JournalRecovery, JournalWriter, ReadMode, WriteOptions,
+};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Writes a reference journal in two sessions and returns its bytes and the
+/// offsets at which each write ended. The writer issues one write per frame.
+fn reference(options: &WriteOptions) -> (Vec<u8>, Vec<u64>) {
+    let mut writer = JournalWriter::new(Cursor::new(Vec::new()), options.clone()).unwrap();
+    for n in 0..2 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let cursor = writer.into_inner().unwrap();
+    let mut writer = JournalWriter::resume(cursor, options.clone()).unwrap();
+    writer.append_event(&make_test_event(2)).unwrap();
+    let bytes = writer.into_inner().unwrap().into_inner();
+
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict).unwrap();
+    let mut boundaries = vec![reader.position()];
+    while reader.read_frame().unwrap().is_some() {
+        boundaries.push(reader.position());
+    }
+    (bytes, boundaries)
+}
+
+fn read_numbers(path: &Path, mode: ReadMode) -> Vec<u64> {
+    let mut reader = JournalReader::open(path, mode).unwrap();
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    numbers
+}
+
+/// Returns the offset just past each event frame of a complete journal.
+fn event_ends(bytes: &[u8]) -> Vec<u64> {
+    let mut reader = JournalReader::new(bytes, ReadMode::Strict).unwrap();
+    let mut ends = Vec::new();
+    while reader.read_event().unwrap().is_some() {
+        ends.push(reader.position());
+    }
+    ends
+}
+
+/// Checks the journal left by a crash after `len` bytes of `bytes` reached disk.
+fn check_crash_point(
+    dir: &Path,
+    options: &WriteOptions,
+    bytes: &[u8],
+    boundaries: &[u64],
+    len: u64,
+) {
+    let journal_path = dir.join("crash.nrj");
+    fs::write(&journal_path, &bytes[..len as usize]).unwrap();
+    let valid_len = *boundaries.iter().filter(|&&b| b <= len).max().unwrap();
+
+    // Readers see exactly the events that were completely written
+    let survivors = read_numbers(&journal_path, ReadMode::Permissive);
+    let complete = event_ends(bytes).iter().filter(|&&end| end <= len).count();
+    assert_eq!(
+        survivors,
+        (0..complete as u64).collect::<Vec<_>>(),
+        "crash at {}",
+        len
+    );
+
+    // Writers refuse a torn tail; recovery cuts back to the last boundary
+    let torn = len != valid_len;
+    let reopened = JournalWriter::open(&journal_path, options.clone());
+    assert_eq!(
+        matches!(reopened, Err(JournalError::TornTail { .. })),
+        torn,
+        "crash at {}",
+        len
+    );
+    drop(reopened);
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert_eq!(recovery.is_clean(), !torn, "crash at {}", len);
+    assert_eq!(recovery.valid_len(), valid_len, "crash at {}", len);
+    recovery.truncate().unwrap();
+
+    let mut writer = JournalWriter::open(&journal_path, options.clone()).unwrap();
+    writer
+        .append_event(&make_test_event(survivors.len()))
+        .unwrap();
+    writer.finish().unwrap();
+
+    let mut expected = survivors;
+    expected.push(expected.len() as u64);
+    assert_eq!(read_numbers(&journal_path, ReadMode::Strict), expected);
+    if options.hash_chain {
+        let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+        reader.verify_chain().unwrap();
+    }
+    fs::remove_file(&journal_path).unwrap();
+}
+
+fn crash_everywhere(options: WriteOptions) {
+    let temp_dir = TempDir::new().unwrap();
+    let (bytes, boundaries) = reference(&options);
+
+    // Every byte prefix at or past the header: each write boundary plus every
+    // point inside a torn write
+    for len in boundaries[0]..=bytes.len() as u64 {
+        check_crash_point(temp_dir.path(), &options, &bytes, &boundaries, len);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_crash_at_every_byte_v1() {
+    crash_everywhere(WriteOptions::default());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_crash_at_every_byte_v2() {
+    crash_everywhere(
+        WriteOptions::default()
+            .with_checksums(true)
+            .with_hash_chain(true)
+            .with_index(true),
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_crash_during_creation_leaves_no_partial_header() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    // A crash while writing the temporary file leaves it behind, but no journal
+    let stale = temp_dir.path().join(".test.nrj.1-0.tmp");
+    fs::write(&stale, b"NRJ1\x01").unwrap();
+    assert!(!journal_path.exists());
+
+    let options = WriteOptions::default().with_durability(Durability::Full);
+    let mut writer = JournalWriter::open(&journal_path, options).unwrap();
+    writer.append_event(&make_test_event(0)).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(read_numbers(&journal_path, ReadMode::Strict), vec![0]);
+
+    // Only the journal and the stale file remain
+    let mut names: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec![".test.nrj.1-0.tmp", "test.nrj"]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_durability_modes_write_identical_journals() {
+    let temp_dir = TempDir::new().unwrap();
+    let modes = [
+        Durability::None,
+        Durability::Data,
+        Durability::Full,
+        Durability::Group {
+            every_n: 2,
+            every_ms: 1,
+        },
+    ];
+    let journals: Vec<_> = modes
+        .iter()
+        .enumerate()
+        .map(|(i, &durability)| {
+            let journal_path = temp_dir.path().join(format!("{}.nrj", i));
+            let options = WriteOptions::default().with_durability(durability);
+            let mut writer = JournalWriter::open(&journal_path, options).unwrap();
+            for n in 0..5 {
+                writer.append_event(&make_test_event(n)).unwrap();
+            }
+            writer.finish().unwrap();
+            fs::read(&journal_path).unwrap()
+        })
+        .collect();
+    assert!(journals.windows(2).all(|pair| pair[0] == pair[1]));
+}
@@ -0,0 +1,158 @@
+use northroot_canonical::{compute_event_id, Canonicalizer, ProfileId};
+use northroot_journal::{
+    DuplicatePolicy, EventJson, FrameKind, JournalError, JournalReader, JournalWriter, ReadMode,
+    Rejection, ValidationPolicy, WriteOptions,
+};
+use serde_json::json;
+use tempfile::TempDir;
+
+fn make_event(n: u64) -> EventJson {
+    let mut event = json!({
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": format!("2024-01-01T00:00:{:02}Z", n),
+        "principal_id": "service:test",
+        "canonical_profile_id": "northroot-canonical-v1",
+        "n": n
+    });
+    let canonicalizer = Canonicalizer::new(ProfileId::parse("northroot-canonical-v1").unwrap());
+    let event_id = compute_event_id(&event, &canonicalizer).unwrap();
+    event["event_id"] = serde_json::to_value(event_id).unwrap();
+    event
+}
+
+fn options(duplicates: DuplicatePolicy) -> WriteOptions {
+    WriteOptions::default().with_duplicates(duplicates)
+}
+
+fn read_all(bytes: &[u8]) -> Vec<EventJson> {
+    let mut reader = JournalReader::new(bytes, ReadMode::Strict).unwrap();
+    std::iter::from_fn(|| reader.read_event().unwrap()).collect()
+}
+
+#[test]
+fn test_skip_returns_earlier_receipt() {
+    let mut writer = JournalWriter::new(Vec::new(), options(DuplicatePolicy::Skip)).unwrap();
+    let first = writer.append_event(&make_event(0)).unwrap();
+    writer.append_event(&make_event(1)).unwrap();
+    let position = writer.position();
+
+    let repeat = writer.append_event(&make_event(0)).unwrap();
+    assert!(repeat.duplicate && !first.duplicate);
+    assert_eq!(
+        (repeat.ordinal, repeat.offset),
+        (first.ordinal, first.offset)
+    );
+    assert_eq!((repeat.frame_len, repeat.chain_head), (0, None));
+    assert_eq!(repeat.event_id, first.event_id);
+    assert_eq!(writer.position(), position);
+
+    // Events without an event_id are never duplicates
+    let unidentified = json!({"event_type": "test"});
+    writer.append_event(&unidentified).unwrap();
+    assert!(!writer.append_event(&unidentified).unwrap().duplicate);
+
+    let bytes = writer.into_inner().unwrap();
+    assert_eq!(read_all(&bytes).len(), 4);
+}
+
+#[test]
+fn test_duplicates_within_a_batch() {
+    let options = options(DuplicatePolicy::Skip).with_buffer(8);
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    let buffered = writer.append_event(&make_event(0)).unwrap();
+    let events = vec![make_event(1), make_event(0), make_event(2), make_event(1)];
+    let receipts = writer.append_batch(&events).unwrap();
+
+    let ordinals: Vec<_> = receipts.iter().map(|r| (r.ordinal, r.duplicate)).collect();
+    assert_eq!(ordinals, vec![(1, false), (0, true), (2, false), (1, true)]);
+    assert_eq!(receipts[1].offset, buffered.offset);
+    assert_eq!(receipts[3].offset, receipts[0].offset);
+    let bytes = writer.into_inner().unwrap();
+    assert_eq!(read_all(&bytes).len(), 3);
+}
+
+#[test]
+fn test_reject_fails_repeats() {
+    let options = options(DuplicatePolicy::Reject).with_validation(ValidationPolicy::strict());
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    writer.append_event(&make_event(0)).unwrap();
+    writer.append_event(&make_event(1)).unwrap();
+
+    // A retried older event is a duplicate, not out of order
+    let event = make_event(0);
+    let result = writer.append_event(&event);
+    match result {
+        Err(JournalError::Rejected(Rejection::DuplicateEventId { event_id, ordinal })) => {
+            assert_eq!(serde_json::to_value(event_id).unwrap(), event["event_id"]);
+            assert_eq!(ordinal, 0);
+        }
+        other => panic!("expected a duplicate rejection, got {:?}", other),
+    }
+
+    // A batch with a repeat is rejected as a whole
+    let result = writer.append_batch(&[make_event(2), make_event(2)]);
+    assert!(matches!(
+        result,
+        Err(JournalError::Rejected(Rejection::DuplicateEventId {
+            ordinal: 2,
+            ..
+        }))
+    ));
+    writer.append_event(&make_event(2)).unwrap();
+
+    let bytes = writer.into_inner().unwrap();
+    assert_eq!(read_all(&bytes).len(), 3);
+}
+
+#[test]
+fn test_append_raw_skips_repeats() {
+    let mut writer = JournalWriter::new(Vec::new(), options(DuplicatePolicy::Skip)).unwrap();
+    let payload = serde_json::to_vec(&make_event(0)).unwrap();
+    writer.append_raw(FrameKind::EventJson, &payload).unwrap();
+    let position = writer.position();
+    writer.append_raw(FrameKind::EventJson, &payload).unwrap();
+    assert_eq!(writer.position(), position);
+    assert!(writer.append_event(&make_event(0)).unwrap().duplicate);
+
+    let options = options(DuplicatePolicy::Reject);
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    writer.append_raw(FrameKind::EventJson, &payload).unwrap();
+    assert!(matches!(
+        writer.append_raw(FrameKind::EventJson, &payload),
+        Err(JournalError::Rejected(Rejection::DuplicateEventId { .. }))
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_reopened_writer_finds_existing_events() {
+    let temp_dir = TempDir::new().unwrap();
+    for index in [false, true] {
+        let path = temp_dir.path().join(format!("events-{}.nrj", index));
+        let options = WriteOptions::default().with_index(index);
+        let mut writer = JournalWriter::open(&path, options.clone()).unwrap();
+        for n in 0..3 {
+            writer.append_event(&make_event(n)).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // Events after the latest index segment are found by scanning
+        let mut writer = JournalWriter::open(&path, options.clone()).unwrap();
+        let appended = writer.append_event(&make_event(3)).unwrap();
+        drop(writer);
+
+        let options = options.with_duplicates(DuplicatePolicy::Skip);
+        let mut writer = JournalWriter::open(&path, options).unwrap();
+        let receipt = writer.append_event(&make_event(1)).unwrap();
+        assert!(receipt.duplicate, "index: {}", index);
+        assert_eq!(receipt.ordinal, 1);
+        let receipt = writer.append_event(&make_event(3)).unwrap();
+        assert_eq!((receipt.ordinal, receipt.offset), (3, appended.offset));
+        assert!(!writer.append_event(&make_event(4)).unwrap().duplicate);
+        writer.finish().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(read_all(&bytes).len(), 5);
+    }
+}
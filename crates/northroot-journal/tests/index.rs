@@ -0,0 +1,197 @@
+// Note: Tests here use file I/O via TempDir. Skip under Miri; segment encoding
+// is covered by the unit tests in index.rs.
+
+mod common;
+
+use common::{event_id, make_test_event, write_events};
+use northroot_journal::index::IndexSegment;
+use northroot_journal::{
+    DuplicatePolicy, FrameKind, JournalReader, JournalWriter, ReadMode, ValidationPolicy,
+    WriteOptions,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn indexed() -> WriteOptions {
+    WriteOptions::default().with_index(true)
+}
+
+fn assert_seeks(path: &Path, count: usize) {
+    let mut reader = JournalReader::open(path, ReadMode::Strict).unwrap();
+    for n in (0..count).rev() {
+        assert!(reader.seek_ordinal(n as u64).unwrap());
+        assert_eq!(reader.read_event().unwrap().unwrap()["n"], n);
+        assert!(reader.seek_event(&event_id(n)).unwrap());
+        assert_eq!(reader.read_event().unwrap().unwrap()["n"], n);
+    }
+    let position = reader.position();
+    assert!(!reader.seek_ordinal(count as u64).unwrap());
+    assert!(!reader.seek_event(&event_id(count)).unwrap());
+    assert_eq!(reader.position(), position);
+}
+
+/// Returns `(offset, payload)` for every index frame.
+fn index_frames(path: &Path) -> Vec<(u64, Vec<u8>)> {
+    let mut reader = JournalReader::open(path, ReadMode::Strict).unwrap();
+    let mut frames = Vec::new();
+    let mut offset = reader.position();
+    while let Some((kind, payload)) = reader.read_frame().unwrap() {
+        if kind == FrameKind::Index {
+            frames.push((offset, payload));
+        }
+        offset = reader.position();
+    }
+    frames
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_seek_with_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..5, indexed());
+
+    assert_eq!(index_frames(&journal_path).len(), 1);
+    assert_seeks(&journal_path, 5);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_seek_falls_back_to_scanning() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..5, WriteOptions::default());
+
+    assert!(index_frames(&journal_path).is_empty());
+    assert_seeks(&journal_path, 5);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_index_segments_are_incremental() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..3, indexed());
+    write_events(&journal_path, 3..5, indexed());
+    // Unindexed events after the latest segment are found by scanning.
+    write_events(&journal_path, 5..7, WriteOptions::default());
+
+    let frames = index_frames(&journal_path);
+    assert_eq!(frames.len(), 2);
+    let first = IndexSegment::from_bytes(&frames[0].1, frames[0].0).unwrap();
+    let second = IndexSegment::from_bytes(&frames[1].1, frames[1].0).unwrap();
+    assert_eq!((first.first_ordinal, first.entries.len()), (0, 3));
+    assert_eq!((second.first_ordinal, second.entries.len()), (3, 2));
+    assert_eq!(second.prev_offset, Some(frames[0].0));
+
+    assert_seeks(&journal_path, 7);
+
+    // Reopening with indexing picks up the unindexed tail.
+    write_events(&journal_path, 7..8, indexed());
+    let frames = index_frames(&journal_path);
+    let third = IndexSegment::from_bytes(&frames[2].1, frames[2].0).unwrap();
+    assert_eq!((third.first_ordinal, third.entries.len()), (5, 3));
+    assert_seeks(&journal_path, 8);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_resume_starts_after_tail_segment() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..3, indexed());
+
+    // Damage event 0's payload; a writer resuming after the segment never reads it
+    let (offset, payload) = index_frames(&journal_path).remove(0);
+    let segment = IndexSegment::from_bytes(&payload, offset).unwrap();
+    let mut bytes = fs::read(&journal_path).unwrap();
+    bytes[segment.entries[0].offset as usize + 8] = b'X';
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let options = indexed()
+        .with_duplicates(DuplicatePolicy::Skip)
+        .with_validation(ValidationPolicy {
+            monotonic_time: true,
+            ..Default::default()
+        });
+    let mut writer = JournalWriter::open(&journal_path, options).unwrap();
+    let receipt = writer.append_event(&make_test_event(1)).unwrap();
+    assert!(receipt.duplicate);
+    assert_eq!(receipt.ordinal, 1);
+    assert_eq!(writer.append_event(&make_test_event(3)).unwrap().ordinal, 3);
+    writer.finish().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_index_in_chained_journal() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+        .with_index(true);
+    write_events(&journal_path, 0..4, options.clone());
+    write_events(&journal_path, 4..6, options);
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+    assert_seeks(&journal_path, 6);
+
+    // Seeking skips frames, so the chain is no longer tracked.
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert!(reader.seek_ordinal(2).unwrap());
+    assert!(reader.chain_head().is_none());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_index_frames_are_skipped_by_event_readers() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..3, indexed());
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    // No header flag is needed, so v1 readers see an unknown kind and skip it.
+    assert_eq!(reader.header().version, northroot_journal::frame::VERSION);
+    let mut count = 0;
+    while reader.read_event().unwrap().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_stale_index_entry_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..3, indexed());
+
+    // Point entry 0 at event 1's frame.
+    let (offset, payload) = index_frames(&journal_path).remove(0);
+    let segment = IndexSegment::from_bytes(&payload, offset).unwrap();
+    let payload_start = offset as usize + 8;
+    let mut bytes = fs::read(&journal_path).unwrap();
+    bytes[payload_start..payload_start + 8]
+        .copy_from_slice(&segment.entries[1].offset.to_le_bytes());
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    assert!(reader.seek_event(&event_id(0)).is_err());
+    assert!(reader.seek_ordinal(2).unwrap());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_index_frames_cannot_be_appended_directly() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let mut writer = JournalWriter::open(&journal_path, indexed()).unwrap();
+    assert!(writer.append_raw(FrameKind::Index, b"NRIX").is_err());
+    drop(writer);
+
+    let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+    assert!(writer.write_index().is_err());
+}
@@ -2,7 +2,9 @@
 // Miri's filesystem emulation is slow and doesn't provide additional UB detection
 // beyond normal test runs. Skip under Miri; core frame logic is tested in frame.rs.
 
-use northroot_journal::{EventJson, JournalReader, JournalWriter, ReadMode, WriteOptions};
+use northroot_journal::{
+    Durability, EventJson, JournalReader, JournalWriter, ReadMode, WriteOptions,
+};
 use serde_json::json;
 use std::fs;
 use tempfile::TempDir;
@@ -95,10 +97,7 @@ fn test_sync_option() {
     let temp_dir = TempDir::new().unwrap();
     let journal_path = temp_dir.path().join("test.nrj");
 
-    let options = WriteOptions {
-        sync: true,
-        ..Default::default()
-    };
+    let options = WriteOptions::default().with_durability(Durability::Full);
 
     let mut writer = JournalWriter::open(&journal_path, options).unwrap();
     writer.append_event(&make_test_event("event1")).unwrap();
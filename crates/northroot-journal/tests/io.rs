@@ -0,0 +1,196 @@
+// Journals over in-memory and non-seekable streams. Only the last test touches
+// the file system.
+
+mod common;
+
+use common::{event_id, make_test_event};
+use northroot_journal::{
+    FrameKind, JournalError, JournalReader, JournalWriter, ReadMode, WriteOptions,
+};
+use std::io::{Cursor, Read};
+use tempfile::TempDir;
+
+fn all_features() -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+        .with_index(true)
+}
+
+fn numbers<R: Read>(reader: &mut JournalReader<R>) -> Vec<u64> {
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    numbers
+}
+
+/// Hands out at most one byte per call, like a slow pipe.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((byte, rest)), Some(slot)) => {
+                *slot = *byte;
+                self.0 = rest;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn test_vec_round_trip() {
+    let mut writer = JournalWriter::new(Vec::new(), all_features()).unwrap();
+    for n in 0..5 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let head = writer.chain_head().unwrap();
+    let bytes = writer.into_inner().unwrap();
+
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict).unwrap();
+    assert_eq!(numbers(&mut reader), vec![0, 1, 2, 3, 4]);
+    assert_eq!(reader.position(), bytes.len() as u64);
+
+    // The trailer extends the chain past the head seen before finishing
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict).unwrap();
+    assert_ne!(reader.verify_chain().unwrap(), head);
+}
+
+#[test]
+fn test_cursor_resume_and_seek() {
+    let mut writer = JournalWriter::new(Cursor::new(Vec::new()), all_features()).unwrap();
+    for n in 0..3 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let cursor = writer.into_inner().unwrap();
+
+    let mut writer = JournalWriter::resume(cursor, all_features()).unwrap();
+    for n in 3..6 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let mut cursor = writer.into_inner().unwrap();
+    cursor.set_position(0);
+
+    let mut reader = JournalReader::new(cursor, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+
+    assert!(reader.seek_event(&event_id(4)).unwrap());
+    assert_eq!(reader.read_event().unwrap().unwrap()["n"], 4);
+    assert!(reader.seek_ordinal(1).unwrap());
+    assert_eq!(numbers(&mut reader), vec![1, 2, 3, 4, 5]);
+    assert!(!reader.seek_ordinal(6).unwrap());
+}
+
+#[test]
+fn test_resume_empty_stream_writes_header() {
+    let writer = JournalWriter::resume(Cursor::new(Vec::new()), WriteOptions::default()).unwrap();
+    let bytes = writer.into_inner().unwrap().into_inner();
+    assert_eq!(&bytes[..4], b"NRJ1");
+
+    let result = JournalWriter::resume(Cursor::new(vec![0u8; 4]), WriteOptions::default());
+    assert!(matches!(result, Err(JournalError::FileNotEmpty)));
+}
+
+#[test]
+fn test_non_seekable_source_truncation() {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    for n in 0..3 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let mut bytes = writer.into_inner().unwrap();
+    bytes.truncate(bytes.len() - 5);
+
+    let mut reader = JournalReader::new(Trickle(&bytes), ReadMode::Permissive).unwrap();
+    assert_eq!(numbers(&mut reader), vec![0, 1]);
+
+    let mut reader = JournalReader::new(Trickle(&bytes), ReadMode::Strict).unwrap();
+    reader.read_event().unwrap();
+    reader.read_event().unwrap();
+    assert!(matches!(
+        reader.read_event(),
+        Err(JournalError::TruncatedFrame { .. })
+    ));
+}
+
+#[test]
+fn test_frame_info_locations() {
+    let mut writer = JournalWriter::new(Cursor::new(Vec::new()), all_features()).unwrap();
+    let mut offsets = Vec::new();
+    for n in 0..3 {
+        offsets.push(writer.position());
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let mut cursor = writer.into_inner().unwrap();
+    cursor.set_position(0);
+
+    let mut reader = JournalReader::new(cursor, ReadMode::Strict).unwrap();
+    let frames: Vec<_> = reader.frames().map(Result::unwrap).collect();
+    let kinds: Vec<_> = frames.iter().map(|(info, _)| info.kind()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            FrameKind::EventJson,
+            FrameKind::EventJson,
+            FrameKind::EventJson,
+            FrameKind::Index,
+            FrameKind::ChainHead
+        ]
+    );
+    for (n, (info, payload)) in frames.iter().enumerate() {
+        assert_eq!(info.ordinal, Some(n as u64));
+        assert_eq!(info.payload_len as usize, payload.len());
+        assert_eq!(info.header.len, info.payload_len);
+    }
+    let event_offsets: Vec<_> = frames[..3].iter().map(|(info, _)| info.offset).collect();
+    assert_eq!(event_offsets, offsets);
+
+    // After a seek, offsets stay exact but ordinals are unknown
+    assert!(reader.seek_ordinal(1).unwrap());
+    let events: Vec<_> = reader.events().map(Result::unwrap).collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].0.offset, offsets[1]);
+    assert_eq!(events[0].0.ordinal, None);
+    assert_eq!(events[1].1["n"], 2);
+}
+
+#[test]
+fn test_events_iterator_stops_after_error() {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    for n in 0..3 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    let mut bytes = writer.into_inner().unwrap();
+    bytes.truncate(bytes.len() - 5);
+
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Strict).unwrap();
+    let results: Vec<_> = reader.events().collect();
+    assert_eq!(results.len(), 3);
+    assert!(results[..2].iter().all(Result::is_ok));
+    assert!(matches!(
+        results[2],
+        Err(JournalError::TruncatedFrame { .. })
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_file_and_memory_writers_agree() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let mut file_writer = JournalWriter::open(&journal_path, all_features()).unwrap();
+    let mut memory_writer = JournalWriter::new(Vec::new(), all_features()).unwrap();
+    for n in 0..4 {
+        file_writer.append_event(&make_test_event(n)).unwrap();
+        memory_writer.append_event(&make_test_event(n)).unwrap();
+    }
+    file_writer.finish().unwrap();
+
+    assert_eq!(
+        std::fs::read(&journal_path).unwrap(),
+        memory_writer.into_inner().unwrap()
+    );
+}
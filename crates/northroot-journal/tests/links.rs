@@ -0,0 +1,186 @@
+use northroot_canonical::{compute_event_id, Canonicalizer, Digest, ProfileId};
+use northroot_journal::{
+    verify_links, verify_links_reader, AppendReceipt, EventJson, FrameKind, JournalReader,
+    JournalWriter, LinkIssueKind, LinkOptions, LinkReport, LinkScope, ReadMode, WriteOptions,
+};
+use serde_json::json;
+use tempfile::TempDir;
+
+/// An event of `principal` numbered `n`, linked to `prev` if given.
+fn make_event(principal: &str, n: u64, prev: Option<&EventJson>) -> EventJson {
+    let mut event = json!({
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": principal,
+        "canonical_profile_id": "northroot-canonical-v1",
+        "subject": {"id": n % 2},
+        "n": n
+    });
+    if let Some(prev) = prev {
+        event["prev_event_id"] = prev["event_id"].clone();
+    }
+    let canonicalizer = Canonicalizer::new(ProfileId::parse("northroot-canonical-v1").unwrap());
+    let event_id = compute_event_id(&event, &canonicalizer).unwrap();
+    event["event_id"] = serde_json::to_value(event_id).unwrap();
+    event
+}
+
+fn write(events: &[&EventJson]) -> (Vec<u8>, Vec<AppendReceipt>) {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    let receipts = events
+        .iter()
+        .map(|event| writer.append_event(event).unwrap())
+        .collect();
+    (writer.into_inner().unwrap(), receipts)
+}
+
+fn check(bytes: &[u8], scope: LinkScope) -> LinkReport {
+    let mut reader = JournalReader::new(bytes, ReadMode::Strict).unwrap();
+    let options = LinkOptions {
+        scope,
+        ..Default::default()
+    };
+    verify_links_reader(&mut reader, &options).unwrap()
+}
+
+fn digest(event: &EventJson) -> Digest {
+    serde_json::from_value(event["event_id"].clone()).unwrap()
+}
+
+fn issues(report: &LinkReport) -> Vec<(LinkIssueKind, u64)> {
+    report
+        .issues
+        .iter()
+        .map(|issue| (issue.kind, issue.offset))
+        .collect()
+}
+
+#[test]
+fn test_unbroken_chain_passes() {
+    let a = make_event("service:a", 0, None);
+    let b = make_event("service:a", 1, Some(&a));
+    let c = make_event("service:a", 2, Some(&b));
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    writer.append_event(&a).unwrap();
+    writer.append_event(&b).unwrap();
+    // Frames of other kinds are not events
+    writer.append_raw(FrameKind::Unknown(0x7F), b"ext").unwrap();
+    writer.append_event(&c).unwrap();
+    let bytes = writer.into_inner().unwrap();
+
+    let report = check(&bytes, LinkScope::Journal);
+    assert!(report.passed(), "{:?}", report.issues);
+    assert_eq!(report.events, 3);
+    assert_eq!(report.chains.len(), 1);
+    let chain = &report.chains[0];
+    assert_eq!(chain.key, None);
+    assert_eq!(chain.events, 3);
+    assert_eq!(chain.genesis, Some(digest(&a)));
+    assert_eq!(chain.head, digest(&c));
+}
+
+#[test]
+fn test_reports_forks_gaps_dangling_and_genesis() {
+    let a = make_event("service:a", 0, None);
+    let b = make_event("service:a", 1, Some(&a));
+    let fork = make_event("service:a", 2, Some(&a));
+    let c = make_event("service:a", 3, Some(&fork));
+    let missing = make_event("service:a", 4, Some(&c));
+    let dangling = make_event("service:a", 5, Some(&missing));
+    let genesis = make_event("service:a", 6, None);
+    let gap = make_event("service:a", 7, Some(&dangling));
+    let mut bad_prev = make_event("service:a", 8, Some(&genesis));
+    bad_prev["prev_event_id"] = json!("not a digest");
+    let (bytes, receipts) = write(&[&a, &b, &fork, &c, &dangling, &genesis, &gap, &bad_prev, &b]);
+
+    let report = check(&bytes, LinkScope::Journal);
+    assert!(!report.passed());
+    assert_eq!(
+        issues(&report),
+        vec![
+            (LinkIssueKind::Fork, receipts[2].offset),
+            (LinkIssueKind::Dangling, receipts[4].offset),
+            (LinkIssueKind::MultipleGenesis, receipts[5].offset),
+            (LinkIssueKind::Gap, receipts[6].offset),
+            (LinkIssueKind::Dangling, receipts[7].offset),
+            // A repeated event forks from its predecessor again
+            (LinkIssueKind::Fork, receipts[8].offset),
+        ]
+    );
+    assert_eq!(report.issues[1].prev_event_id, Some(digest(&missing)));
+    assert_eq!(report.issues[4].prev_event_id, None);
+    assert_eq!(report.chains[0].genesis, Some(digest(&a)));
+    assert_eq!(report.chains[0].events, 9);
+}
+
+#[test]
+fn test_chains_per_principal_and_field() {
+    let a0 = make_event("service:a", 0, None);
+    let b0 = make_event("service:b", 1, None);
+    let a1 = make_event("service:a", 2, Some(&a0));
+    let b1 = make_event("service:b", 3, Some(&b0));
+    let (bytes, receipts) = write(&[&a0, &b0, &a1, &b1]);
+
+    // Interleaved chains look broken as one journal-wide chain
+    let report = check(&bytes, LinkScope::Journal);
+    assert_eq!(
+        issues(&report),
+        vec![
+            (LinkIssueKind::MultipleGenesis, receipts[1].offset),
+            (LinkIssueKind::Gap, receipts[2].offset),
+            (LinkIssueKind::Gap, receipts[3].offset),
+        ]
+    );
+
+    let report = check(&bytes, LinkScope::Principal);
+    assert!(report.passed(), "{:?}", report.issues);
+    let chains: Vec<_> = report
+        .chains
+        .iter()
+        .map(|chain| (chain.key.as_deref(), chain.events, chain.head.clone()))
+        .collect();
+    assert_eq!(
+        chains,
+        vec![
+            (Some("service:a"), 2, digest(&a1)),
+            (Some("service:b"), 2, digest(&b1)),
+        ]
+    );
+
+    // `subject.id` is `n % 2`, so every event of a chain has the same parity
+    let report = check(&bytes, LinkScope::Field("/subject/id".to_string()));
+    assert!(report.passed(), "{:?}", report.issues);
+    assert_eq!(report.chains[0].key.as_deref(), Some("0"));
+
+    let report = check(&bytes, LinkScope::Field("tenant".to_string()));
+    assert_eq!((report.unkeyed, report.chains.len()), (4, 0));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_verify_links_reads_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    let mut writer = JournalWriter::open(&path, WriteOptions::default()).unwrap();
+    let mut prev = None;
+    for n in 0..4 {
+        let event = make_event("service:a", n, prev.as_ref());
+        writer.append_event(&event).unwrap();
+        prev = Some(event);
+    }
+    writer.append_event(&json!({"event_type": "test"})).unwrap();
+    writer.finish().unwrap();
+
+    let options = LinkOptions {
+        max_events: Some(3),
+        ..Default::default()
+    };
+    let report = verify_links(&path, &options).unwrap();
+    assert!(report.passed());
+    assert_eq!(report.chains[0].events, 3);
+
+    let report = verify_links(&path, &LinkOptions::default()).unwrap();
+    assert_eq!((report.events, report.unidentified), (5, 1));
+    assert_eq!(report.chains[0].head, digest(prev.as_ref().unwrap()));
+}
@@ -0,0 +1,155 @@
+// Note: All tests in this file use file I/O via TempDir, and one re-runs this
+// test binary as child processes. Skip under Miri.
+
+use northroot_journal::{
+    EventJson, JournalError, JournalReader, JournalWriter, LockPolicy, ReadMode, WriteOptions,
+};
+use serde_json::json;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Set in child processes spawned by `test_concurrent_writers_never_interleave`.
+const CHILD_ENV: &str = "NORTHROOT_LOCK_TEST_JOURNAL";
+const WRITERS: u64 = 4;
+const EVENTS_PER_WRITER: u64 = 50;
+
+fn make_test_event(writer: u64, n: u64) -> EventJson {
+    json!({
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "principal_id": format!("service:writer{}", writer),
+        "writer": writer,
+        "n": n
+    })
+}
+
+fn options(lock: LockPolicy) -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+        .with_lock(lock)
+}
+
+fn write_as_child(path: &Path, writer_id: u64) {
+    let mut writer = JournalWriter::open(path, options(LockPolicy::Wait)).unwrap();
+    for n in 0..EVENTS_PER_WRITER {
+        writer.append_event(&make_test_event(writer_id, n)).unwrap();
+        if n % 10 == 0 {
+            thread::yield_now();
+        }
+    }
+    writer.finish().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_concurrent_writers_never_interleave() {
+    if let Ok(path) = std::env::var(CHILD_ENV) {
+        let writer_id = std::env::var("NORTHROOT_LOCK_TEST_WRITER").unwrap();
+        write_as_child(Path::new(&path), writer_id.parse().unwrap());
+        return;
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let children: Vec<_> = (0..WRITERS)
+        .map(|writer_id| {
+            Command::new(std::env::current_exe().unwrap())
+                .args(["test_concurrent_writers_never_interleave", "--exact"])
+                .env(CHILD_ENV, &journal_path)
+                .env("NORTHROOT_LOCK_TEST_WRITER", writer_id.to_string())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let events: Vec<_> = reader.events().map(|item| item.unwrap().1).collect();
+    assert_eq!(events.len() as u64, WRITERS * EVENTS_PER_WRITER);
+
+    // Each writer's events form one contiguous, ordered run
+    for run in events.chunks(EVENTS_PER_WRITER as usize) {
+        let writer = &run[0]["writer"];
+        for (n, event) in run.iter().enumerate() {
+            assert_eq!(&event["writer"], writer);
+            assert_eq!(event["n"], n as u64);
+        }
+    }
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_second_writer_fails_while_locked() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let writer = JournalWriter::open(&journal_path, options(LockPolicy::Fail)).unwrap();
+    let result = JournalWriter::open(&journal_path, options(LockPolicy::Fail));
+    assert!(matches!(result, Err(JournalError::Locked)));
+    let result = JournalReader::open_locked(&journal_path, ReadMode::Strict, LockPolicy::Fail);
+    assert!(matches!(result, Err(JournalError::Locked)));
+
+    // Unlocked readers are not affected
+    JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+
+    drop(writer);
+    JournalWriter::open(&journal_path, options(LockPolicy::Fail)).unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_shared_readers_keep_writers_out() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let mut writer = JournalWriter::open(&journal_path, options(LockPolicy::Fail)).unwrap();
+    writer.append_event(&make_test_event(0, 0)).unwrap();
+    writer.finish().unwrap();
+
+    let first = JournalReader::open_locked(&journal_path, ReadMode::Strict, LockPolicy::Fail);
+    let second = JournalReader::open_locked(&journal_path, ReadMode::Strict, LockPolicy::Fail);
+    assert!(first.is_ok() && second.is_ok());
+
+    let result = JournalWriter::open(&journal_path, options(LockPolicy::Fail));
+    assert!(matches!(result, Err(JournalError::Locked)));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_waiting_writer_resumes_after_release() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+
+    let mut writer = JournalWriter::open(&journal_path, options(LockPolicy::Fail)).unwrap();
+    writer.append_event(&make_test_event(0, 0)).unwrap();
+
+    let path = journal_path.clone();
+    let waiter = thread::spawn(move || {
+        let mut writer = JournalWriter::open(&path, options(LockPolicy::Wait)).unwrap();
+        writer.append_event(&make_test_event(1, 0)).unwrap();
+        writer.finish().unwrap();
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    writer.append_event(&make_test_event(0, 1)).unwrap();
+    writer.finish().unwrap();
+    waiter.join().unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let writers: Vec<_> = reader
+        .events()
+        .map(|item| item.unwrap().1["writer"].as_u64().unwrap())
+        .collect();
+    assert_eq!(writers, vec![0, 0, 1]);
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+}
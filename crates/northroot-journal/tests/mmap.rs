@@ -0,0 +1,266 @@
+// Note: Tests here map files created in a TempDir. Skip under Miri.
+
+mod common;
+
+use common::make_test_event;
+use northroot_journal::frame::{FLAG_CANONICAL_PAYLOAD, VERSION_2};
+use northroot_journal::{
+    FrameKind, JournalError, JournalReader, JournalWriter, MmapJournalReader, ReadMode,
+    WriteOptions,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::fs;
+use std::io;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write_journal(path: &Path, options: WriteOptions, count: usize) {
+    let mut writer = JournalWriter::open(path, options).unwrap();
+    for n in 0..count {
+        writer.append_event(&make_test_event(n)).unwrap();
+        if n == 2 {
+            writer
+                .append_raw(FrameKind::Unknown(0x7f), b"opaque")
+                .unwrap();
+        }
+    }
+    writer.finish().unwrap();
+}
+
+fn all_features() -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+}
+
+/// Payloads and kinds as read by the buffered reader.
+fn buffered_frames(path: &Path, mode: ReadMode) -> Result<Vec<(FrameKind, Vec<u8>)>, JournalError> {
+    let mut reader = JournalReader::open(path, mode)?;
+    let mut frames = Vec::new();
+    while let Some(frame) = reader.read_frame()? {
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+fn mmap_frames(path: &Path, mode: ReadMode) -> Result<Vec<(FrameKind, Vec<u8>)>, JournalError> {
+    let reader = MmapJournalReader::open(path, mode)?;
+    reader
+        .frames()
+        .map(|frame| frame.map(|f| (f.kind(), f.payload().to_vec())))
+        .collect()
+}
+
+fn par_frames(
+    path: &Path,
+    mode: ReadMode,
+    threads: usize,
+) -> Result<Vec<(FrameKind, Vec<u8>)>, JournalError> {
+    let reader = MmapJournalReader::open(path, mode)?;
+    reader.par_map_frames(threads, |f| (f.kind(), f.payload().to_vec()))
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_matches_buffered_reader() {
+    let temp_dir = TempDir::new().unwrap();
+    for (name, options) in [
+        ("v1.nrj", WriteOptions::default()),
+        ("v2.nrj", all_features()),
+    ] {
+        let journal_path = temp_dir.path().join(name);
+        write_journal(&journal_path, options, 10);
+
+        let expected = buffered_frames(&journal_path, ReadMode::Strict).unwrap();
+        assert_eq!(
+            mmap_frames(&journal_path, ReadMode::Strict).unwrap(),
+            expected
+        );
+        for threads in [0, 1, 3, 64] {
+            assert_eq!(
+                par_frames(&journal_path, ReadMode::Strict, threads).unwrap(),
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_borrowed_views() {
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        event_type: &'a str,
+        n: u64,
+    }
+
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_journal(&journal_path, WriteOptions::default(), 4);
+
+    let reader = MmapJournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let raw: Vec<_> = reader.raw_events().collect::<Result<_, _>>().unwrap();
+    assert_eq!(raw.len(), 4);
+    assert!(raw[0].get().contains("\"n\":0"));
+
+    let frame = reader.frames().next().unwrap().unwrap();
+    assert_eq!(frame.offset(), 16);
+    assert_eq!(frame.ordinal(), 0);
+    let borrowed: Borrowed<'_> = frame.parse().unwrap();
+    assert_eq!((borrowed.event_type, borrowed.n), ("test", 0));
+    assert_eq!(frame.event().unwrap()["n"], 0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_chain_head_matches() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_journal(&journal_path, all_features(), 5);
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let head = reader.verify_chain().unwrap();
+
+    let mmap = MmapJournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let mut frames = mmap.frames();
+    for frame in frames.by_ref() {
+        frame.unwrap();
+    }
+    assert_eq!(frames.chain_head(), Some(head));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_truncation_follows_read_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_journal(&journal_path, all_features(), 5);
+    let bytes = fs::read(&journal_path).unwrap();
+
+    // Cut inside a payload, then inside a frame header.
+    for cut in [bytes.len() - 5, bytes.len() - 50] {
+        fs::write(&journal_path, &bytes[..cut]).unwrap();
+
+        let expected = buffered_frames(&journal_path, ReadMode::Strict).unwrap_err();
+        for result in [
+            mmap_frames(&journal_path, ReadMode::Strict),
+            par_frames(&journal_path, ReadMode::Strict, 4),
+        ] {
+            assert_eq!(result.unwrap_err().to_string(), expected.to_string());
+        }
+
+        let expected = buffered_frames(&journal_path, ReadMode::Permissive).unwrap();
+        assert_eq!(
+            mmap_frames(&journal_path, ReadMode::Permissive).unwrap(),
+            expected
+        );
+        assert_eq!(
+            par_frames(&journal_path, ReadMode::Permissive, 4).unwrap(),
+            expected
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_reports_first_corruption() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_journal(&journal_path, all_features(), 8);
+    let original = fs::read(&journal_path).unwrap();
+
+    // Flip a payload byte near the end and one near the start.
+    let mut bytes = original.clone();
+    let len = bytes.len();
+    bytes[len - 60] ^= 0x01;
+    bytes[200] ^= 0x01;
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let expected = buffered_frames(&journal_path, ReadMode::Strict).unwrap_err();
+    assert!(matches!(expected, JournalError::ChecksumMismatch { .. }));
+    for threads in [1, 4] {
+        let err = par_frames(&journal_path, ReadMode::Strict, threads).unwrap_err();
+        assert_eq!(err.to_string(), expected.to_string());
+    }
+    let err = mmap_frames(&journal_path, ReadMode::Strict).unwrap_err();
+    assert_eq!(err.to_string(), expected.to_string());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_detects_broken_chain_in_parallel() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_journal(&journal_path, all_features(), 8);
+
+    // Drop the fourth frame.
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let mut bounds = vec![reader.position()];
+    while reader.read_frame().unwrap().is_some() {
+        bounds.push(reader.position());
+    }
+    let mut bytes = fs::read(&journal_path).unwrap();
+    bytes.drain(bounds[3] as usize..bounds[4] as usize);
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let expected = buffered_frames(&journal_path, ReadMode::Strict).unwrap_err();
+    assert!(matches!(
+        expected,
+        JournalError::ChainBroken { ordinal: 3, .. }
+    ));
+    for threads in [1, 2, 5] {
+        let err = par_frames(&journal_path, ReadMode::Strict, threads).unwrap_err();
+        assert_eq!(err.to_string(), expected.to_string());
+    }
+    let err = mmap_frames(&journal_path, ReadMode::Strict).unwrap_err();
+    assert_eq!(err.to_string(), expected.to_string());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_strict_rejects_non_canonical_payload() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+    for n in 0..2 {
+        let mut event = make_test_event(n);
+        // serde_json writes 1.0 where the canonical form has 1
+        event["amount"] = json!(1.0);
+        writer.append_event(&event).unwrap();
+    }
+    writer.finish().unwrap();
+    // Mark the v1 journal as canonical; the frame layout is unchanged
+    let mut bytes = fs::read(&journal_path).unwrap();
+    bytes[4..6].copy_from_slice(&VERSION_2.to_le_bytes());
+    bytes[6..8].copy_from_slice(&FLAG_CANONICAL_PAYLOAD.to_le_bytes());
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let expected = reader.read_event().unwrap_err();
+    assert!(matches!(expected, JournalError::NonCanonicalPayload { .. }));
+
+    let mmap = MmapJournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    let err = mmap.raw_events().next().unwrap().unwrap_err();
+    assert_eq!(err.to_string(), expected.to_string());
+    let frame = mmap.frames().next().unwrap().unwrap();
+    assert_eq!(frame.event().unwrap_err().to_string(), expected.to_string());
+
+    let mmap = MmapJournalReader::open(&journal_path, ReadMode::Permissive).unwrap();
+    assert_eq!(mmap.raw_events().count(), 2);
+    let frame = mmap.frames().next().unwrap().unwrap();
+    assert_eq!(frame.event().unwrap()["n"], 0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mmap_refuses_salvage_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_journal(&journal_path, all_features(), 2);
+
+    let err = MmapJournalReader::open(&journal_path, ReadMode::Salvage)
+        .err()
+        .unwrap();
+    assert!(matches!(err, JournalError::Io(e) if e.kind() == io::ErrorKind::Unsupported));
+}
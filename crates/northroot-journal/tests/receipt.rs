@@ -0,0 +1,75 @@
+mod common;
+
+use common::make_test_event;
+use northroot_journal::{
+    AppendReceipt, FrameKind, JournalReader, JournalWriter, MemoryStorage, ReadMode, WriteOptions,
+};
+
+fn append(
+    storage: MemoryStorage,
+    options: &WriteOptions,
+    range: std::ops::Range<usize>,
+) -> (MemoryStorage, Vec<AppendReceipt>) {
+    let mut writer = JournalWriter::with_storage(storage, options.clone()).unwrap();
+    let receipts = range
+        .map(|n| writer.append_event(&make_test_event(n)).unwrap())
+        .collect();
+    (writer.into_inner().unwrap().into_storage(), receipts)
+}
+
+#[test]
+fn test_receipts_locate_events() {
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true);
+    let (storage, receipts) = append(MemoryStorage::new(), &options, 0..3);
+
+    let mut reader = JournalReader::from_storage(storage, ReadMode::Strict).unwrap();
+    for (n, receipt) in receipts.iter().enumerate() {
+        assert_eq!(receipt.ordinal, n as u64);
+        let event = make_test_event(n);
+        assert_eq!(
+            receipt.event_id.as_ref(),
+            Some(&serde_json::from_value(event["event_id"].clone()).unwrap())
+        );
+
+        let (info, read) = reader.read_event_with_info().unwrap().unwrap();
+        assert_eq!(read, event);
+        assert_eq!(info.offset, receipt.offset);
+        assert_eq!(reader.position() - info.offset, receipt.frame_len);
+        // The receipt's chain head covers the event's own frame
+        assert_eq!(reader.chain_head(), receipt.chain_head);
+    }
+
+    for receipt in receipts.iter().rev() {
+        reader.seek_offset(receipt.offset).unwrap();
+        let by_offset = reader.read_event().unwrap().unwrap();
+        assert!(reader.seek_ordinal(receipt.ordinal).unwrap());
+        assert_eq!(reader.read_event().unwrap().unwrap(), by_offset);
+    }
+}
+
+#[test]
+fn test_ordinals_continue_after_reopen() {
+    for options in [
+        WriteOptions::default(),
+        WriteOptions::default()
+            .with_hash_chain(true)
+            .with_index(true),
+    ] {
+        let (storage, _) = append(MemoryStorage::new(), &options, 0..2);
+
+        // Frames of other kinds are not events
+        let mut writer = JournalWriter::with_storage(storage, options.clone()).unwrap();
+        writer
+            .append_raw(FrameKind::Unknown(0x7F), b"extension")
+            .unwrap();
+        let payload = serde_json::to_vec(&make_test_event(2)).unwrap();
+        writer.append_raw(FrameKind::EventJson, &payload).unwrap();
+        let storage = writer.into_inner().unwrap().into_storage();
+
+        let (_, receipts) = append(storage, &options, 3..5);
+        let ordinals: Vec<_> = receipts.iter().map(|receipt| receipt.ordinal).collect();
+        assert_eq!(ordinals, vec![3, 4]);
+    }
+}
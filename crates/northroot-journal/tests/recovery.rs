@@ -0,0 +1,208 @@
+// Note: All tests in this file use file I/O via TempDir. Skip under Miri; the
+// writer's tail check is covered by the unit tests in recovery.rs.
+
+mod common;
+
+use common::{make_test_event, write_events};
+use northroot_journal::{
+    FrameKind, JournalError, JournalReader, JournalRecovery, JournalWriter, ReadMode, WriteOptions,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn tear(path: &Path, tail: &[u8]) {
+    let mut bytes = fs::read(path).unwrap();
+    bytes.extend_from_slice(tail);
+    fs::write(path, &bytes).unwrap();
+}
+
+fn read_numbers(path: &Path) -> Vec<u64> {
+    let mut reader = JournalReader::open(path, ReadMode::Strict).unwrap();
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    numbers
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_clean_journal_needs_no_repair() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let len = write_events(&journal_path, 0..3, WriteOptions::default());
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert!(recovery.is_clean());
+    assert_eq!(recovery.valid_len(), len);
+    assert_eq!(recovery.discarded_len(), 0);
+    assert_eq!((recovery.frames(), recovery.events()), (3, 3));
+
+    recovery.truncate().unwrap();
+    assert_eq!(fs::metadata(&journal_path).unwrap().len(), len);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_writer_refuses_torn_tail_until_repaired() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let len = write_events(&journal_path, 0..3, WriteOptions::default());
+    tear(&journal_path, &[0x01, 0, 0, 0, 0x40, 0, 0, 0, b'{']);
+
+    let result = JournalWriter::open(&journal_path, WriteOptions::default());
+    assert!(matches!(result, Err(JournalError::TornTail { offset }) if offset == len));
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert!(matches!(
+        recovery.damage(),
+        Some(JournalError::TruncatedFrame { .. })
+    ));
+    assert_eq!(recovery.valid_len(), len);
+    assert_eq!(recovery.discarded_len(), 9);
+
+    let quarantine = temp_dir.path().join("test.nrj.torn");
+    assert_eq!(recovery.quarantine(&quarantine).unwrap(), 9);
+    assert_eq!(fs::read(&quarantine).unwrap()[8], b'{');
+    // The quarantine file is never overwritten
+    assert!(recovery.quarantine(&quarantine).is_err());
+
+    recovery.truncate().unwrap();
+    write_events(&journal_path, 3..5, WriteOptions::default());
+    assert_eq!(read_numbers(&journal_path), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recovery_stops_at_bad_checksum() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true);
+    write_events(&journal_path, 0..3, options.clone());
+
+    // Corrupt the last byte of the ChainHead trailer
+    let mut bytes = fs::read(&journal_path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    fs::write(&journal_path, &bytes).unwrap();
+
+    assert!(matches!(
+        JournalWriter::open(&journal_path, options.clone()),
+        Err(JournalError::TornTail { .. })
+    ));
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert!(matches!(
+        recovery.damage(),
+        Some(JournalError::ChecksumMismatch { .. })
+    ));
+    assert_eq!((recovery.frames(), recovery.events()), (3, 3));
+    recovery.truncate().unwrap();
+
+    // Without its trailer the chain is recomputed and appends continue it
+    write_events(&journal_path, 3..4, options);
+    let mut reader = JournalReader::open(&journal_path, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_truncate_refuses_changed_journal() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    write_events(&journal_path, 0..2, WriteOptions::default());
+    tear(&journal_path, &[0x01, 0, 0]);
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    tear(&journal_path, &[0]);
+    assert!(recovery.truncate().is_err());
+    assert!(recovery.quarantine(temp_dir.path().join("torn")).is_err());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_zero_filled_tail_is_torn() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let len = write_events(&journal_path, 0..3, WriteOptions::default());
+    // Space preallocated before a crash reads as empty frames of kind 0
+    tear(&journal_path, &[0; 64]);
+
+    let result = JournalWriter::open(&journal_path, WriteOptions::default());
+    assert!(matches!(result, Err(JournalError::TornTail { offset }) if offset == len));
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert!(matches!(
+        recovery.damage(),
+        Some(JournalError::InvalidFrame { offset, .. }) if *offset == len
+    ));
+    assert!(recovery.is_torn_tail());
+    assert_eq!((recovery.valid_len(), recovery.events()), (len, 3));
+
+    recovery.truncate().unwrap();
+    write_events(&journal_path, 3..4, WriteOptions::default());
+    assert_eq!(read_numbers(&journal_path), vec![0, 1, 2, 3]);
+
+    // Writers never produce such a frame
+    let mut writer = JournalWriter::open(&journal_path, WriteOptions::default()).unwrap();
+    assert!(writer.append_raw(FrameKind::Unknown(0), b"").is_err());
+    assert!(writer.append_raw(FrameKind::Unknown(0), b"x").is_ok());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_torn_v1_payload_is_torn() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let len = write_events(&journal_path, 0..2, WriteOptions::default());
+    // The frame header reached the disk, its payload did not
+    tear(&journal_path, &[0x01, 0, 0, 0, 16, 0, 0, 0]);
+    tear(&journal_path, &[0; 16]);
+
+    let result = JournalWriter::open(&journal_path, WriteOptions::default());
+    assert!(matches!(result, Err(JournalError::TornTail { offset }) if offset == len));
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert!(recovery.is_torn_tail());
+    assert_eq!((recovery.valid_len(), recovery.discarded_len()), (len, 24));
+    recovery.truncate().unwrap();
+    assert_eq!(read_numbers(&journal_path), vec![0, 1]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_truncate_refuses_mid_file_damage() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let options = WriteOptions::default().with_checksums(true);
+    let mut writer = JournalWriter::open(&journal_path, options).unwrap();
+    let receipts: Vec<_> = (0..3)
+        .map(|n| writer.append_event(&make_test_event(n)).unwrap())
+        .collect();
+    writer.finish().unwrap();
+
+    // Flip one payload byte of the middle event
+    let mut bytes = fs::read(&journal_path).unwrap();
+    bytes[receipts[1].offset as usize + 20] ^= 0x01;
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let recovery = JournalRecovery::scan(&journal_path).unwrap();
+    assert!(matches!(
+        recovery.damage(),
+        Some(JournalError::ChecksumMismatch { .. })
+    ));
+    assert!(!recovery.is_torn_tail());
+    assert_eq!(recovery.valid_len(), receipts[1].offset);
+    assert_eq!(recovery.resumes_at(), Some(receipts[2].offset));
+
+    let err = recovery.truncate().unwrap_err();
+    assert!(matches!(
+        err,
+        JournalError::DamagedMidFile { offset, resumes_at }
+            if offset == receipts[1].offset && resumes_at == receipts[2].offset
+    ));
+    assert_eq!(fs::read(&journal_path).unwrap(), bytes);
+}
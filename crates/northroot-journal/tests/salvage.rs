@@ -0,0 +1,185 @@
+mod common;
+
+use common::make_test_event;
+use northroot_journal::{
+    AppendReceipt, JournalReader, JournalWriter, ReadMode, SalvageGap, WriteOptions,
+};
+use std::cell::Cell;
+use std::io::{self, Read};
+
+fn write_journal(options: WriteOptions, count: usize) -> (Vec<u8>, Vec<AppendReceipt>) {
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    let receipts = (0..count)
+        .map(|n| writer.append_event(&make_test_event(n)).unwrap())
+        .collect();
+    (writer.into_inner().unwrap(), receipts)
+}
+
+fn salvage(bytes: &[u8]) -> (Vec<u64>, Vec<SalvageGap>) {
+    let mut reader = JournalReader::new(bytes, ReadMode::Salvage).unwrap();
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    (numbers, reader.gaps().to_vec())
+}
+
+/// The byte range of the frame described by `receipt`.
+fn span(receipt: &AppendReceipt) -> std::ops::Range<usize> {
+    receipt.offset as usize..(receipt.offset + receipt.frame_len) as usize
+}
+
+#[test]
+fn test_clean_journal_has_no_gaps() {
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+        .with_index(true);
+    let (bytes, _) = write_journal(options, 3);
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Salvage).unwrap();
+    let mut ordinals = Vec::new();
+    while let Some((info, _)) = reader.read_frame_with_info().unwrap() {
+        ordinals.push(info.ordinal);
+    }
+    // Three events, an index segment and a trailer
+    assert_eq!(ordinals, (0..5).map(Some).collect::<Vec<_>>());
+    assert!(reader.gaps().is_empty());
+}
+
+#[test]
+fn test_skips_events_with_bad_payloads() {
+    let (mut bytes, receipts) = write_journal(WriteOptions::default(), 5);
+    // Damage the payloads but leave the frame headers intact
+    let payload = span(&receipts[1]).start + 8;
+    bytes[payload + 20..payload + 30].fill(0xFF);
+    let payload = span(&receipts[3]).start + 8;
+    bytes[payload] = b'x';
+
+    let (numbers, gaps) = salvage(&bytes);
+    assert_eq!(numbers, vec![0, 2, 4]);
+    assert_eq!(
+        gaps.iter()
+            .map(|gap| (gap.offset, gap.len))
+            .collect::<Vec<_>>(),
+        vec![
+            (receipts[1].offset, receipts[1].frame_len),
+            (receipts[3].offset, receipts[3].frame_len)
+        ]
+    );
+    assert!(gaps[0].reason.contains("UTF-8"), "{}", gaps[0].reason);
+    assert!(gaps[1].reason.contains("JSON"), "{}", gaps[1].reason);
+}
+
+#[test]
+fn test_resynchronizes_after_bad_frame_header() {
+    let options = WriteOptions::default().with_checksums(true);
+    let (mut bytes, receipts) = write_journal(options, 4);
+    let damaged = span(&receipts[1]);
+    // A length field pointing far past the frame
+    bytes[damaged.start + 4..damaged.start + 8].copy_from_slice(&0x1000u32.to_le_bytes());
+
+    let (numbers, gaps) = salvage(&bytes);
+    assert_eq!(numbers, vec![0, 2, 3]);
+    assert_eq!(
+        gaps.iter()
+            .map(|gap| (gap.offset, gap.len))
+            .collect::<Vec<_>>(),
+        vec![(receipts[1].offset, receipts[1].frame_len)]
+    );
+}
+
+#[test]
+fn test_recovers_around_overwritten_region() {
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true);
+    let (mut bytes, receipts) = write_journal(options, 8);
+    // Zero a region from inside event 2 to inside event 5
+    let start = span(&receipts[2]).start + 10;
+    let end = span(&receipts[5]).start + 10;
+    bytes[start..end].fill(0);
+    // And tear off the end of the last event and the trailer
+    bytes.truncate(span(&receipts[7]).end - 5);
+
+    let (numbers, gaps) = salvage(&bytes);
+    assert_eq!(numbers, vec![0, 1, 6]);
+    assert_eq!(
+        gaps.iter()
+            .map(|gap| (gap.offset, gap.len))
+            .collect::<Vec<_>>(),
+        vec![
+            (receipts[2].offset, receipts[6].offset - receipts[2].offset),
+            (receipts[7].offset, receipts[7].frame_len - 5),
+        ]
+    );
+
+    // Permissive reads stop at the first damaged frame
+    let mut reader = JournalReader::new(bytes.as_slice(), ReadMode::Permissive).unwrap();
+    reader.read_event().unwrap();
+    reader.read_event().unwrap();
+    assert!(reader.read_event().is_err());
+}
+
+#[test]
+fn test_trailing_garbage_is_one_gap() {
+    let (mut bytes, _) = write_journal(WriteOptions::default(), 2);
+    let len = bytes.len() as u64;
+    bytes.extend_from_slice(&[0xAB; 3]);
+
+    let (numbers, gaps) = salvage(&bytes);
+    assert_eq!(numbers, vec![0, 1]);
+    assert_eq!(
+        gaps.iter()
+            .map(|gap| (gap.offset, gap.len))
+            .collect::<Vec<_>>(),
+        vec![(len, 3)]
+    );
+}
+
+/// Counts the bytes read from the wrapped slice.
+struct CountingReader<'a> {
+    bytes: &'a [u8],
+    read: &'a Cell<usize>,
+}
+
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.bytes.read(buf)?;
+        self.read.set(self.read.get() + n);
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_resync_reads_each_byte_once() {
+    let (bytes, receipts) = write_journal(WriteOptions::default(), 3);
+    // 64 KiB of frame headers claiming 15 MiB event payloads
+    let mut garbage = Vec::new();
+    for _ in 0..8 * 1024 {
+        garbage.extend_from_slice(&[0x01, 0, 0, 0]);
+        garbage.extend_from_slice(&(15u32 << 20).to_le_bytes());
+    }
+    let at = span(&receipts[1]).start;
+    let damaged = [&bytes[..at], &garbage, &bytes[at..]].concat();
+
+    let read = Cell::new(0);
+    let source = CountingReader {
+        bytes: &damaged,
+        read: &read,
+    };
+    let mut reader = JournalReader::new(source, ReadMode::Salvage).unwrap();
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    assert_eq!(numbers, vec![0, 1, 2]);
+    assert_eq!(
+        reader
+            .gaps()
+            .iter()
+            .map(|gap| (gap.offset, gap.len))
+            .collect::<Vec<_>>(),
+        vec![(at as u64, garbage.len() as u64)]
+    );
+    assert_eq!(read.get(), damaged.len());
+}
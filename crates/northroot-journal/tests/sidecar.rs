@@ -0,0 +1,290 @@
+// Note: Tests here use file I/O via TempDir. Skip under Miri.
+
+mod common;
+
+use common::event_id;
+use northroot_journal::sidecar::MAX_RUNS;
+use northroot_journal::{
+    EventJson, JournalReader, JournalWriter, ReadMode, SidecarIndex, SidecarRefresh, WriteOptions,
+};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn make_test_event(n: usize) -> EventJson {
+    // Fields that vary between events, for lookups by type, principal and time
+    let mut event = common::make_test_event(n);
+    event["event_type"] = json!(if n.is_multiple_of(2) { "even" } else { "odd" });
+    event["occurred_at"] = json!(format!("2024-01-01T00:00:{:02}Z", n));
+    event["principal_id"] = json!(format!("service:p{}", n % 3));
+    event
+}
+
+fn append_events(path: &Path, range: std::ops::Range<usize>) {
+    let mut writer = JournalWriter::open(path, WriteOptions::default()).unwrap();
+    for n in range {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+/// Reads the `n` field of the events at `offsets`.
+fn numbers(path: &Path, offsets: &[u64]) -> Vec<u64> {
+    let mut reader = JournalReader::open(path, ReadMode::Strict).unwrap();
+    offsets
+        .iter()
+        .map(|offset| {
+            reader.seek_offset(*offset).unwrap();
+            reader.read_event().unwrap().unwrap()["n"].as_u64().unwrap()
+        })
+        .collect()
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_lookups() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..6);
+
+    let mut sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+    assert_eq!(sidecar.event_count(), 6);
+    assert!(SidecarIndex::path_for(&journal_path).exists());
+
+    let offset = sidecar.find_event(&event_id(4)).unwrap().unwrap();
+    assert_eq!(numbers(&journal_path, &[offset]), [4]);
+    assert!(sidecar.find_event(&event_id(99)).unwrap().is_none());
+
+    let even = sidecar.find_event_type("even").unwrap();
+    assert_eq!(numbers(&journal_path, &even), [0, 2, 4]);
+    let p1 = sidecar.find_principal("service:p1").unwrap();
+    assert_eq!(numbers(&journal_path, &p1), [1, 4]);
+    assert!(sidecar.find_event_type("missing").unwrap().is_empty());
+
+    let range = sidecar
+        .find_occurred_between(Some("2024-01-01T00:00:01.5Z"), Some("2024-01-01T00:00:04Z"))
+        .unwrap();
+    assert_eq!(numbers(&journal_path, &range), [2, 3, 4]);
+    let open_ended = sidecar
+        .find_occurred_between(Some("2024-01-01T00:00:04Z"), None)
+        .unwrap();
+    assert_eq!(numbers(&journal_path, &open_ended), [4, 5]);
+    assert!(sidecar
+        .find_occurred_between(Some("yesterday"), None)
+        .is_err());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_updates_incrementally() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..3);
+    SidecarIndex::open(&journal_path).unwrap();
+
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Current);
+
+    append_events(&journal_path, 3..5);
+    let mut sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Updated { events: 2 });
+    assert_eq!(sidecar.event_count(), 5);
+    assert_eq!(
+        sidecar.journal_len(),
+        fs::metadata(&journal_path).unwrap().len()
+    );
+    let even = sidecar.find_event_type("even").unwrap();
+    assert_eq!(numbers(&journal_path, &even), [0, 2, 4]);
+
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Current);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_writer_updates_sidecar_on_finish() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    let options = WriteOptions::default().with_sidecar(true);
+    for range in [0..3, 3..5] {
+        let mut writer = JournalWriter::open(&journal_path, options.clone()).unwrap();
+        for n in range {
+            writer.append_event(&make_test_event(n)).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    let mut sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Current);
+    assert_eq!(sidecar.event_count(), 5);
+    let offset = sidecar.find_event(&event_id(4)).unwrap().unwrap();
+    assert_eq!(numbers(&journal_path, &[offset]), [4]);
+
+    // A writer dropped without finishing leaves the update to the next open
+    let mut writer = JournalWriter::open(&journal_path, options).unwrap();
+    writer.append_event(&make_test_event(5)).unwrap();
+    drop(writer);
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Updated { events: 1 });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_compacts_runs() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..1);
+    SidecarIndex::open(&journal_path).unwrap();
+
+    let total = MAX_RUNS as usize + 3;
+    for n in 1..total {
+        append_events(&journal_path, n..n + 1);
+        let sidecar = SidecarIndex::open(&journal_path).unwrap();
+        assert_eq!(sidecar.refresh(), SidecarRefresh::Updated { events: 1 });
+    }
+
+    let mut sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.event_count(), total as u64);
+    for n in 0..total {
+        let offset = sidecar.find_event(&event_id(n)).unwrap().unwrap();
+        assert_eq!(numbers(&journal_path, &[offset]), [n as u64]);
+    }
+    // One header plus at most MAX_RUNS small runs.
+    let size = fs::metadata(SidecarIndex::path_for(&journal_path))
+        .unwrap()
+        .len();
+    assert!(size < 96 + (MAX_RUNS as u64 + 1) * (32 + 4 * 40 * total as u64));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_rebuilds_when_stale() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..4);
+    SidecarIndex::open(&journal_path).unwrap();
+
+    // Replace the journal with a different one of the same length.
+    fs::remove_file(&journal_path).unwrap();
+    append_events(&journal_path, 10..14);
+    let mut sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+    assert!(sidecar.find_event(&event_id(0)).unwrap().is_none());
+    assert!(sidecar.find_event(&event_id(10)).unwrap().is_some());
+
+    // Truncated journal.
+    let bytes = fs::read(&journal_path).unwrap();
+    fs::write(&journal_path, &bytes[..16]).unwrap();
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+    assert_eq!(sidecar.event_count(), 0);
+
+    // Damaged sidecar.
+    append_events(&journal_path, 0..2);
+    let sidecar_path = SidecarIndex::path_for(&journal_path);
+    SidecarIndex::open(&journal_path).unwrap();
+    let sidecar_bytes = fs::read(&sidecar_path).unwrap();
+    fs::write(&sidecar_path, &sidecar_bytes[..sidecar_bytes.len() - 1]).unwrap();
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+    assert_eq!(sidecar.event_count(), 2);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_updates_across_blocks_keep_the_binding() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    // Uneven batches, so updates start and end in the middle of 64 KiB blocks
+    for range in [0..1, 1..300, 300..301, 301..1200, 1200..1500] {
+        append_events(&journal_path, range);
+        let sidecar = SidecarIndex::open(&journal_path).unwrap();
+        assert!(sidecar.verify(&journal_path).unwrap());
+    }
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Current);
+    assert_eq!(sidecar.event_count(), 1500);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_verify_detects_rewrite_in_the_middle() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..2000);
+    let mut sidecar = SidecarIndex::open(&journal_path).unwrap();
+    let mut offset = |n| sidecar.find_event(&event_id(n)).unwrap().unwrap() as usize;
+    let (first, second, third) = (offset(1000), offset(1001), offset(1002));
+
+    // Swap two events of the same length well inside the journal, so
+    // neither its length nor its first and last 64 KiB change
+    let mut bytes = fs::read(&journal_path).unwrap();
+    let len = second - first;
+    let (head, tail) = bytes.split_at_mut(third);
+    head[first..second].swap_with_slice(&mut tail[..len]);
+    fs::write(&journal_path, &bytes).unwrap();
+
+    // Opening only checks the last block, so the swap goes unnoticed until a
+    // full verify
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Current);
+    assert!(!sidecar.verify(&journal_path).unwrap());
+
+    let mut sidecar = SidecarIndex::rebuild(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+    assert!(sidecar.verify(&journal_path).unwrap());
+    let offset = sidecar.find_event(&event_id(1000)).unwrap().unwrap();
+    assert_eq!(offset as usize, third);
+    assert_eq!(numbers(&journal_path, &[offset]), [1000]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_rebuilds_when_counts_overflow() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..2);
+    let sidecar_path = SidecarIndex::path_for(&journal_path);
+    SidecarIndex::open(&journal_path).unwrap();
+    let original = fs::read(&sidecar_path).unwrap();
+
+    // Counts of the first run, right after the 96-byte header
+    for counts in [[u64::MAX, 0, 0, 0], [u64::MAX / 40, u64::MAX / 40, 0, 0]] {
+        let mut bytes = original.clone();
+        for (i, count) in counts.iter().enumerate() {
+            bytes[96 + i * 8..104 + i * 8].copy_from_slice(&count.to_le_bytes());
+        }
+        fs::write(&sidecar_path, &bytes).unwrap();
+        let sidecar = SidecarIndex::open(&journal_path).unwrap();
+        assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+        assert_eq!(sidecar.event_count(), 2);
+    }
+
+    // A run count no compacted sidecar can have
+    let mut bytes = original;
+    bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&sidecar_path, &bytes).unwrap();
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Rebuilt);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sidecar_ignores_torn_tail() {
+    let temp_dir = TempDir::new().unwrap();
+    let journal_path = temp_dir.path().join("test.nrj");
+    append_events(&journal_path, 0..2);
+    let complete_len = fs::metadata(&journal_path).unwrap().len();
+
+    let mut bytes = fs::read(&journal_path).unwrap();
+    bytes.extend_from_slice(&[0x01, 0, 0, 0, 100, 0, 0, 0, b'{']);
+    fs::write(&journal_path, &bytes).unwrap();
+
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.event_count(), 2);
+    assert_eq!(sidecar.journal_len(), complete_len);
+    let sidecar = SidecarIndex::open(&journal_path).unwrap();
+    assert_eq!(sidecar.refresh(), SidecarRefresh::Current);
+}
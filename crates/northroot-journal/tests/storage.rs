@@ -0,0 +1,203 @@
+// Fault injection against in-memory storage: short writes, a full disk,
+// failing syncs and torn writes, all deterministic and without file I/O.
+
+mod common;
+
+use common::make_test_event;
+use northroot_journal::{
+    Durability, Faults, FaultyStorage, FrameKind, JournalError, JournalReader, JournalStorage,
+    JournalWriter, MemoryStorage, ReadMode, WriteOptions,
+};
+use std::io;
+
+fn v2_options() -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+}
+
+fn write_events<S: JournalStorage>(storage: S, count: usize, options: WriteOptions) -> S {
+    let mut writer = JournalWriter::with_storage(storage, options).unwrap();
+    for n in 0..count {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    writer.into_inner().unwrap().into_storage()
+}
+
+fn read_numbers(bytes: &[u8], mode: ReadMode) -> Vec<u64> {
+    let storage = MemoryStorage::from_bytes(bytes.to_vec());
+    let mut reader = JournalReader::from_storage(storage, mode).unwrap();
+    let mut numbers = Vec::new();
+    while let Some(event) = reader.read_event().unwrap() {
+        numbers.push(event["n"].as_u64().unwrap());
+    }
+    numbers
+}
+
+fn io_kind(err: &JournalError) -> Option<io::ErrorKind> {
+    match err {
+        JournalError::Io(e) => Some(e.kind()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_short_writes_produce_identical_journal() {
+    let expected = write_events(MemoryStorage::new(), 3, v2_options()).into_bytes();
+
+    for max_write in [1, 3, 7, 64] {
+        let faults = Faults {
+            max_write: Some(max_write),
+            ..Default::default()
+        };
+        let storage = FaultyStorage::new(MemoryStorage::new(), faults);
+        let storage = write_events(storage, 3, v2_options());
+        assert_eq!(storage.into_inner().into_bytes(), expected);
+    }
+}
+
+#[test]
+fn test_full_disk_rolls_back_append() {
+    let one_event = write_events(MemoryStorage::new(), 1, WriteOptions::default()).into_bytes();
+    let faults = Faults {
+        capacity: Some(one_event.len() as u64 + 10),
+        ..Default::default()
+    };
+    let storage = FaultyStorage::new(MemoryStorage::new(), faults);
+    let mut writer = JournalWriter::with_storage(storage, WriteOptions::default()).unwrap();
+    writer.append_event(&make_test_event(0)).unwrap();
+
+    // The second frame only partly fits; the partial frame is removed again
+    let err = writer.append_event(&make_test_event(1)).unwrap_err();
+    assert_eq!(io_kind(&err), Some(io::ErrorKind::StorageFull));
+    let stream = writer.into_inner().unwrap();
+    assert_eq!(stream.storage().inner().as_bytes(), one_event.as_slice());
+
+    // Once space is freed, appends continue where the journal left off
+    let mut storage = stream.into_storage();
+    storage.faults_mut().capacity = None;
+    let storage = write_events(storage, 2, WriteOptions::default());
+    let bytes = storage.into_inner().into_bytes();
+    assert_eq!(read_numbers(&bytes, ReadMode::Strict), vec![0, 0, 1]);
+}
+
+#[test]
+fn test_failed_sync_rolls_back_append() {
+    let options = v2_options().with_durability(Durability::Data);
+    let storage = write_events(MemoryStorage::new(), 1, options.clone());
+    let synced = storage.as_bytes().to_vec();
+
+    let faults = Faults {
+        fail_sync: true,
+        ..Default::default()
+    };
+    let mut storage = FaultyStorage::new(storage, faults);
+    let mut writer = JournalWriter::with_storage(&mut storage, options.clone()).unwrap();
+    let err = writer.append_event(&make_test_event(1)).unwrap_err();
+    assert_eq!(io_kind(&err), Some(io::ErrorKind::Other));
+    writer.finish().unwrap();
+    // Nothing was appended, not even a trailer
+    assert_eq!(storage.inner().as_bytes(), synced.as_slice());
+
+    storage.faults_mut().fail_sync = false;
+    let storage = write_events(storage.into_inner(), 2, options);
+    let bytes = storage.into_bytes();
+    assert_eq!(read_numbers(&bytes, ReadMode::Strict), vec![0, 0, 1]);
+    let storage = MemoryStorage::from_bytes(bytes);
+    let mut reader = JournalReader::from_storage(storage, ReadMode::Strict).unwrap();
+    reader.verify_chain().unwrap();
+}
+
+#[test]
+fn test_group_durability_loses_only_unsynced_frames() {
+    let options = WriteOptions::default().with_durability(Durability::Group {
+        every_n: 2,
+        every_ms: 0,
+    });
+    let mut storage = MemoryStorage::new();
+    let mut writer = JournalWriter::with_storage(&mut storage, options).unwrap();
+    for n in 0..4 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    drop(writer);
+
+    // The header is synced with the first event, then events go in pairs;
+    // a crash loses the last, unsynced event
+    assert_eq!(storage.syncs(), 2);
+    storage.crash();
+    assert_eq!(
+        read_numbers(storage.as_bytes(), ReadMode::Strict),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_sync_means_full_durability() {
+    let mut options = WriteOptions::default();
+    options.sync = true;
+    let mut storage = MemoryStorage::new();
+    let mut writer = JournalWriter::with_storage(&mut storage, options).unwrap();
+    for n in 0..3 {
+        writer.append_event(&make_test_event(n)).unwrap();
+    }
+    drop(writer);
+
+    // Every append is synced, so a crash loses nothing
+    assert_eq!(storage.synced_len(), storage.as_bytes().len() as u64);
+    storage.crash();
+    assert_eq!(
+        read_numbers(storage.as_bytes(), ReadMode::Strict),
+        vec![0, 1, 2]
+    );
+}
+
+#[test]
+fn test_torn_write_at_every_byte() {
+    let options = v2_options().with_index(true);
+    let complete = write_events(MemoryStorage::new(), 3, options.clone()).into_bytes();
+    let storage = MemoryStorage::from_bytes(complete.clone());
+    let mut reader = JournalReader::from_storage(storage, ReadMode::Strict).unwrap();
+    let mut boundaries = vec![reader.position()];
+    let mut event_ends = Vec::new();
+    while let Some((info, _)) = reader.read_frame_with_info().unwrap() {
+        boundaries.push(reader.position());
+        if info.kind() == FrameKind::EventJson {
+            event_ends.push(reader.position());
+        }
+    }
+
+    for crash_at in boundaries[0]..complete.len() as u64 {
+        let faults = Faults {
+            crash_at: Some(crash_at),
+            ..Default::default()
+        };
+        let mut storage = FaultyStorage::new(MemoryStorage::new(), faults);
+        let mut writer = JournalWriter::with_storage(&mut storage, options.clone()).unwrap();
+        let result = (0..3).try_for_each(|n| writer.append_event(&make_test_event(n)).map(drop));
+        assert!(result.and_then(|()| writer.finish()).is_err());
+        assert!(storage.has_crashed());
+
+        // What reached storage is a prefix of the complete journal
+        let bytes = storage.into_inner().into_bytes();
+        assert_eq!(bytes.as_slice(), &complete[..crash_at as usize]);
+        let survivors = event_ends.iter().filter(|&&end| end <= crash_at).count() as u64;
+        assert_eq!(
+            read_numbers(&bytes, ReadMode::Permissive),
+            (0..survivors).collect::<Vec<_>>(),
+            "crash at {}",
+            crash_at
+        );
+
+        // A writer refuses the torn tail unless the crash hit a frame boundary
+        let torn = !boundaries.contains(&crash_at);
+        let reopened =
+            JournalWriter::with_storage(MemoryStorage::from_bytes(bytes), options.clone());
+        assert_eq!(
+            matches!(reopened, Err(JournalError::TornTail { .. })),
+            torn,
+            "crash at {}",
+            crash_at
+        );
+    }
+}
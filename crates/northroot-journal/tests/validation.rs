@@ -0,0 +1,116 @@
+use northroot_canonical::{compute_event_id, Canonicalizer, ProfileId};
+use northroot_journal::{
+    EventJson, FrameKind, JournalError, JournalHeader, JournalWriter, MemoryStorage, Rejection,
+    ValidationPolicy, WriteOptions,
+};
+use serde_json::json;
+
+fn make_event(occurred_at: &str) -> EventJson {
+    let mut event = json!({
+        "event_type": "test",
+        "event_version": "1",
+        "occurred_at": occurred_at,
+        "principal_id": "service:test",
+        "canonical_profile_id": "northroot-canonical-v1"
+    });
+    let canonicalizer = Canonicalizer::new(ProfileId::parse("northroot-canonical-v1").unwrap());
+    let event_id = compute_event_id(&event, &canonicalizer).unwrap();
+    event["event_id"] = serde_json::to_value(event_id).unwrap();
+    event
+}
+
+fn strict_options() -> WriteOptions {
+    WriteOptions::default().with_validation(ValidationPolicy::strict())
+}
+
+fn rejection<T: std::fmt::Debug>(result: Result<T, JournalError>) -> Rejection {
+    match result {
+        Err(JournalError::Rejected(rejection)) => rejection,
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_rejected_events_write_nothing() {
+    let mut writer = JournalWriter::new(Vec::new(), strict_options()).unwrap();
+
+    assert_eq!(
+        rejection(writer.append_event(&json!("not an event"))),
+        Rejection::NotAnObject
+    );
+    let mut event = make_event("2024-01-01T00:00:00Z");
+    event.as_object_mut().unwrap().remove("event_id");
+    assert_eq!(
+        rejection(writer.append_event(&event)),
+        Rejection::MissingField("event_id")
+    );
+    let mut event = make_event("2024-01-01T00:00:00Z");
+    event["principal_id"] = json!("service:someone-else");
+    assert!(matches!(
+        rejection(writer.append_event(&event)),
+        Rejection::EventIdMismatch { .. }
+    ));
+
+    assert_eq!(writer.position(), JournalHeader::HEADER_SIZE as u64);
+    let bytes = writer.into_inner().unwrap();
+    assert_eq!(bytes.len(), JournalHeader::HEADER_SIZE);
+}
+
+#[test]
+fn test_unvalidated_writer_accepts_anything() {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    writer.append_event(&json!([1, 2, 3])).unwrap();
+    writer.append_event(&json!({"event_type": "test"})).unwrap();
+}
+
+#[test]
+fn test_raw_event_frames_are_validated() {
+    let mut writer = JournalWriter::new(Vec::new(), strict_options()).unwrap();
+    let event = make_event("2024-01-01T00:00:00Z");
+    writer
+        .append_raw(FrameKind::EventJson, &serde_json::to_vec(&event).unwrap())
+        .unwrap();
+
+    let result = writer.append_raw(FrameKind::EventJson, b"{\"event_type\": \"test\"}");
+    assert!(matches!(rejection(result), Rejection::MissingField(_)));
+    let result = writer.append_raw(FrameKind::EventJson, b"not json");
+    assert!(matches!(result, Err(JournalError::JsonParse(_))));
+}
+
+#[test]
+fn test_monotonic_time_survives_resume() {
+    // With an index segment the last event's time comes from its entry
+    for index in [false, true] {
+        let options = WriteOptions::default()
+            .with_index(index)
+            .with_validation(ValidationPolicy {
+                monotonic_time: true,
+                ..Default::default()
+            });
+        let mut writer =
+            JournalWriter::with_storage(MemoryStorage::new(), options.clone()).unwrap();
+        writer
+            .append_event(&make_event("2024-01-01T00:00:00Z"))
+            .unwrap();
+        writer
+            .append_event(&make_event("2024-01-01T00:00:00Z"))
+            .unwrap();
+        writer
+            .append_event(&make_event("2024-01-02T00:00:00Z"))
+            .unwrap();
+        let storage = writer.into_inner().unwrap().into_storage();
+
+        // The resumed writer knows the last event's time
+        let mut writer = JournalWriter::with_storage(storage, options).unwrap();
+        assert_eq!(
+            rejection(writer.append_event(&make_event("2024-01-01T12:00:00Z"))),
+            Rejection::OutOfOrder {
+                occurred_at: "2024-01-01T12:00:00Z".to_string(),
+                previous: "2024-01-02T00:00:00Z".to_string(),
+            }
+        );
+        writer
+            .append_event(&make_event("2024-01-02T00:00:00.001Z"))
+            .unwrap();
+    }
+}
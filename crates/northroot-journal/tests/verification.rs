@@ -1,6 +1,12 @@
-use northroot_canonical::{compute_event_id, Canonicalizer, ProfileId};
-use northroot_journal::verify_event_id;
+use northroot_canonical::{compute_event_id, Canonicalizer, HygieneStatus, ProfileId};
+use northroot_journal::{
+    verify_event_id, verify_incremental, verify_incremental_with, verify_journal,
+    verify_journal_with, verify_reader, verify_reader_with, FrameKind, FrameOutcome, FrameReport,
+    JournalError, JournalReader, JournalWriter, ReadMode, VerificationReport, VerificationTotals,
+    VerifiedPrefix, VerifyOptions, WriteOptions,
+};
 use serde_json::json;
+use tempfile::TempDir;
 
 fn make_canonicalizer() -> Canonicalizer {
     Canonicalizer::new(ProfileId::parse("northroot-canonical-v1").unwrap())
@@ -48,3 +54,388 @@ fn test_verify_event_id_invalid() {
     let valid = verify_event_id(&event, &canonicalizer).unwrap();
     assert!(!valid);
 }
+
+fn make_numbered_event(n: u64) -> serde_json::Value {
+    let mut event = make_test_event();
+    event["n"] = json!(n.to_string());
+    event.as_object_mut().unwrap().remove("event_id");
+    let event_id = compute_event_id(&event, &make_canonicalizer()).unwrap();
+    event["event_id"] = serde_json::to_value(event_id).unwrap();
+    event
+}
+
+fn verify_bytes(bytes: &[u8]) -> VerificationReport {
+    let mut reader = JournalReader::new(bytes, ReadMode::Strict).unwrap();
+    verify_reader(&mut reader, &VerifyOptions::default()).unwrap()
+}
+
+/// Verifies `bytes` and also returns every frame's result, as streamed.
+fn verify_frames(bytes: &[u8], options: &VerifyOptions) -> (VerificationReport, Vec<FrameReport>) {
+    let mut reader = JournalReader::new(bytes, ReadMode::Strict).unwrap();
+    let mut frames = Vec::new();
+    let report =
+        verify_reader_with(&mut reader, options, |frame| frames.push(frame.clone())).unwrap();
+    (report, frames)
+}
+
+#[test]
+fn test_report_counts_outcomes() {
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true);
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    writer.append_event(&make_numbered_event(0)).unwrap();
+    let mut tampered = make_numbered_event(1);
+    tampered["n"] = json!("tampered");
+    writer.append_event(&tampered).unwrap();
+    writer.append_event(&json!({"event_type": "test"})).unwrap();
+    writer.append_raw(FrameKind::Unknown(0x7F), b"ext").unwrap();
+    let duplicate = writer.append_event(&make_numbered_event(0)).unwrap();
+    let bytes = writer.into_inner().unwrap();
+
+    let (report, frames) = verify_frames(&bytes, &VerifyOptions::default());
+    let outcomes: Vec<_> = frames.iter().map(|frame| frame.outcome).collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            FrameOutcome::Valid,
+            FrameOutcome::Mismatch,
+            FrameOutcome::Invalid,
+            FrameOutcome::UnknownKind,
+            FrameOutcome::Valid,
+            // The ChainHead trailer
+            FrameOutcome::Valid,
+        ]
+    );
+    assert_eq!(
+        report.totals,
+        VerificationTotals {
+            frames: 6,
+            events: 4,
+            valid: 3,
+            mismatch: 1,
+            invalid: 1,
+            unknown_kind: 1,
+            damaged: 0,
+        }
+    );
+    assert!(report.complete);
+    assert!(!report.passed());
+    assert!(report.chain_head.is_some());
+    assert_eq!(report.unknown_frame_kinds, vec![0x7F]);
+    assert_eq!(report.duplicate_event_ids.len(), 1);
+    assert_eq!(
+        report.duplicate_event_ids[0].offsets,
+        vec![frames[0].offset, duplicate.offset]
+    );
+    let hygiene = frames[0].hygiene.as_ref().unwrap();
+    assert_eq!(hygiene.status, HygieneStatus::Ok);
+    // Only the failing frames are kept in the report
+    assert_eq!(report.failures, vec![frames[1].clone(), frames[2].clone()]);
+}
+
+#[test]
+fn test_reject_duplicates_fails_repeated_events() {
+    let mut writer = JournalWriter::new(Vec::new(), WriteOptions::default()).unwrap();
+    let first = writer.append_event(&make_numbered_event(0)).unwrap();
+    writer.append_event(&make_numbered_event(1)).unwrap();
+    writer.append_event(&make_numbered_event(0)).unwrap();
+    let bytes = writer.into_inner().unwrap();
+
+    // Repeats are only a warning by default, and not looked for at all when
+    // tracking every event_id would cost too much memory
+    assert!(verify_bytes(&bytes).passed());
+    let options = VerifyOptions {
+        detect_duplicates: false,
+        ..Default::default()
+    };
+    assert!(verify_frames(&bytes, &options)
+        .0
+        .duplicate_event_ids
+        .is_empty());
+
+    let options = VerifyOptions {
+        reject_duplicates: true,
+        ..Default::default()
+    };
+    let (report, frames) = verify_frames(&bytes, &options);
+    assert!(!report.passed());
+    assert_eq!((report.totals.valid, report.totals.invalid), (2, 1));
+    assert_eq!(report.failures, vec![frames[2].clone()]);
+    assert_eq!(frames[2].outcome, FrameOutcome::Invalid);
+    assert_eq!(
+        frames[2].error.as_deref(),
+        Some(format!("duplicate event_id (first at offset {})", first.offset).as_str())
+    );
+    assert_eq!(report.duplicate_event_ids.len(), 1);
+}
+
+#[test]
+fn test_report_records_damage_and_truncation() {
+    let options = WriteOptions::default().with_checksums(true);
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    let receipts: Vec<_> = (0..3)
+        .map(|n| writer.append_event(&make_numbered_event(n)).unwrap())
+        .collect();
+    let bytes = writer.into_inner().unwrap();
+
+    let report = verify_bytes(&bytes[..bytes.len() - 3]);
+    assert_eq!(report.truncated_at, Some(receipts[2].offset));
+    assert_eq!(report.totals.valid, 2);
+    assert!(!report.complete && !report.passed());
+
+    let mut damaged = bytes.clone();
+    damaged[receipts[1].offset as usize + 20] ^= 0xFF;
+    let report = verify_bytes(&damaged);
+    let last = report.failures.last().unwrap();
+    assert_eq!(
+        (last.offset, last.outcome, last.kind),
+        (receipts[1].offset, FrameOutcome::Damaged, None)
+    );
+    assert_eq!(report.totals.frames, 2);
+    assert!(!report.complete);
+
+    let report = verify_bytes(&bytes);
+    assert!(report.complete && report.passed());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_parallel_report_matches_sequential() {
+    let options = WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+        .with_index(true);
+    let mut writer = JournalWriter::new(Vec::new(), options).unwrap();
+    let mut receipts = Vec::new();
+    // Enough frames for several windows of work per thread
+    for n in 0..400 {
+        match n % 47 {
+            13 => {
+                let mut tampered = make_numbered_event(n);
+                tampered["n"] = json!("tampered");
+                receipts.push(writer.append_event(&tampered).unwrap());
+            }
+            29 => {
+                receipts.push(writer.append_event(&json!({"event_type": "test"})).unwrap());
+            }
+            41 => {
+                writer.append_raw(FrameKind::Unknown(0x7F), b"ext").unwrap();
+            }
+            // Repeats the first event
+            37 => receipts.push(writer.append_event(&make_numbered_event(0)).unwrap()),
+            _ => receipts.push(writer.append_event(&make_numbered_event(n)).unwrap()),
+        }
+    }
+    let bytes = writer.into_inner().unwrap();
+
+    let mut truncated = bytes.clone();
+    truncated.truncate(receipts[300].offset as usize + 5);
+    let mut damaged = bytes.clone();
+    damaged[receipts[200].offset as usize + 20] ^= 0xFF;
+
+    for journal in [&bytes, &truncated, &damaged] {
+        for max_events in [None, Some(250)] {
+            let verify = |threads| {
+                let options = VerifyOptions {
+                    max_events,
+                    threads,
+                    ..Default::default()
+                };
+                verify_frames(journal, &options)
+            };
+            let sequential = verify(1);
+            let report = &sequential.0;
+            assert!(report.totals.mismatch > 0 && !report.duplicate_event_ids.is_empty());
+            for threads in [0, 3, 8] {
+                assert_eq!(verify(threads), sequential, "threads = {}", threads);
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_verify_journal_serializes_stable_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    let mut writer = JournalWriter::open(&path, WriteOptions::default()).unwrap();
+    for n in 0..3 {
+        writer.append_event(&make_numbered_event(n)).unwrap();
+    }
+    writer.finish().unwrap();
+
+    let options = VerifyOptions {
+        max_events: Some(2),
+        ..Default::default()
+    };
+    let mut frames = Vec::new();
+    let report = verify_journal_with(&path, &options, |frame| frames.push(frame.clone())).unwrap();
+    assert_eq!(report, verify_journal(&path, &options).unwrap());
+    assert_eq!(report.totals.events, 2);
+    assert!(!report.complete && report.passed());
+
+    // Fields keep their declaration order
+    let text = serde_json::to_string(&report).unwrap();
+    assert!(text.starts_with(
+        "{\"version\":1,\"flags\":0,\"complete\":false,\"truncated_at\":null,\"chain_head\":null,\"totals\":{\"frames\":2,"
+    ), "{}", text);
+    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(json["failures"], json!([]));
+    let json = serde_json::to_value(&frames[1]).unwrap();
+    assert_eq!(json["offset"], frames[1].offset);
+    assert_eq!(json["outcome"], "valid");
+    assert_eq!(json["kind"], 1);
+}
+
+fn chained_options() -> WriteOptions {
+    WriteOptions::default()
+        .with_checksums(true)
+        .with_hash_chain(true)
+}
+
+fn append_numbered(path: &std::path::Path, range: std::ops::Range<u64>) {
+    let mut writer = JournalWriter::open(path, chained_options()).unwrap();
+    for n in range {
+        writer.append_event(&make_numbered_event(n)).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_incremental_verifies_only_new_frames() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    let state = VerifiedPrefix::path_for(&path);
+    let options = VerifyOptions::default();
+    append_numbered(&path, 0..3);
+
+    let (report, prefix) = verify_incremental(&path, None, &options).unwrap();
+    assert_eq!(report.totals.events, 3);
+    let prefix = prefix.unwrap();
+    assert_eq!(prefix.len, std::fs::metadata(&path).unwrap().len());
+    prefix.save(&state).unwrap();
+    assert_eq!(VerifiedPrefix::load(&state).unwrap(), Some(prefix.clone()));
+
+    append_numbered(&path, 3..5);
+    let mut frames = Vec::new();
+    let (report, extended) = verify_incremental_with(&path, Some(&prefix), &options, |frame| {
+        frames.push(frame.clone())
+    })
+    .unwrap();
+    // Two events and the new trailer, numbered after the prefix
+    assert_eq!(report.totals.frames, 3);
+    assert_eq!(report.totals.events, 2);
+    assert_eq!(frames[0].ordinal, prefix.frames);
+    assert_eq!(frames[0].offset, prefix.len);
+    assert!(report.complete && report.passed());
+    let full = verify_journal(&path, &options).unwrap();
+    assert_eq!(report.chain_head, full.chain_head);
+
+    // The extended prefix is the one a fresh run produces
+    let extended = extended.unwrap();
+    let (_, fresh) = verify_incremental(&path, None, &options).unwrap();
+    assert_eq!(Some(&extended), fresh.as_ref());
+    assert_eq!(extended.chain_head, full.chain_head);
+
+    let (report, unchanged) = verify_incremental(&path, Some(&extended), &options).unwrap();
+    assert_eq!(report.totals.frames, 0);
+    assert!(report.complete);
+    assert_eq!(unchanged, Some(extended));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_incremental_fails_when_prefix_changed() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    let options = VerifyOptions::default();
+    append_numbered(&path, 0..3);
+    let (_, prefix) = verify_incremental(&path, None, &options).unwrap();
+    let prefix = prefix.unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+
+    let mut rewritten = bytes.clone();
+    rewritten[prefix.len as usize / 2] ^= 0x01;
+    std::fs::write(&path, &rewritten).unwrap();
+    let err = verify_incremental(&path, Some(&prefix), &options).unwrap_err();
+    assert!(
+        matches!(err, JournalError::PrefixChanged { len } if len == prefix.len),
+        "{:?}",
+        err
+    );
+
+    std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    let err = verify_incremental(&path, Some(&prefix), &options).unwrap_err();
+    assert!(
+        matches!(err, JournalError::PrefixChanged { .. }),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_incremental_prefix_stops_before_bad_frames() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    let options = VerifyOptions::default();
+    append_numbered(&path, 0..2);
+    let (_, prefix) = verify_incremental(&path, None, &options).unwrap();
+    let prefix = prefix.unwrap();
+
+    // A partial frame at the end is left for the next run
+    append_numbered(&path, 2..4);
+    let len = std::fs::metadata(&path).unwrap().len();
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(len - 3).unwrap();
+    let (report, extended) = verify_incremental(&path, Some(&prefix), &options).unwrap();
+    assert_eq!(report.totals.events, 2);
+    let extended = extended.unwrap();
+    assert_eq!(Some(extended.len), report.truncated_at);
+
+    // A mismatched event keeps the previous prefix
+    file.set_len(extended.len).unwrap();
+    let mut writer = JournalWriter::open(&path, chained_options()).unwrap();
+    let mut tampered = make_numbered_event(4);
+    tampered["n"] = json!("tampered");
+    writer.append_event(&tampered).unwrap();
+    writer.finish().unwrap();
+    let (report, unchanged) = verify_incremental(&path, Some(&extended), &options).unwrap();
+    assert_eq!(report.totals.mismatch, 1);
+    assert_eq!(unchanged, None);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_incremental_locks_out_writers() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    append_numbered(&path, 0..2);
+
+    let writer = JournalWriter::open(&path, chained_options()).unwrap();
+    let err = verify_incremental(&path, None, &VerifyOptions::default()).unwrap_err();
+    assert!(matches!(err, JournalError::Locked), "{:?}", err);
+    drop(writer);
+
+    let (report, _) = verify_incremental(&path, None, &VerifyOptions::default()).unwrap();
+    assert_eq!(report.totals.events, 2);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_incremental_misses_duplicates_of_the_prefix() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.nrj");
+    let options = VerifyOptions::default();
+    append_numbered(&path, 0..2);
+    let (_, prefix) = verify_incremental(&path, None, &options).unwrap();
+
+    // Documented limitation: only the whole-journal check sees the repeat
+    append_numbered(&path, 0..1);
+    let (report, _) = verify_incremental(&path, prefix.as_ref(), &options).unwrap();
+    assert!(report.duplicate_event_ids.is_empty());
+    let full = verify_journal(&path, &options).unwrap();
+    assert_eq!(full.duplicate_event_ids.len(), 1);
+}
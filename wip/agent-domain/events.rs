@@ -1,5 +1,5 @@
 use northroot_canonical::{
-    ContentRef, Digest, HygieneReport, PrincipalId, ProfileId, Timestamp, ToolName,
+    ContentRef, Digest, HygieneReport, PrincipalId, ProfileId, Timestamp, ToolName, ToolPattern,
 };
 use serde::{Deserialize, Serialize};
 
@@ -66,8 +66,8 @@ pub struct GrantBounds {
     /// Optional expiration timestamp.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<Timestamp>,
-    /// Allowed tool names or prefixes.
-    pub allowed_tools: Vec<String>,
+    /// Allowed tool patterns; see [`ToolPattern`] for matching semantics.
+    pub allowed_tools: Vec<ToolPattern>,
     /// Meter capacity caps.
     pub meter_caps: Vec<Meter>,
     /// Optional rate limits.